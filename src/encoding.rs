@@ -0,0 +1,126 @@
+use crate::result::JankenError;
+use base64::Engine;
+
+/// How binary (BLOB/BYTEA) values are represented in JSON
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlobEncoding {
+    /// Array of byte numbers, e.g. `[1, 2, 255]`
+    #[default]
+    Array,
+    /// Standard base64 string
+    Base64,
+    /// Lowercase hexadecimal string
+    Hex,
+}
+
+/// Encode raw bytes as a JSON value using the given blob encoding
+pub fn encode_blob(bytes: &[u8], encoding: BlobEncoding) -> serde_json::Value {
+    match encoding {
+        BlobEncoding::Array => serde_json::Value::Array(
+            bytes
+                .iter()
+                .map(|&byte| serde_json::Value::Number(byte.into()))
+                .collect(),
+        ),
+        BlobEncoding::Base64 => {
+            serde_json::Value::String(base64::engine::general_purpose::STANDARD.encode(bytes))
+        }
+        BlobEncoding::Hex => serde_json::Value::String(encode_hex(bytes)),
+    }
+}
+
+/// Encode bytes as a lowercase hexadecimal string
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+impl std::str::FromStr for BlobEncoding {
+    type Err = JankenError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "array" => Ok(BlobEncoding::Array),
+            "base64" => Ok(BlobEncoding::Base64),
+            "hex" => Ok(BlobEncoding::Hex),
+            _ => Err(JankenError::new_parameter_type_mismatch(
+                "blob encoding array, base64 or hex",
+                s,
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for BlobEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            BlobEncoding::Array => "array",
+            BlobEncoding::Base64 => "base64",
+            BlobEncoding::Hex => "hex",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Get the encoding of an encoded blob parameter value: `{"base64": "..."}` or `{"hex": "..."}`
+/// Returns None for any other value, including plain byte arrays
+pub fn encoded_blob_encoding(value: &serde_json::Value) -> Option<BlobEncoding> {
+    let obj = value.as_object()?;
+    if obj.len() != 1 {
+        return None;
+    }
+    let (key, text) = obj.iter().next().expect("object has exactly one entry");
+    if !text.is_string() {
+        return None;
+    }
+    match key.as_str() {
+        "base64" => Some(BlobEncoding::Base64),
+        "hex" => Some(BlobEncoding::Hex),
+        _ => None,
+    }
+}
+
+/// Decode an encoded blob parameter value (`{"base64": "..."}` or `{"hex": "..."}`) to raw bytes
+/// Returns None when the value is not an encoded blob or its text is not valid for the encoding
+pub fn decode_encoded_blob(value: &serde_json::Value) -> Option<Vec<u8>> {
+    let encoding = encoded_blob_encoding(value)?;
+    let text = value.get(encoding.to_string())?.as_str()?;
+    match encoding {
+        BlobEncoding::Base64 => base64::engine::general_purpose::STANDARD.decode(text).ok(),
+        BlobEncoding::Hex => decode_hex(text),
+        BlobEncoding::Array => None,
+    }
+}
+
+/// Decode a hexadecimal string (either case) to bytes
+pub fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_decode_encoded_blob() {
+        assert_eq!(
+            decode_encoded_blob(&json!({"base64": "AQL/"})),
+            Some(vec![1, 2, 255])
+        );
+        assert_eq!(
+            decode_encoded_blob(&json!({"hex": "0102fF"})),
+            Some(vec![1, 2, 255])
+        );
+        assert_eq!(decode_encoded_blob(&json!({"hex": "abc"})), None);
+        assert_eq!(decode_encoded_blob(&json!({"hex": "zz"})), None);
+        assert_eq!(decode_encoded_blob(&json!({"base64": "@@"})), None);
+        assert_eq!(decode_encoded_blob(&json!({"array": "0102"})), None);
+        assert_eq!(decode_encoded_blob(&json!([1, 2])), None);
+    }
+}
@@ -0,0 +1,174 @@
+use crate::encoding::BlobEncoding;
+use std::io::Write;
+
+/// Output format for exported query results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    /// Newline-delimited JSON: one JSON object per row
+    #[default]
+    Ndjson,
+    /// Comma-separated values with a header row in `returns` order
+    Csv,
+}
+
+/// How SQL NULL values are written
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum NullEncoding {
+    /// Empty CSV field, JSON `null` in NDJSON
+    #[default]
+    Empty,
+    /// The given text in CSV, the same text as a JSON string in NDJSON
+    Text(String),
+    /// Empty CSV field, key left out of the NDJSON object
+    Omit,
+}
+
+/// Options controlling how query results are exported
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    pub blob_encoding: BlobEncoding,
+    pub null_encoding: NullEncoding,
+}
+
+/// Writes mapped result rows to any `std::io::Write` in the configured export format
+/// Fields are always written in the order of `fields` (the resolved `returns` of the query)
+pub struct ResultWriter<W: Write> {
+    writer: W,
+    fields: Vec<String>,
+    options: ExportOptions,
+    rows_written: u64,
+}
+
+impl<W: Write> ResultWriter<W> {
+    /// Create a writer; for CSV the header row is written immediately
+    pub fn new(mut writer: W, fields: Vec<String>, options: ExportOptions) -> anyhow::Result<Self> {
+        if options.format == ExportFormat::Csv {
+            let header: Vec<String> = fields.iter().map(|field| csv_escape(field)).collect();
+            writeln!(writer, "{}", header.join(","))?;
+        }
+        Ok(ResultWriter {
+            writer,
+            fields,
+            options,
+            rows_written: 0,
+        })
+    }
+
+    /// Write a single row object
+    pub fn write_row(&mut self, row: &serde_json::Value) -> anyhow::Result<()> {
+        match self.options.format {
+            ExportFormat::Csv => self.write_csv_row(row)?,
+            ExportFormat::Ndjson => self.write_ndjson_row(row)?,
+        }
+        self.rows_written += 1;
+        Ok(())
+    }
+
+    /// Flush the underlying writer and return the number of rows written
+    pub fn finish(mut self) -> anyhow::Result<u64> {
+        self.writer.flush()?;
+        Ok(self.rows_written)
+    }
+
+    fn write_csv_row(&mut self, row: &serde_json::Value) -> std::io::Result<()> {
+        let cells: Vec<String> = self
+            .fields
+            .iter()
+            .map(
+                |field| match row.get(field).unwrap_or(&serde_json::Value::Null) {
+                    serde_json::Value::Null => match &self.options.null_encoding {
+                        NullEncoding::Text(text) => csv_escape(text),
+                        NullEncoding::Empty | NullEncoding::Omit => String::new(),
+                    },
+                    serde_json::Value::String(s) => csv_escape(s),
+                    // Numbers and booleans are written as-is, arrays and objects as JSON text
+                    other => csv_escape(&other.to_string()),
+                },
+            )
+            .collect();
+        writeln!(self.writer, "{}", cells.join(","))
+    }
+
+    fn write_ndjson_row(&mut self, row: &serde_json::Value) -> anyhow::Result<()> {
+        // Build the object by hand so keys keep the `returns` order
+        let mut entries = Vec::with_capacity(self.fields.len());
+        for field in &self.fields {
+            let value = match row.get(field).unwrap_or(&serde_json::Value::Null) {
+                serde_json::Value::Null => match &self.options.null_encoding {
+                    NullEncoding::Empty => serde_json::Value::Null,
+                    NullEncoding::Text(text) => serde_json::Value::String(text.clone()),
+                    NullEncoding::Omit => continue,
+                },
+                other => other.clone(),
+            };
+            entries.push(format!(
+                "{}:{}",
+                serde_json::to_string(field)?,
+                serde_json::to_string(&value)?
+            ));
+        }
+        writeln!(self.writer, "{{{}}}", entries.join(","))?;
+        Ok(())
+    }
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break (RFC 4180)
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn export(rows: &[serde_json::Value], options: ExportOptions) -> String {
+        let fields = vec!["id".to_string(), "name".to_string()];
+        let mut out = Vec::new();
+        let mut writer = ResultWriter::new(&mut out, fields, options).unwrap();
+        for row in rows {
+            writer.write_row(row).unwrap();
+        }
+        assert_eq!(writer.finish().unwrap(), rows.len() as u64);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_csv_escaping_and_nulls() {
+        let rows = [
+            json!({"id": 1, "name": "plain"}),
+            json!({"id": 2, "name": "a,\"b\"\nc"}),
+            json!({"id": 3, "name": null}),
+        ];
+        let options = ExportOptions {
+            format: ExportFormat::Csv,
+            null_encoding: NullEncoding::Text("NULL".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            export(&rows, options),
+            "id,name\n1,plain\n2,\"a,\"\"b\"\"\nc\"\n3,NULL\n"
+        );
+    }
+
+    #[test]
+    fn test_ndjson_keeps_returns_order_and_omits_nulls() {
+        let rows = [
+            json!({"name": "Alice", "id": 1}),
+            json!({"id": 2, "name": null}),
+        ];
+        let options = ExportOptions {
+            null_encoding: NullEncoding::Omit,
+            ..Default::default()
+        };
+        assert_eq!(
+            export(&rows, options),
+            "{\"id\":1,\"name\":\"Alice\"}\n{\"id\":2}\n"
+        );
+    }
+}
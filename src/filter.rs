@@ -0,0 +1,349 @@
+use crate::{
+    ParameterType,
+    parameter_constraints::{ParameterConstraints, parse_constraints, parse_field_type},
+    parameters::{ParameterValue, json_value_to_parameter_value},
+    result::{JankenError, Result},
+};
+
+/// Comparison operators of a filter condition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    In,
+    Like,
+    IsNull,
+}
+
+impl std::str::FromStr for FilterOp {
+    type Err = JankenError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "eq" => Ok(FilterOp::Eq),
+            "ne" => Ok(FilterOp::Ne),
+            "lt" => Ok(FilterOp::Lt),
+            "gt" => Ok(FilterOp::Gt),
+            "in" => Ok(FilterOp::In),
+            "like" => Ok(FilterOp::Like),
+            "is_null" => Ok(FilterOp::IsNull),
+            _ => Err(JankenError::new_parameter_type_mismatch(
+                "filter operator eq, ne, lt, gt, in, like or is_null",
+                s,
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for FilterOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            FilterOp::Eq => "eq",
+            FilterOp::Ne => "ne",
+            FilterOp::Lt => "lt",
+            FilterOp::Gt => "gt",
+            FilterOp::In => "in",
+            FilterOp::Like => "like",
+            FilterOp::IsNull => "is_null",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// A column a filter may compare, declared in args under "columns"
+#[derive(Debug, Clone)]
+pub struct FilterColumn {
+    /// Column as written into the SQL (may be qualified, e.g. `u.name`)
+    pub name: String,
+    /// Type of the values compared with the column
+    pub param_type: ParameterType,
+    /// Constraints applied to every value compared with the column
+    pub constraints: ParameterConstraints,
+    /// Operators allowed on the column
+    pub ops: Vec<FilterOp>,
+}
+
+/// Parse the "columns" of a filter parameter:
+/// `{"status": {"ops": ["eq", "in"], "enum": [...]}, "age": {"type": "integer", "ops": ["lt", "gt"]}}`
+/// Each column accepts the same constraint keys as a regular parameter; the type defaults to string
+pub(crate) fn parse_filter_columns(columns_val: &serde_json::Value) -> Result<Vec<FilterColumn>> {
+    let column_defs = columns_val
+        .as_object()
+        .filter(|column_defs| !column_defs.is_empty())
+        .ok_or_else(|| {
+            JankenError::new_parameter_type_mismatch(
+                "non-empty object of column definitions for columns",
+                columns_val.to_string(),
+            )
+        })?;
+
+    let mut columns = Vec::with_capacity(column_defs.len());
+    for (name, column_def) in column_defs {
+        // Column names are written into the SQL, so they are restricted to (qualified) identifiers
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+        {
+            return Err(JankenError::new_parameter_type_mismatch(
+                "column name with alphanumeric characters, underscores and dots",
+                name.clone(),
+            ));
+        }
+
+        let param_type = parse_field_type(column_def, name)?;
+        let ops = column_def
+            .get("ops")
+            .and_then(|ops_val| ops_val.as_array())
+            .filter(|ops| !ops.is_empty())
+            .ok_or_else(|| {
+                JankenError::new_parameter_type_mismatch(
+                    format!("non-empty array of operators for column {name}"),
+                    column_def.to_string(),
+                )
+            })?
+            .iter()
+            .map(|op| {
+                op.as_str()
+                    .ok_or_else(|| {
+                        JankenError::new_parameter_type_mismatch(
+                            "filter operator eq, ne, lt, gt, in, like or is_null",
+                            op.to_string(),
+                        )
+                    })?
+                    .parse::<FilterOp>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if ops.contains(&FilterOp::Like) && param_type != ParameterType::String {
+            return Err(JankenError::new_parameter_type_mismatch(
+                "string column for like operator",
+                format!("{param_type} for column {name}"),
+            ));
+        }
+
+        let mut constraints = ParameterConstraints::default();
+        parse_constraints(&mut constraints, column_def)?;
+        columns.push(FilterColumn {
+            name: name.clone(),
+            param_type,
+            constraints,
+            ops,
+        });
+    }
+    Ok(columns)
+}
+
+/// A filter expression compiled to SQL with `@name_n` placeholders and their values
+pub(crate) struct CompiledFilter {
+    pub sql: String,
+    pub parameters: Vec<(String, ParameterValue)>,
+    pub sensitive_parameters: Vec<String>,
+    /// Number of column conditions in the expression
+    pub conditions: usize,
+}
+
+/// Validate a filter expression tree against the allowlisted columns and compile it to SQL.
+/// Nodes are `{"and": [...]}`, `{"or": [...]}`, `{"not": {...}}` or column conditions
+/// `{"column": "status", "op": "in", "value": ["a", "b"]}` (`is_null` takes no value).
+pub(crate) fn compile_filter(
+    param_name: &str,
+    constraints: &ParameterConstraints,
+    value: &serde_json::Value,
+    all_params: &serde_json::Map<String, serde_json::Value>,
+) -> Result<CompiledFilter> {
+    let mut compiler = FilterCompiler {
+        param_name,
+        columns: constraints.filter_columns.as_deref().unwrap_or_default(),
+        sensitive: constraints.sensitive,
+        all_params,
+        compiled: CompiledFilter {
+            sql: String::new(),
+            parameters: Vec::new(),
+            sensitive_parameters: Vec::new(),
+            conditions: 0,
+        },
+    };
+    let sql = compiler.compile_node(value, param_name)?;
+    compiler.compiled.sql = sql;
+    Ok(compiler.compiled)
+}
+
+struct FilterCompiler<'a> {
+    param_name: &'a str,
+    columns: &'a [FilterColumn],
+    sensitive: bool,
+    all_params: &'a serde_json::Map<String, serde_json::Value>,
+    compiled: CompiledFilter,
+}
+
+impl FilterCompiler<'_> {
+    fn compile_node(&mut self, node: &serde_json::Value, path: &str) -> Result<String> {
+        let obj = node
+            .as_object()
+            .ok_or_else(|| Self::node_error(node, path))?;
+
+        if obj.len() == 1 {
+            if let Some(children) = obj.get("and") {
+                return self.compile_group(children, "AND", "1 = 1", &format!("{path}.and"));
+            }
+            if let Some(children) = obj.get("or") {
+                return self.compile_group(children, "OR", "1 = 0", &format!("{path}.or"));
+            }
+            if let Some(child) = obj.get("not") {
+                let sql = self.compile_node(child, &format!("{path}.not"))?;
+                return Ok(format!("NOT ({sql})"));
+            }
+        }
+        if obj.contains_key("column")
+            && obj
+                .keys()
+                .all(|key| ["column", "op", "value"].contains(&key.as_str()))
+        {
+            return self.compile_condition(obj, path);
+        }
+        Err(Self::node_error(node, path))
+    }
+
+    fn node_error(node: &serde_json::Value, path: &str) -> JankenError {
+        JankenError::new_parameter_type_mismatch(
+            format!("filter expression (and, or, not or a column condition) at {path}"),
+            node.to_string(),
+        )
+    }
+
+    fn compile_group(
+        &mut self,
+        children: &serde_json::Value,
+        joiner: &str,
+        empty_sql: &str,
+        path: &str,
+    ) -> Result<String> {
+        let children = children.as_array().ok_or_else(|| {
+            JankenError::new_parameter_type_mismatch(
+                format!("array of filter expressions at {path}"),
+                children.to_string(),
+            )
+        })?;
+        if children.is_empty() {
+            return Ok(empty_sql.to_string());
+        }
+        let parts = children
+            .iter()
+            .enumerate()
+            .map(|(index, child)| self.compile_node(child, &format!("{path}[{index}]")))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(format!("({})", parts.join(&format!(" {joiner} "))))
+    }
+
+    fn compile_condition(
+        &mut self,
+        condition: &serde_json::Map<String, serde_json::Value>,
+        path: &str,
+    ) -> Result<String> {
+        let column_val = &condition["column"];
+        let column = self
+            .columns
+            .iter()
+            .find(|column| column_val.as_str() == Some(column.name.as_str()))
+            .ok_or_else(|| {
+                let names: Vec<&str> = self.columns.iter().map(|c| c.name.as_str()).collect();
+                JankenError::new_parameter_type_mismatch(
+                    format!("one of [{}] for column at {path}", names.join(", ")),
+                    column_val.to_string(),
+                )
+            })?;
+
+        let op_val = condition
+            .get("op")
+            .ok_or_else(|| JankenError::new_parameter_not_provided(format!("{path}.op")))?;
+        let op = op_val
+            .as_str()
+            .and_then(|op| op.parse::<FilterOp>().ok())
+            .filter(|op| column.ops.contains(op))
+            .ok_or_else(|| {
+                let ops: Vec<String> = column.ops.iter().map(|op| op.to_string()).collect();
+                JankenError::new_parameter_type_mismatch(
+                    format!("one of [{}] for {} at {path}", ops.join(", "), column.name),
+                    op_val.to_string(),
+                )
+            })?;
+        self.compiled.conditions += 1;
+
+        if op == FilterOp::IsNull {
+            if condition.contains_key("value") {
+                return Err(JankenError::new_parameter_type_mismatch(
+                    format!("no value for is_null at {path}"),
+                    condition["value"].to_string(),
+                ));
+            }
+            return Ok(format!("{} IS NULL", column.name));
+        }
+
+        let value = condition
+            .get("value")
+            .ok_or_else(|| JankenError::new_parameter_not_provided(format!("{path}.value")))?;
+        if op == FilterOp::In {
+            let items = value
+                .as_array()
+                .filter(|items| !items.is_empty())
+                .ok_or_else(|| {
+                    JankenError::new_parameter_type_mismatch(
+                        format!("non-empty array for in at {path}"),
+                        value.to_string(),
+                    )
+                })?;
+            let placeholders = items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| self.bind(column, item, &format!("{path}.value[{index}]")))
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(format!("{} IN ({})", column.name, placeholders.join(", ")));
+        }
+
+        let placeholder = self.bind(column, value, &format!("{path}.value"))?;
+        let operator = match op {
+            FilterOp::Eq => "=",
+            FilterOp::Ne => "<>",
+            FilterOp::Lt => "<",
+            FilterOp::Gt => ">",
+            _ => "LIKE",
+        };
+        Ok(format!("{} {operator} {placeholder}", column.name))
+    }
+
+    /// Validate a value compared with the column and bind it to the next placeholder
+    fn bind(
+        &mut self,
+        column: &FilterColumn,
+        value: &serde_json::Value,
+        path: &str,
+    ) -> Result<String> {
+        let context = format!(" for {} at {path}", column.name);
+        if ParameterConstraints::validate_basic_type(value, &column.param_type).is_err() {
+            return Err(JankenError::new_parameter_type_mismatch(
+                format!("{}{context}", column.param_type),
+                value.to_string(),
+            ));
+        }
+        column.constraints.validate_constraint_rules(
+            value,
+            &column.param_type,
+            &column.name,
+            self.all_params,
+            &context,
+        )?;
+
+        let param_key = format!("{}_{}", self.param_name, self.compiled.parameters.len());
+        if self.sensitive || column.constraints.sensitive {
+            self.compiled.sensitive_parameters.push(param_key.clone());
+        }
+        let placeholder = format!("@{param_key}");
+        self.compiled.parameters.push((
+            param_key,
+            json_value_to_parameter_value(value, &column.param_type)?,
+        ));
+        Ok(placeholder)
+    }
+}
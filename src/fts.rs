@@ -0,0 +1,169 @@
+use crate::{
+    parameters::Dialect,
+    result::{JankenError, Result},
+};
+
+/// How the terms of a full-text search query are combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FtsOperator {
+    /// Every term must match
+    #[default]
+    And,
+    /// Any term may match
+    Or,
+}
+
+impl std::str::FromStr for FtsOperator {
+    type Err = JankenError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "and" => Ok(FtsOperator::And),
+            "or" => Ok(FtsOperator::Or),
+            _ => Err(JankenError::new_parameter_type_mismatch(
+                "fts operator 'and' or 'or'",
+                s,
+            )),
+        }
+    }
+}
+
+/// Options of an fts_query parameter, declared in args as "prefix", "operator" and "term_length"
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FtsOptions {
+    /// Match every term as a prefix (`"term"*` in SQLite, `'term':*` in PostgreSQL)
+    pub prefix: bool,
+    pub operator: FtsOperator,
+    /// Inclusive [min, max] character count of each term
+    pub term_length: Option<[usize; 2]>,
+}
+
+/// Free text sanitized into search terms, rendered as a query for the target backend
+#[derive(Debug, Clone, PartialEq)]
+pub struct FtsQuery {
+    /// Runs of letters, digits and underscores of the text; everything else separates terms
+    pub terms: Vec<String>,
+    pub prefix: bool,
+    pub operator: FtsOperator,
+}
+
+impl FtsQuery {
+    /// Split free text into terms, dropping quotes, operators and other punctuation
+    pub fn from_text(text: &str, options: &FtsOptions) -> Self {
+        let terms = text
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .filter(|term| !term.is_empty())
+            .map(str::to_string)
+            .collect();
+        Self {
+            terms,
+            prefix: options.prefix,
+            operator: options.operator,
+        }
+    }
+
+    /// Render the query string for SQLite FTS5 `MATCH` or PostgreSQL `to_tsquery`
+    /// Terms are quoted, so words like `OR` and `NEAR` are searched for rather than parsed as operators
+    pub fn to_query_string(&self, dialect: Dialect) -> String {
+        let (quote, prefix_marker) = match dialect {
+            Dialect::Sqlite => ('"', "*"),
+            Dialect::Postgresql => ('\'', ":*"),
+        };
+        let separator = match (dialect, self.operator) {
+            (Dialect::Sqlite, FtsOperator::And) => " AND ",
+            (Dialect::Sqlite, FtsOperator::Or) => " OR ",
+            (Dialect::Postgresql, FtsOperator::And) => " & ",
+            (Dialect::Postgresql, FtsOperator::Or) => " | ",
+        };
+        let suffix = if self.prefix { prefix_marker } else { "" };
+        self.terms
+            .iter()
+            .map(|term| format!("{quote}{term}{quote}{suffix}"))
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+}
+
+/// Parse the fts_query options of an arg definition, if it declares any
+pub(crate) fn parse_fts_options(arg_def: &serde_json::Value) -> Result<Option<FtsOptions>> {
+    let (prefix_val, operator_val, term_length_val) = (
+        arg_def.get("prefix"),
+        arg_def.get("operator"),
+        arg_def.get("term_length"),
+    );
+    if prefix_val.is_none() && operator_val.is_none() && term_length_val.is_none() {
+        return Ok(None);
+    }
+
+    let mut options = FtsOptions::default();
+    if let Some(prefix_val) = prefix_val {
+        options.prefix = prefix_val.as_bool().ok_or_else(|| {
+            JankenError::new_parameter_type_mismatch("boolean for prefix", prefix_val.to_string())
+        })?;
+    }
+    if let Some(operator_val) = operator_val {
+        let operator_str = operator_val.as_str().ok_or_else(|| {
+            JankenError::new_parameter_type_mismatch(
+                "fts operator 'and' or 'or'",
+                operator_val.to_string(),
+            )
+        })?;
+        options.operator = operator_str.parse()?;
+    }
+    if let Some(term_length_val) = term_length_val {
+        let bounds: Option<Vec<usize>> = term_length_val
+            .as_array()
+            .and_then(|arr| arr.iter().map(|v| v.as_u64().map(|n| n as usize)).collect());
+        match bounds.as_deref() {
+            Some(&[min, max]) if min <= max => options.term_length = Some([min, max]),
+            _ => {
+                return Err(JankenError::new_parameter_type_mismatch(
+                    "array with exactly 2 non-negative integers [min, max] for term_length",
+                    term_length_val.to_string(),
+                ));
+            }
+        }
+    }
+    Ok(Some(options))
+}
+
+/// Sanitize an fts_query value and check it against the term count (`range`) and term length limits
+pub(crate) fn validate_fts_query(
+    value: &serde_json::Value,
+    options: &FtsOptions,
+    range: Option<&[f64]>,
+) -> Result<FtsQuery> {
+    let text = value
+        .as_str()
+        .ok_or_else(|| JankenError::new_parameter_type_mismatch("fts_query", value.to_string()))?;
+    let query = FtsQuery::from_text(text, options);
+
+    // A query without terms is a syntax error in FTS5 and matches nothing in PostgreSQL
+    if query.terms.is_empty() {
+        return Err(JankenError::new_parameter_type_mismatch(
+            "fts_query with at least one search term",
+            value.to_string(),
+        ));
+    }
+    if let Some(&[min, max]) = range {
+        let count = query.terms.len() as f64;
+        if count < min || count > max {
+            return Err(JankenError::new_parameter_type_mismatch(
+                format!("fts_query size between {min} and {max} terms"),
+                format!("{count} terms"),
+            ));
+        }
+    }
+    if let Some([min, max]) = options.term_length {
+        for (index, term) in query.terms.iter().enumerate() {
+            let len = term.chars().count();
+            if len < min || len > max {
+                return Err(JankenError::new_parameter_type_mismatch(
+                    format!("fts_query term length between {min} and {max} characters"),
+                    format!("{len} characters at term {index}"),
+                ));
+            }
+        }
+    }
+    Ok(query)
+}
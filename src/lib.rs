@@ -1,10 +1,18 @@
+pub mod encoding;
+pub mod export;
+pub mod filter;
+pub mod fts;
+pub mod pagination;
 pub mod parameter_constraints;
 pub mod parameters;
 pub mod query;
 pub mod result;
 #[cfg(feature = "sqlite")]
 pub mod runner_sqlite;
+pub mod statement_cache;
 pub mod str_utils;
+pub mod transaction;
+pub mod workflow;
 
 // PostgreSQL runner (now available in production builds)
 #[cfg(feature = "postgresql")]
@@ -12,28 +20,55 @@ pub mod runner_postgresql;
 
 // Re-export PostgreSQL functions for production use
 #[cfg(feature = "postgresql")]
-pub use runner_postgresql::{query_run_postgresql, query_run_postgresql_with_transaction};
+pub use runner_postgresql::{
+    query_batch_postgresql, query_batch_postgresql_with_transaction, query_explain_postgresql,
+    query_export_postgresql, query_export_postgresql_with_transaction, query_run_postgresql,
+    query_run_postgresql_cached, query_run_postgresql_with_savepoint,
+    query_run_postgresql_with_transaction, query_stream_postgresql_with_transaction,
+    workflow_run_postgresql, workflow_run_postgresql_with_transaction,
+};
 
 #[cfg(feature = "sqlite")]
-pub use runner_sqlite::{query_run_sqlite, query_run_sqlite_with_transaction};
+pub use runner_sqlite::{
+    query_batch_sqlite, query_batch_sqlite_with_transaction, query_explain_sqlite,
+    query_export_sqlite, query_export_sqlite_with_transaction, query_run_sqlite,
+    query_run_sqlite_cached, query_run_sqlite_with_savepoint, query_run_sqlite_with_transaction,
+    query_stream_sqlite, query_stream_sqlite_with_transaction, workflow_run_sqlite,
+    workflow_run_sqlite_with_transaction,
+};
 
 // Re-export types for convenience
-pub use parameters::{Parameter, ParameterType};
-pub use query::{QueryDef, QueryDefinitions};
+pub use encoding::BlobEncoding;
+pub use export::{ExportFormat, ExportOptions, NullEncoding};
+pub use filter::{FilterColumn, FilterOp};
+pub use fts::{FtsOperator, FtsOptions, FtsQuery};
+pub use pagination::{KeysetKey, PAGE_PARAMETER, Pagination, PaginationMode};
+pub use parameters::{
+    BoundParameter, Dialect, ExecutedStatement, LIKE_ESCAPE_CHAR, Parameter, ParameterType,
+    ParameterValue, SearchMode, like_pattern, quote_identifier,
+};
+pub use query::{QueryDef, QueryDefinitions, RowLimit, RowLimitAction};
 pub use result::{
+    BatchResult,
     // Error codes
+    ERR_CODE_BATCH_ITEM_FAILED,
     ERR_CODE_PARAMETER_NAME_CONFLICT,
     ERR_CODE_PARAMETER_NOT_PROVIDED,
     ERR_CODE_PARAMETER_TYPE_MISMATCH,
     ERR_CODE_QUERY_NOT_FOUND,
+    ERR_CODE_READONLY_VIOLATION,
+    ERR_CODE_ROW_LIMIT_EXCEEDED,
     JankenError,
     M_CONFLICT_NAME,
     M_ERROR,
     // Metadata field names
     M_EXPECTED,
     M_GOT,
+    M_INDEX,
+    M_MAX_ROWS,
     M_PARAM_NAME,
     M_QUERY_NAME,
+    M_SQL,
     QueryResult,
     Result,
     error_meta,
@@ -41,6 +76,13 @@ pub use result::{
     get_error_data,
     get_error_info,
 };
+#[cfg(feature = "postgresql")]
+pub use statement_cache::PostgresStatementCache;
+#[cfg(feature = "sqlite")]
+pub use statement_cache::SqliteStatementCache;
+pub use statement_cache::{StatementCache, StatementCacheStats};
+pub use transaction::{IsolationLevel, RetryPolicy, TransactionBehavior, TransactionOptions};
+pub use workflow::{WorkflowDef, WorkflowResult, WorkflowStep, WorkflowStepResult};
 
 // Re-export third-party types used in the public API to provide fallback for dependency conflicts
 pub use serde_json::Value as JsonValue;
@@ -49,4 +91,8 @@ pub use serde_json::Value as JsonValue;
 #[cfg(feature = "sqlite")]
 pub use rusqlite::Connection as SqliteConnection;
 
+// Re-export the stream trait returned by the PostgreSQL streaming API
+#[cfg(feature = "postgresql")]
+pub use futures_util::Stream;
+
 pub use anyhow;
@@ -0,0 +1,544 @@
+use crate::{
+    ParameterType, QueryDef,
+    parameter_constraints::ParameterConstraints,
+    parameters::Parameter,
+    query::ReturnsSpec,
+    result::{JankenError, QueryResult, Result},
+    str_utils::{quote_mask, split_sql_statements},
+};
+use base64::Engine;
+
+/// Request parameter holding the page selection: `{"limit": 20, "offset": 40}` or `{"limit": 20, "cursor": "..."}`
+pub const PAGE_PARAMETER: &str = "page";
+
+const LIMIT_PARAMETER: &str = "janken_page_limit";
+const OFFSET_PARAMETER: &str = "janken_page_offset";
+const AFTER_PARAMETER_PREFIX: &str = "janken_page_after_";
+
+/// A column the keyset pagination orders by and resumes after
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeysetKey {
+    /// Column of the query's result, also listed in its returns
+    pub column: String,
+    /// Whether the rows are ordered by the column descending
+    pub descending: bool,
+}
+
+/// How a paginated query selects its page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaginationMode {
+    /// `LIMIT`/`OFFSET` appended to the query
+    Offset,
+    /// Rows after the cursor in the order of the keys (the last key should be unique)
+    Keyset(Vec<KeysetKey>),
+}
+
+/// Pagination declared per query definition under "paginate":
+/// `{"mode": "keyset", "keys": [{"column": "created_at", "dir": "desc"}, {"column": "id"}], "max_page_size": 100, "count": true}`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pagination {
+    pub mode: PaginationMode,
+    /// Largest `limit` a request may ask for
+    pub max_page_size: usize,
+    /// `limit` used when the request does not give one
+    pub default_page_size: usize,
+    /// Whether a derived `COUNT(*)` query reports the total number of rows
+    pub count: bool,
+}
+
+impl Pagination {
+    /// Parse the "paginate" object of a query definition; `mode` defaults to offset and
+    /// `default_page_size` to `max_page_size`
+    pub fn from_json(query_name: &str, value: &serde_json::Value) -> Result<Self> {
+        let map = value.as_object().ok_or_else(|| {
+            JankenError::new_parameter_type_mismatch(
+                "object for paginate",
+                format!("{query_name}: {value}"),
+            )
+        })?;
+
+        let page_size = |key: &str| -> Result<Option<usize>> {
+            map.get(key)
+                .map(|val| {
+                    val.as_u64()
+                        .filter(|size| *size > 0)
+                        .map(|size| size as usize)
+                        .ok_or_else(|| {
+                            JankenError::new_parameter_type_mismatch(
+                                format!("positive integer for paginate {key}"),
+                                format!("{query_name}: {val}"),
+                            )
+                        })
+                })
+                .transpose()
+        };
+        let max_page_size = page_size("max_page_size")?.ok_or_else(|| {
+            JankenError::new_parameter_type_mismatch(
+                "positive integer for paginate max_page_size",
+                format!("{query_name}: missing max_page_size"),
+            )
+        })?;
+        let default_page_size = page_size("default_page_size")?.unwrap_or(max_page_size);
+        if default_page_size > max_page_size {
+            return Err(JankenError::new_parameter_type_mismatch(
+                "paginate default_page_size within max_page_size",
+                format!("{query_name}: {default_page_size}"),
+            ));
+        }
+
+        let count = match map.get("count") {
+            Some(count_val) => count_val.as_bool().ok_or_else(|| {
+                JankenError::new_parameter_type_mismatch(
+                    "boolean for paginate count",
+                    format!("{query_name}: {count_val}"),
+                )
+            })?,
+            None => false,
+        };
+
+        let mode = match map
+            .get("mode")
+            .map(|mode_val| (mode_val, mode_val.as_str()))
+        {
+            None | Some((_, Some("offset"))) => {
+                if map.contains_key("keys") {
+                    return Err(JankenError::new_parameter_type_mismatch(
+                        "paginate keys only with keyset mode",
+                        format!("{query_name}: keys"),
+                    ));
+                }
+                PaginationMode::Offset
+            }
+            Some((_, Some("keyset"))) => PaginationMode::Keyset(Self::parse_keys(
+                query_name,
+                map.get("keys").unwrap_or(&serde_json::Value::Null),
+            )?),
+            Some((mode_val, _)) => {
+                return Err(JankenError::new_parameter_type_mismatch(
+                    "paginate mode offset or keyset",
+                    format!("{query_name}: {mode_val}"),
+                ));
+            }
+        };
+
+        Ok(Pagination {
+            mode,
+            max_page_size,
+            default_page_size,
+            count,
+        })
+    }
+
+    /// Parse the keyset keys: `[{"column": "created_at", "dir": "desc"}, {"column": "id"}]`
+    fn parse_keys(query_name: &str, keys_val: &serde_json::Value) -> Result<Vec<KeysetKey>> {
+        let key_error = |got: String| {
+            JankenError::new_parameter_type_mismatch(
+                "non-empty array of {\"column\", \"dir\"} keys for keyset pagination",
+                format!("{query_name}: {got}"),
+            )
+        };
+        let keys = keys_val
+            .as_array()
+            .filter(|keys| !keys.is_empty())
+            .ok_or_else(|| key_error(keys_val.to_string()))?;
+
+        keys.iter()
+            .map(|key_val| {
+                let key = key_val
+                    .as_object()
+                    .filter(|key| key.keys().all(|k| k == "column" || k == "dir"))
+                    .ok_or_else(|| key_error(key_val.to_string()))?;
+                // Key columns are written into the SQL, so they are restricted to identifiers
+                let column = key
+                    .get("column")
+                    .and_then(|column| column.as_str())
+                    .filter(|column| {
+                        !column.is_empty()
+                            && column.chars().all(|c| c.is_alphanumeric() || c == '_')
+                    })
+                    .ok_or_else(|| key_error(key_val.to_string()))?;
+                let descending = match key.get("dir").map(|dir| dir.as_str()) {
+                    None | Some(Some("asc")) => false,
+                    Some(Some("desc")) => true,
+                    Some(_) => return Err(key_error(key_val.to_string())),
+                };
+                Ok(KeysetKey {
+                    column: column.to_string(),
+                    descending,
+                })
+            })
+            .collect()
+    }
+
+    /// Check that a query can be paginated: a single statement returning rows, with no parameter
+    /// of its own named `page` and no rows parameter (which could be split into several statements)
+    pub(crate) fn check_query(query_name: &str, query_def: &QueryDef) -> Result<()> {
+        let returns_rows = match &query_def.returns {
+            ReturnsSpec::Static(fields) => !fields.is_empty(),
+            ReturnsSpec::Dynamic(_) => true,
+            ReturnsSpec::PerStatement(_) => false,
+        };
+        if !returns_rows || split_sql_statements(&query_def.sql).len() != 1 {
+            return Err(JankenError::new_parameter_type_mismatch(
+                "single statement with returns for paginated query",
+                query_name,
+            ));
+        }
+        if query_def
+            .parameters
+            .iter()
+            .any(|param| param.name == PAGE_PARAMETER)
+        {
+            return Err(JankenError::new_parameter_type_mismatch(
+                format!("no '{PAGE_PARAMETER}' parameter in paginated query"),
+                query_name,
+            ));
+        }
+        if query_def
+            .parameters
+            .iter()
+            .any(|param| param.param_type == ParameterType::Rows)
+        {
+            return Err(JankenError::new_parameter_type_mismatch(
+                "no rows parameter in paginated query",
+                query_name,
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A paginated request resolved into the query selecting the page and, when the pagination
+/// counts, the query counting every row. Both are ordinary query definitions, so each runner
+/// executes them like any other query.
+pub(crate) struct PageQuery {
+    /// The query with the page selection applied
+    pub query: QueryDef,
+    /// Request parameters extended with the page selection values
+    pub params: serde_json::Map<String, serde_json::Value>,
+    /// `SELECT COUNT(*)` over the query, run with the original request parameters
+    pub count_query: Option<QueryDef>,
+    limit: usize,
+    keys: Option<Vec<KeysetKey>>,
+}
+
+impl PageQuery {
+    /// Resolve the `page` request parameter against the query's pagination
+    pub(crate) fn resolve(
+        query: &QueryDef,
+        pagination: &Pagination,
+        request_params_obj: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<Self> {
+        let empty_page = serde_json::Map::new();
+        let page = match request_params_obj.get(PAGE_PARAMETER) {
+            Some(page_val) => page_val.as_object().ok_or_else(|| {
+                JankenError::new_parameter_type_mismatch(
+                    format!("object for {PAGE_PARAMETER}"),
+                    page_val.to_string(),
+                )
+            })?,
+            None => &empty_page,
+        };
+        let position_key = match pagination.mode {
+            PaginationMode::Offset => "offset",
+            PaginationMode::Keyset(_) => "cursor",
+        };
+        if let Some(key) = page.keys().find(|k| *k != "limit" && *k != position_key) {
+            return Err(JankenError::new_parameter_type_mismatch(
+                format!("{PAGE_PARAMETER} with limit and {position_key}"),
+                key.clone(),
+            ));
+        }
+
+        let limit = match page.get("limit") {
+            Some(limit_val) => limit_val
+                .as_u64()
+                .map(|limit| limit as usize)
+                .filter(|limit| (1..=pagination.max_page_size).contains(limit))
+                .ok_or_else(|| {
+                    JankenError::new_parameter_type_mismatch(
+                        format!(
+                            "{PAGE_PARAMETER} limit between 1 and {}",
+                            pagination.max_page_size
+                        ),
+                        limit_val.to_string(),
+                    )
+                })?,
+            None => pagination.default_page_size,
+        };
+
+        // Pages and counts are only consistent when the whole query runs as one statement
+        let mut parameters = unchunked_parameters(&query.parameters);
+        let mut params = request_params_obj.clone();
+        let sql = subquery_sql(&query.sql);
+
+        let (page_sql, keys) = match &pagination.mode {
+            PaginationMode::Offset => {
+                let offset = match page.get("offset") {
+                    Some(offset_val) => offset_val.as_u64().ok_or_else(|| {
+                        JankenError::new_parameter_type_mismatch(
+                            format!("non-negative integer for {PAGE_PARAMETER} offset"),
+                            offset_val.to_string(),
+                        )
+                    })?,
+                    None => 0,
+                };
+                bind(&mut parameters, &mut params, LIMIT_PARAMETER, limit.into());
+                bind(
+                    &mut parameters,
+                    &mut params,
+                    OFFSET_PARAMETER,
+                    offset.into(),
+                );
+                // Wrapped like keyset pages, so a LIMIT of the query itself stays valid
+                let page_sql = format!(
+                    "SELECT * FROM ({sql}) AS janken_page LIMIT CAST(@{LIMIT_PARAMETER} AS INTEGER) OFFSET CAST(@{OFFSET_PARAMETER} AS INTEGER)"
+                );
+                (page_sql, None)
+            }
+            PaginationMode::Keyset(keys) => {
+                let returns_fields = query.returns.resolve(request_params_obj)?;
+                if let Some(key) = keys
+                    .iter()
+                    .find(|key| !returns_fields.contains(&key.column))
+                {
+                    return Err(JankenError::new_parameter_type_mismatch(
+                        "returns including every keyset column",
+                        key.column.clone(),
+                    ));
+                }
+
+                let mut page_sql = format!("SELECT * FROM ({sql}) AS janken_page");
+                if let Some(cursor_val) = page.get("cursor") {
+                    let after = decode_cursor(cursor_val, keys.len())?;
+                    for (index, value) in after.into_iter().enumerate() {
+                        bind(
+                            &mut parameters,
+                            &mut params,
+                            &format!("{AFTER_PARAMETER_PREFIX}{index}"),
+                            value,
+                        );
+                    }
+                    page_sql.push_str(&format!(" WHERE {}", keyset_condition(keys)));
+                }
+                let order_by: Vec<String> = keys
+                    .iter()
+                    .map(|key| {
+                        let dir = if key.descending { "DESC" } else { "ASC" };
+                        format!("{} {dir}", key.column)
+                    })
+                    .collect();
+                // One extra row tells whether there is a next page
+                bind(
+                    &mut parameters,
+                    &mut params,
+                    LIMIT_PARAMETER,
+                    (limit + 1).into(),
+                );
+                page_sql.push_str(&format!(
+                    " ORDER BY {} LIMIT CAST(@{LIMIT_PARAMETER} AS INTEGER)",
+                    order_by.join(", ")
+                ));
+                (page_sql, Some(keys.clone()))
+            }
+        };
+
+        let count_query = pagination.count.then(|| {
+            derived_query(
+                query,
+                format!("SELECT COUNT(*) AS total FROM ({sql}) AS janken_count"),
+                unchunked_parameters(&query.parameters),
+                ReturnsSpec::Static(vec!["total".to_string()]),
+            )
+        });
+
+        Ok(PageQuery {
+            query: derived_query(query, page_sql, parameters, query.returns.clone()),
+            params,
+            count_query,
+            limit,
+            keys,
+        })
+    }
+
+    /// Combine the results of the page and count queries: the page rows with the cursor of
+    /// the next page (keyset mode, when there is one) and the total row count
+    pub(crate) fn finish(
+        &self,
+        page_result: QueryResult,
+        count_result: Option<QueryResult>,
+    ) -> Result<QueryResult> {
+        let mut data = page_result.data;
+        let mut next_cursor = None;
+        if let Some(keys) = &self.keys
+            && data.len() > self.limit
+        {
+            data.truncate(self.limit);
+            let last_row = data.last().expect("limit is at least 1");
+            next_cursor = Some(encode_cursor(keys, last_row)?);
+        }
+
+        let mut statements = page_result.statements;
+        let mut total = None;
+        if let Some(count_result) = count_result {
+            total = count_result
+                .data
+                .first()
+                .and_then(|row| row["total"].as_u64());
+            statements.extend(count_result.statements);
+        }
+
+        let mut query_result = QueryResult::from_statements(statements, data, vec![]);
+        query_result.next_cursor = next_cursor;
+        query_result.total = total;
+        Ok(query_result)
+    }
+}
+
+/// The query's SQL as the body of a subquery: without the trailing `;` and trailing `--`
+/// comments, which would otherwise comment out the closing parenthesis
+fn subquery_sql(sql: &str) -> &str {
+    let mut sql = sql;
+    loop {
+        sql = sql.trim_end().trim_end_matches(';').trim_end();
+        let in_quotes = quote_mask(sql);
+        let last_line_start = sql.rfind('\n').map_or(0, |pos| pos + 1);
+        let comment_start = sql[last_line_start..]
+            .match_indices("--")
+            .map(|(pos, _)| last_line_start + pos)
+            .find(|&pos| !in_quotes[pos]);
+        match comment_start {
+            Some(pos) => sql = &sql[..pos],
+            None => return sql,
+        }
+    }
+}
+
+/// The query's parameters without `chunk_size`: lists are then never split into several
+/// statements, and `check_query` rules out rows parameters, the only ones split at the
+/// parameter limit
+fn unchunked_parameters(parameters: &[Parameter]) -> Vec<Parameter> {
+    parameters
+        .iter()
+        .cloned()
+        .map(|mut param| {
+            param.constraints.chunk_size = None;
+            param
+        })
+        .collect()
+}
+
+/// A query definition like `query` with its own SQL, parameters and returns
+/// It has no `max_rows`: the page query is bounded by `max_page_size` and reads one lookahead
+/// row in keyset mode, which the limit would count, and the count query returns a single row
+fn derived_query(
+    query: &QueryDef,
+    sql: String,
+    parameters: Vec<Parameter>,
+    returns: ReturnsSpec,
+) -> QueryDef {
+    QueryDef {
+        sql,
+        parameters,
+        returns,
+        blob_encoding: query.blob_encoding,
+        readonly: query.readonly,
+        transaction: query.transaction.clone(),
+        paginate: None,
+        max_rows: None,
+    }
+}
+
+/// Declare a page selection parameter and set its value
+fn bind(
+    parameters: &mut Vec<Parameter>,
+    params: &mut serde_json::Map<String, serde_json::Value>,
+    name: &str,
+    value: serde_json::Value,
+) {
+    let param_type = match &value {
+        serde_json::Value::Bool(_) => ParameterType::Boolean,
+        serde_json::Value::Number(number) if number.is_i64() || number.is_u64() => {
+            ParameterType::Integer
+        }
+        serde_json::Value::Number(_) => ParameterType::Float,
+        _ => ParameterType::String,
+    };
+    parameters.push(Parameter {
+        name: name.to_string(),
+        param_type,
+        constraints: ParameterConstraints::default(),
+    });
+    params.insert(name.to_string(), value);
+}
+
+/// Rows strictly after the cursor in key order:
+/// `(a < @after_0) OR (a = @after_0 AND b > @after_1)` for keys `a DESC, b ASC`
+fn keyset_condition(keys: &[KeysetKey]) -> String {
+    let branches: Vec<String> = (0..keys.len())
+        .map(|branch| {
+            let mut comparisons: Vec<String> = keys[..branch]
+                .iter()
+                .enumerate()
+                .map(|(index, key)| format!("{} = @{AFTER_PARAMETER_PREFIX}{index}", key.column))
+                .collect();
+            let key = &keys[branch];
+            let operator = if key.descending { "<" } else { ">" };
+            comparisons.push(format!(
+                "{} {operator} @{AFTER_PARAMETER_PREFIX}{branch}",
+                key.column
+            ));
+            format!("({})", comparisons.join(" AND "))
+        })
+        .collect();
+    format!("({})", branches.join(" OR "))
+}
+
+/// Opaque cursor: the last row's key values as URL-safe base64 of a JSON array
+/// A NULL key cannot be resumed after (`decode_cursor` only accepts scalars), so it is an error
+fn encode_cursor(keys: &[KeysetKey], row: &serde_json::Value) -> Result<String> {
+    let values = keys
+        .iter()
+        .map(|key| match &row[&key.column] {
+            serde_json::Value::Null => Err(JankenError::new_parameter_type_mismatch(
+                "non-null keyset column value for the next page cursor",
+                format!("null in {}", key.column),
+            )),
+            value => Ok(value.clone()),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .encode(serde_json::Value::Array(values).to_string()))
+}
+
+/// Decode a cursor produced by `encode_cursor` back into one non-null value per key
+fn decode_cursor(
+    cursor_val: &serde_json::Value,
+    key_count: usize,
+) -> Result<Vec<serde_json::Value>> {
+    let cursor_error = || {
+        JankenError::new_parameter_type_mismatch(
+            format!("{PAGE_PARAMETER} cursor from a previous page"),
+            cursor_val.to_string(),
+        )
+    };
+    let bytes = cursor_val
+        .as_str()
+        .and_then(|cursor| {
+            base64::engine::general_purpose::URL_SAFE_NO_PAD
+                .decode(cursor)
+                .ok()
+        })
+        .ok_or_else(cursor_error)?;
+    match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(serde_json::Value::Array(values))
+            if values.len() == key_count
+                && values
+                    .iter()
+                    .all(|value| value.is_string() || value.is_number() || value.is_boolean()) =>
+        {
+            Ok(values)
+        }
+        _ => Err(cursor_error()),
+    }
+}
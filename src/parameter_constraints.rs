@@ -1,8 +1,10 @@
 use crate::{
     ParameterType,
+    encoding::{BlobEncoding, decode_encoded_blob, encoded_blob_encoding},
     result::{JankenError, Result},
 };
 use regex::Regex;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::str::FromStr;
 
@@ -14,6 +16,15 @@ pub struct ParameterConstraints {
     pub enum_values: Option<Vec<serde_json::Value>>, // For any type: allowed values
     pub item_type: Option<crate::ParameterType>, // For list types: the type of each item
     pub enumif: Option<HashMap<String, HashMap<String, Vec<serde_json::Value>>>>, // Conditional enums: {"other_param": {"value": [allowed_values]}} where value can be "exact_match", "start:pattern", "end:pattern", or "contain:pattern"
+    pub blob_encoding: Option<BlobEncoding>, // For blob types: also accept {"base64": "..."} or {"hex": "..."} values
+    pub sensitive: bool, // For any type: redact bound values when rendering executed SQL
+    pub fields: Option<Vec<crate::parameters::Parameter>>, // For rows types: the ordered fields of each row, with their own type and constraints
+    pub pg_array: bool, // For list types: bind the whole list as one typed array in PostgreSQL
+    pub chunk_size: Option<usize>, // For list and rows types: the most items bound per statement when split into chunks
+    pub filter_columns: Option<Vec<crate::filter::FilterColumn>>, // For filter types: the allowlisted columns with their type, constraints and operators
+    pub search_mode: Option<crate::parameters::SearchMode>, // For search types: where the term may occur (prefix, suffix or contains)
+    pub fts: Option<crate::fts::FtsOptions>, // For fts_query types: prefix matching, term operator and term length
+    pub quote: bool, // For table_name and comma_list types: accept schema.name and emit double-quoted identifiers
 }
 
 impl ParameterConstraints {
@@ -201,7 +212,7 @@ impl ParameterConstraints {
     }
 
     /// Validate basic type (without any constraints)
-    fn validate_basic_type(
+    pub(crate) fn validate_basic_type(
         value: &serde_json::Value,
         param_type: &crate::ParameterType,
     ) -> Result<()> {
@@ -211,44 +222,43 @@ impl ParameterConstraints {
         }
 
         match param_type {
-            crate::ParameterType::String => {
-                if !value.is_string() {
-                    return Err(Self::constraint_mismatch_error(param_type, value));
-                }
+            crate::ParameterType::String | crate::ParameterType::Search if !value.is_string() => {
+                return Err(Self::constraint_mismatch_error(param_type, value));
             }
-            crate::ParameterType::Integer => {
+            crate::ParameterType::Integer
                 if !value.is_number()
                     || value
                         .as_number()
                         .expect("is_number() already verified this is a number")
                         .as_i64()
-                        .is_none()
-                {
-                    return Err(Self::constraint_mismatch_error(param_type, value));
-                }
+                        .is_none() =>
+            {
+                return Err(Self::constraint_mismatch_error(param_type, value));
             }
-            crate::ParameterType::Float => {
+            crate::ParameterType::Float
                 if !value.is_number()
                     || value
                         .as_number()
                         .expect("is_number() already verified this is a number")
                         .as_f64()
-                        .is_none()
-                {
-                    return Err(Self::constraint_mismatch_error(param_type, value));
-                }
+                        .is_none() =>
+            {
+                return Err(Self::constraint_mismatch_error(param_type, value));
             }
-            crate::ParameterType::Boolean => {
-                if !value.is_boolean() {
-                    return Err(Self::constraint_mismatch_error(param_type, value));
-                }
+            crate::ParameterType::Boolean if !value.is_boolean() => {
+                return Err(Self::constraint_mismatch_error(param_type, value));
             }
-            crate::ParameterType::TableName => {
-                if !value.is_string() {
-                    return Err(Self::constraint_mismatch_error(param_type, value));
-                }
+            crate::ParameterType::TableName if !value.is_string() => {
+                return Err(Self::constraint_mismatch_error(param_type, value));
             }
             crate::ParameterType::Blob => {
+                // Encoded blobs ({"base64": "..."} or {"hex": "..."}) must decode to bytes
+                if encoded_blob_encoding(value).is_some() {
+                    if decode_encoded_blob(value).is_none() {
+                        return Err(Self::constraint_mismatch_error(param_type, value));
+                    }
+                    return Ok(());
+                }
                 if !value.is_array() {
                     return Err(Self::constraint_mismatch_error(param_type, value));
                 }
@@ -284,8 +294,21 @@ impl ParameterConstraints {
     }
 
     /// Validate that a string is a valid table name (alphanumeric and underscores only)
-    fn validate_table_name_format(table_name: &str, context: &str) -> Result<()> {
-        if table_name.is_empty() || !table_name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+    /// Quoted identifiers may also be schema-qualified (`schema.name`)
+    fn validate_table_name_format(table_name: &str, quote: bool, context: &str) -> Result<()> {
+        let is_valid_part =
+            |part: &str| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_');
+        if quote {
+            let parts: Vec<&str> = table_name.split('.').collect();
+            if parts.len() > 2 || !parts.iter().all(|part| is_valid_part(part)) {
+                return Err(JankenError::new_parameter_type_mismatch(
+                    format!(
+                        "valid identifier (name or schema.name, alphanumeric and underscores only){context}"
+                    ),
+                    table_name,
+                ));
+            }
+        } else if !is_valid_part(table_name) {
             return Err(JankenError::new_parameter_type_mismatch(
                 format!("valid table name (alphanumeric and underscores only){context}"),
                 table_name,
@@ -331,7 +354,7 @@ impl ParameterConstraints {
 
     /// Validate constraint rules (range, pattern, enum, enumif) only, assuming basic type is already validated
     /// `context` is used to provide context in error messages (e.g., " at index 0" for array items)
-    fn validate_constraint_rules(
+    pub(crate) fn validate_constraint_rules(
         &self,
         value: &serde_json::Value,
         param_type: &crate::ParameterType,
@@ -347,6 +370,21 @@ impl ParameterConstraints {
             ));
         }
 
+        // Encoded blob values are only accepted in the encoding selected by the arg definition
+        if matches!(param_type, crate::ParameterType::Blob)
+            && let Some(encoding) = encoded_blob_encoding(value)
+            && self.blob_encoding != Some(encoding)
+        {
+            let expected = match self.blob_encoding {
+                Some(allowed) => format!("array of byte values or {allowed} encoded blob{context}"),
+                None => format!("array of byte values{context}"),
+            };
+            return Err(JankenError::new_parameter_type_mismatch(
+                expected,
+                value.to_string(),
+            ));
+        }
+
         // Check range for different types (skip if value is null)
         if let Some(range) = &self.range
             && !value.is_null()
@@ -367,7 +405,9 @@ impl ParameterConstraints {
                         ));
                     }
                 }
-                crate::ParameterType::String | crate::ParameterType::TableName => {
+                crate::ParameterType::String
+                | crate::ParameterType::TableName
+                | crate::ParameterType::Search => {
                     // For strings, range represents min/max character count
                     let str_len = value
                         .as_str()
@@ -385,11 +425,13 @@ impl ParameterConstraints {
                     }
                 }
                 crate::ParameterType::Blob => {
-                    // For blob, range represents min/max size in bytes
-                    let blob_len = value
-                        .as_array()
-                        .expect("value already validated as Blob type")
-                        .len();
+                    // For blob, range represents min/max size in bytes (decoded size for encoded blobs)
+                    let blob_len = match value.as_array() {
+                        Some(bytes) => bytes.len(),
+                        None => decode_encoded_blob(value)
+                            .expect("value already validated as encoded Blob type")
+                            .len(),
+                    };
                     Self::validate_array_size_range(range, blob_len, "blob", "bytes")?;
                 }
                 _ => {}
@@ -408,6 +450,111 @@ impl ParameterConstraints {
         Ok(())
     }
 
+    /// Validate each field of a rows or tuple list item with the field's own type and constraints;
+    /// enumif conditions of a field refer to the other fields of the same item
+    fn validate_item_fields(
+        fields: &[crate::parameters::Parameter],
+        item: &serde_json::Value,
+        param_name: &str,
+        index: usize,
+        accept_arrays: bool,
+    ) -> Result<()> {
+        let values = item_field_values(item, fields, param_name, index, accept_arrays)?;
+        let item_params: Cow<serde_json::Map<String, serde_json::Value>> = match item {
+            serde_json::Value::Object(obj) => Cow::Borrowed(obj),
+            _ => Cow::Owned(
+                fields
+                    .iter()
+                    .zip(&values)
+                    .map(|(field, value)| (field.name.clone(), (*value).clone()))
+                    .collect(),
+            ),
+        };
+
+        for (field, field_value) in fields.iter().zip(values) {
+            let context = format!(" for field {} at index {index}", field.name);
+            if Self::validate_basic_type(field_value, &field.param_type).is_err() {
+                return Err(JankenError::new_parameter_type_mismatch(
+                    format!("{}{context}", field.param_type),
+                    field_value.to_string(),
+                ));
+            }
+            field.constraints.validate_constraint_rules(
+                field_value,
+                &field.param_type,
+                &field.name,
+                &item_params,
+                &context,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Validate one sort key `{"field": ..., "dir": "asc"|"desc", "nulls": "first"|"last"}`:
+    /// the field must be in the column allowlist (`enum`), direction and nulls ordering are optional
+    fn validate_sort_key(
+        &self,
+        key: &serde_json::Value,
+        param_name: &str,
+        index: usize,
+        all_params: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<()> {
+        let obj = key.as_object().ok_or_else(|| {
+            JankenError::new_parameter_type_mismatch(
+                format!("object at index {index}"),
+                key.to_string(),
+            )
+        })?;
+        if let Some(unknown) = obj
+            .keys()
+            .find(|name| !["field", "dir", "nulls"].contains(&name.as_str()))
+        {
+            return Err(JankenError::new_parameter_type_mismatch(
+                format!("sort key with field, dir and nulls at index {index}"),
+                unknown.clone(),
+            ));
+        }
+
+        let field = obj.get("field").ok_or_else(|| {
+            JankenError::new_parameter_not_provided(format!("{param_name}[{index}].field"))
+        })?;
+        let context = format!(" for sort field at index {index}");
+        if !field.is_string() {
+            return Err(JankenError::new_parameter_type_mismatch(
+                format!("string{context}"),
+                field.to_string(),
+            ));
+        }
+        let field_constraints = ParameterConstraints {
+            enum_values: self.enum_values.clone(),
+            ..Default::default()
+        };
+        field_constraints.validate_constraint_rules(
+            field,
+            &crate::ParameterType::String,
+            param_name,
+            all_params,
+            &context,
+        )?;
+
+        for (option, allowed) in [("dir", ["asc", "desc"]), ("nulls", ["first", "last"])] {
+            if let Some(option_value) = obj.get(option)
+                && !option_value
+                    .as_str()
+                    .is_some_and(|text| allowed.contains(&text.to_lowercase().as_str()))
+            {
+                return Err(JankenError::new_parameter_type_mismatch(
+                    format!(
+                        "{} or {} for {option} at index {index}",
+                        allowed[0], allowed[1]
+                    ),
+                    option_value.to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
     /// Validate a parameter value against these constraints
     pub fn validate(
         &self,
@@ -430,6 +577,14 @@ impl ParameterConstraints {
                 Self::validate_array_size_range(range, array.len(), "list", "elements")?;
             }
 
+            // Validate each item of a tuple list against the fields of the tuple
+            if let Some(fields) = &self.fields {
+                for (index, item) in array.iter().enumerate() {
+                    Self::validate_item_fields(fields, item, param_name, index, true)?;
+                }
+                return Ok(());
+            }
+
             // Validate each item in the list if item_type is specified
             // Note: item_type validation is already done during constraint parsing at definition time
             if let Some(item_type) = &self.item_type {
@@ -474,6 +629,15 @@ impl ParameterConstraints {
                 enum_values: self.enum_values.clone(),
                 item_type: None, // Not applicable for CommaList items
                 enumif: self.enumif.clone(),
+                blob_encoding: None, // Not applicable for CommaList items
+                sensitive: self.sensitive,
+                fields: None,         // Not applicable for CommaList items
+                pg_array: false,      // Not applicable for CommaList items
+                chunk_size: None,     // Not applicable for CommaList items
+                filter_columns: None, // Not applicable for CommaList items
+                search_mode: None,    // Not applicable for CommaList items
+                fts: None,            // Not applicable for CommaList items
+                quote: false,         // Applied by the identifier check below
             };
 
             // Validate each item in the comma list - must be strings
@@ -501,11 +665,68 @@ impl ParameterConstraints {
                 let string_val = item
                     .as_str()
                     .expect("is_string() already verified for this item");
-                Self::validate_table_name_format(string_val, &context)?;
+                Self::validate_table_name_format(string_val, self.quote, &context)?;
+            }
+            return Ok(());
+        }
+
+        if param_type == &crate::ParameterType::Rows {
+            let array = value
+                .as_array()
+                .ok_or_else(|| Self::constraint_mismatch_error(param_type, value))?;
+
+            // Validate the number of rows against the range constraint
+            if let Some(range) = &self.range {
+                Self::validate_array_size_range(range, array.len(), "rows", "rows")?;
+            }
+
+            let fields = self.fields.as_deref().unwrap_or_default();
+            for (index, row) in array.iter().enumerate() {
+                Self::validate_item_fields(fields, row, param_name, index, false)?;
+            }
+            return Ok(());
+        }
+
+        if param_type == &crate::ParameterType::Filter {
+            let compiled = crate::filter::compile_filter(param_name, self, value, all_params)?;
+            // Validate the number of column conditions against the range constraint
+            if let Some(range) = &self.range {
+                Self::validate_array_size_range(
+                    range,
+                    compiled.conditions,
+                    "filter",
+                    "conditions",
+                )?;
             }
             return Ok(());
         }
 
+        if param_type == &crate::ParameterType::Sort {
+            let array = value
+                .as_array()
+                .ok_or_else(|| Self::constraint_mismatch_error(param_type, value))?;
+
+            // Validate the number of sort keys against the range constraint
+            if let Some(range) = &self.range {
+                Self::validate_array_size_range(range, array.len(), "sort", "keys")?;
+            }
+
+            for (index, key) in array.iter().enumerate() {
+                self.validate_sort_key(key, param_name, index, all_params)?;
+            }
+            return Ok(());
+        }
+
+        if param_type == &crate::ParameterType::FtsQuery {
+            // Range limits the number of terms rather than the length of the text
+            crate::fts::validate_fts_query(
+                value,
+                &self.fts.clone().unwrap_or_default(),
+                self.range.as_deref(),
+            )?;
+            return Ok(());
+        }
+
         self.validate_constraints(value, param_type, param_name, all_params)?;
 
         if param_type == &crate::ParameterType::TableName {
@@ -513,13 +734,57 @@ impl ParameterConstraints {
             let table_name_str = value
                 .as_str()
                 .expect("basic type validation already verified this is a string");
-            Self::validate_table_name_format(table_name_str, "")?;
+            Self::validate_table_name_format(table_name_str, self.quote, "")?;
         }
 
         Ok(())
     }
 }
 
+/// Values of one rows or tuple list item aligned with the fields: objects are matched by field
+/// name, arrays (accepted for tuple lists) by position with exactly one value per field
+pub(crate) fn item_field_values<'a>(
+    item: &'a serde_json::Value,
+    fields: &[crate::parameters::Parameter],
+    param_name: &str,
+    index: usize,
+    accept_arrays: bool,
+) -> Result<Vec<&'a serde_json::Value>> {
+    match item {
+        serde_json::Value::Object(obj) => fields
+            .iter()
+            .map(|field| {
+                obj.get(&field.name).ok_or_else(|| {
+                    JankenError::new_parameter_not_provided(format!(
+                        "{param_name}[{index}].{}",
+                        field.name
+                    ))
+                })
+            })
+            .collect(),
+        serde_json::Value::Array(values) if accept_arrays => {
+            if values.len() != fields.len() {
+                return Err(JankenError::new_parameter_type_mismatch(
+                    format!("array of {} values at index {index}", fields.len()),
+                    item.to_string(),
+                ));
+            }
+            Ok(values.iter().collect())
+        }
+        _ => {
+            let expected = if accept_arrays {
+                "array or object"
+            } else {
+                "object"
+            };
+            Err(JankenError::new_parameter_type_mismatch(
+                format!("{expected} at index {index}"),
+                item.to_string(),
+            ))
+        }
+    }
+}
+
 /// Parse constraints from JSON into ParameterConstraints
 pub fn parse_constraints(
     constraints: &mut ParameterConstraints,
@@ -677,6 +942,27 @@ pub fn parse_constraints(
         ));
     }
 
+    if let Some(encoding_val) = arg_def.get("encoding") {
+        let encoding_str = encoding_val.as_str().ok_or_else(|| {
+            JankenError::new_parameter_type_mismatch(
+                "string for blob encoding",
+                encoding_val.to_string(),
+            )
+        })?;
+        let encoding = BlobEncoding::from_str(encoding_str)?;
+        // Plain byte arrays are always accepted, so "array" adds nothing
+        constraints.blob_encoding = (encoding != BlobEncoding::Array).then_some(encoding);
+    }
+
+    if let Some(sensitive_val) = arg_def.get("sensitive") {
+        constraints.sensitive = sensitive_val.as_bool().ok_or_else(|| {
+            JankenError::new_parameter_type_mismatch(
+                "boolean for sensitive",
+                sensitive_val.to_string(),
+            )
+        })?;
+    }
+
     if let Some(itemtype_val) = arg_def.get("itemtype")
         && let Some(itemtype_str) = itemtype_val.as_str()
     {
@@ -689,14 +975,142 @@ pub fn parse_constraints(
                     item_type.to_string(),
                 ));
             }
+            // Search patterns and full-text queries only make sense on the right of LIKE / MATCH
+            ParameterType::Search | ParameterType::FtsQuery => {
+                return Err(JankenError::new_parameter_type_mismatch(
+                    "item_type for list items cannot be search or fts_query",
+                    item_type.to_string(),
+                ));
+            }
             _ => {}
         }
         constraints.item_type = Some(item_type);
     }
 
+    if let Some(pg_array_val) = arg_def.get("pg_array") {
+        constraints.pg_array = pg_array_val.as_bool().ok_or_else(|| {
+            JankenError::new_parameter_type_mismatch(
+                "boolean for pg_array",
+                pg_array_val.to_string(),
+            )
+        })?;
+    }
+
+    if let Some(chunk_size_val) = arg_def.get("chunk_size") {
+        let chunk_size = chunk_size_val
+            .as_u64()
+            .filter(|size| *size > 0)
+            .ok_or_else(|| {
+                JankenError::new_parameter_type_mismatch(
+                    "positive integer for chunk_size",
+                    chunk_size_val.to_string(),
+                )
+            })?;
+        constraints.chunk_size = Some(chunk_size as usize);
+    }
+
+    if let Some(mode_val) = arg_def.get("mode") {
+        let mode_str = mode_val.as_str().ok_or_else(|| {
+            JankenError::new_parameter_type_mismatch(
+                "search mode prefix, suffix or contains",
+                mode_val.to_string(),
+            )
+        })?;
+        constraints.search_mode = Some(crate::parameters::SearchMode::from_str(mode_str)?);
+    }
+
+    constraints.fts = crate::fts::parse_fts_options(arg_def)?;
+
+    if let Some(quote_val) = arg_def.get("quote") {
+        constraints.quote = quote_val.as_bool().ok_or_else(|| {
+            JankenError::new_parameter_type_mismatch("boolean for quote", quote_val.to_string())
+        })?;
+    }
+
+    if let Some(columns_val) = arg_def.get("columns") {
+        constraints.filter_columns = Some(crate::filter::parse_filter_columns(columns_val)?);
+    }
+
+    if let Some(fields_val) = arg_def.get("fields") {
+        constraints.fields = Some(parse_row_fields(fields_val)?);
+    }
+
     Ok(())
 }
 
+/// Parse the "fields" of a rows or tuple list parameter: `[{"name": "a", "type": "integer", ...}, ...]`
+/// Each field accepts the same constraint keys as a regular parameter; the type defaults to string
+fn parse_row_fields(fields_val: &serde_json::Value) -> Result<Vec<crate::parameters::Parameter>> {
+    let field_defs = fields_val
+        .as_array()
+        .filter(|field_defs| !field_defs.is_empty())
+        .ok_or_else(|| {
+            JankenError::new_parameter_type_mismatch(
+                "non-empty array of field definitions for fields",
+                fields_val.to_string(),
+            )
+        })?;
+
+    let mut fields: Vec<crate::parameters::Parameter> = Vec::new();
+    for field_def in field_defs {
+        // Field names become part of placeholder names, so they are restricted to word characters
+        let name = field_def
+            .get("name")
+            .and_then(|name_val| name_val.as_str())
+            .filter(|name| {
+                !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+            })
+            .ok_or_else(|| {
+                JankenError::new_parameter_type_mismatch(
+                    "field definition with an alphanumeric 'name'",
+                    field_def.to_string(),
+                )
+            })?;
+        if fields.iter().any(|field| field.name == name) {
+            return Err(JankenError::new_parameter_type_mismatch(
+                "unique field names",
+                format!("duplicate field '{name}'"),
+            ));
+        }
+
+        let param_type = parse_field_type(field_def, name)?;
+
+        let mut field_constraints = ParameterConstraints::default();
+        parse_constraints(&mut field_constraints, field_def)?;
+        fields.push(crate::parameters::Parameter {
+            name: name.to_string(),
+            param_type,
+            constraints: field_constraints,
+        });
+    }
+    Ok(fields)
+}
+
+/// Parse the scalar "type" of a rows/tuple list field or a filter column; the type defaults to string
+pub(crate) fn parse_field_type(field_def: &serde_json::Value, name: &str) -> Result<ParameterType> {
+    let param_type = match field_def.get("type").and_then(|type_val| type_val.as_str()) {
+        Some(type_str) => ParameterType::from_str(type_str)?,
+        None => ParameterType::String,
+    };
+    if matches!(
+        param_type,
+        ParameterType::TableName
+            | ParameterType::List
+            | ParameterType::CommaList
+            | ParameterType::Rows
+            | ParameterType::Sort
+            | ParameterType::Filter
+            | ParameterType::Search
+            | ParameterType::FtsQuery
+    ) {
+        return Err(JankenError::new_parameter_type_mismatch(
+            "field type integer, string, float, boolean or blob",
+            format!("{param_type} for field {name}"),
+        ));
+    }
+    Ok(param_type)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,7 +1,7 @@
 use crate::{
-    parameter_constraints::ParameterConstraints,
+    parameter_constraints::{ParameterConstraints, item_field_values},
     result::{JankenError, Result},
-    str_utils::is_in_quotes,
+    str_utils::{is_in_quotes, looks_like_write_statement, quote_mask},
 };
 use regex::Regex;
 use std::str::FromStr;
@@ -18,6 +18,16 @@ pub static LIST_PARAMETER_REGEX: once_cell::sync::Lazy<Regex> = once_cell::sync:
 pub static COMMA_LIST_REGEX: once_cell::sync::Lazy<Regex> = once_cell::sync::Lazy::new(|| {
     Regex::new(r"~\[(\w+)\]").expect("COMMA_LIST_REGEX is a valid regex")
 });
+pub static ROWS_PARAMETER_REGEX: once_cell::sync::Lazy<Regex> = once_cell::sync::Lazy::new(|| {
+    Regex::new(r"\*\[(\w+)\]").expect("ROWS_PARAMETER_REGEX is a valid regex")
+});
+pub static SORT_PARAMETER_REGEX: once_cell::sync::Lazy<Regex> = once_cell::sync::Lazy::new(|| {
+    Regex::new(r"\^\[(\w+)\]").expect("SORT_PARAMETER_REGEX is a valid regex")
+});
+pub static FILTER_PARAMETER_REGEX: once_cell::sync::Lazy<Regex> =
+    once_cell::sync::Lazy::new(|| {
+        Regex::new(r"\?\[(\w+)\]").expect("FILTER_PARAMETER_REGEX is a valid regex")
+    });
 
 /// Parameter type enums for database operations
 #[derive(Debug, Clone, PartialEq)]
@@ -30,6 +40,11 @@ pub enum ParameterType {
     List,
     Blob,
     CommaList, // Array of table names joined with commas
+    Rows,      // Array of objects expanded to VALUES tuples
+    Sort,      // Array of sort keys expanded to an ORDER BY clause
+    Filter,    // Expression tree compiled to a boolean SQL expression
+    Search,    // Search term bound as an escaped LIKE pattern
+    FtsQuery,  // Free text bound as a full-text query for the target backend
 }
 
 impl FromStr for ParameterType {
@@ -44,8 +59,10 @@ impl FromStr for ParameterType {
             "table_name" => Ok(ParameterType::TableName),
             "list" => Ok(ParameterType::List),
             "blob" => Ok(ParameterType::Blob),
+            "search" => Ok(ParameterType::Search),
+            "fts_query" => Ok(ParameterType::FtsQuery),
             _ => Err(JankenError::new_parameter_type_mismatch(
-                "integer, string, float, boolean, table_name, list or blob",
+                "integer, string, float, boolean, table_name, list, blob, search or fts_query",
                 s,
             )),
         }
@@ -63,11 +80,101 @@ impl std::fmt::Display for ParameterType {
             ParameterType::List => "list",
             ParameterType::Blob => "blob",
             ParameterType::CommaList => "comma_list",
+            ParameterType::Rows => "rows",
+            ParameterType::Sort => "sort",
+            ParameterType::Filter => "filter",
+            ParameterType::Search => "search",
+            ParameterType::FtsQuery => "fts_query",
         };
         write!(f, "{s}")
     }
 }
 
+/// Where a search term may occur in the matched value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchMode {
+    /// Values starting with the term: `term%`
+    Prefix,
+    /// Values ending with the term: `%term`
+    Suffix,
+    /// Values containing the term: `%term%`
+    #[default]
+    Contains,
+}
+
+impl FromStr for SearchMode {
+    type Err = JankenError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "prefix" => Ok(SearchMode::Prefix),
+            "suffix" => Ok(SearchMode::Suffix),
+            "contains" => Ok(SearchMode::Contains),
+            _ => Err(JankenError::new_parameter_type_mismatch(
+                "search mode prefix, suffix or contains",
+                s,
+            )),
+        }
+    }
+}
+
+/// Escape character of the LIKE patterns built from search parameters
+/// (not a backslash, which the quote detection of SQL text treats as an escape)
+pub const LIKE_ESCAPE_CHAR: char = '!';
+
+/// Whether the `@param` at `start..end` of `sql` is the pattern of a LIKE or ILIKE operator
+/// (case-insensitive, outside quotes) that has no ESCAPE clause of its own yet
+pub(crate) fn is_escapable_like_pattern(
+    sql: &str,
+    in_quotes: &[bool],
+    start: usize,
+    end: usize,
+) -> bool {
+    let is_word_char = |ch: char| ch.is_ascii_alphanumeric() || ch == '_';
+    let before = sql[..start].trim_end();
+    let operator_start = before
+        .rfind(|ch: char| !is_word_char(ch))
+        .map_or(0, |pos| pos + 1);
+    let operator = &before[operator_start..];
+    let follows_like = (operator.eq_ignore_ascii_case("like")
+        || operator.eq_ignore_ascii_case("ilike"))
+        && !in_quotes[operator_start];
+
+    let after = sql[end..].trim_start();
+    let next_word_end = after
+        .find(|ch: char| !is_word_char(ch))
+        .unwrap_or(after.len());
+    let has_escape = after[..next_word_end].eq_ignore_ascii_case("escape");
+
+    follows_like && !has_escape
+}
+
+/// Escape the LIKE wildcards (`%`, `_`) and the escape character in a search term, then wrap
+/// the term in wildcards according to the mode
+pub fn like_pattern(term: &str, mode: SearchMode) -> String {
+    let mut escaped = String::with_capacity(term.len() + 2);
+    for ch in term.chars() {
+        if ch == '%' || ch == '_' || ch == LIKE_ESCAPE_CHAR {
+            escaped.push(LIKE_ESCAPE_CHAR);
+        }
+        escaped.push(ch);
+    }
+    match mode {
+        SearchMode::Prefix => format!("{escaped}%"),
+        SearchMode::Suffix => format!("%{escaped}"),
+        SearchMode::Contains => format!("%{escaped}%"),
+    }
+}
+
+/// Render a validated `name` or `schema.name` as a double-quoted identifier, which both SQLite and
+/// PostgreSQL accept; embedded double quotes are doubled
+pub fn quote_identifier(name: &str) -> String {
+    name.split('.')
+        .map(|part| format!("\"{}\"", part.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
 /// Parameter definition for SQL queries with validation constraints
 #[derive(Debug, Clone)]
 pub struct Parameter {
@@ -77,17 +184,23 @@ pub struct Parameter {
 }
 
 /// Parse parameters from SQL while respecting quote boundaries
-/// Extracts normal parameters (@param), table name parameters (#\[table\]), list parameters (:\[list\]), and comma list parameters (~\[param\]) from the SQL.
+/// Extracts normal parameters (@param), table name parameters (#\[table\]), list parameters (:\[list\]), comma list parameters (~\[param\]) and rows parameters (*\[rows\]) from the SQL.
 /// Returns combined results. If a name is used for multiple parameter types, an error is returned.
 /// For @params: type defaults to String but can be overridden by "args" JSON
 /// For #\[table\] names: type is always TableName (auto-detected), only constraints from "args" JSON are applied
 /// For :\[list\] parameters: type is always List (auto-detected), constraints from "args" JSON are applied
 /// For ~\[param\] parameters: type is always CommaList (auto-detected), constraints from "args" JSON are applied
+/// For *\[rows\] parameters: type is always Rows (auto-detected), the "fields" of each row come from "args" JSON
+/// For ^\[sort\] parameters: type is always Sort (auto-detected), the allowed columns come from the "enum" in "args" JSON
+/// For ?\[filter\] parameters: type is always Filter (auto-detected), the allowed "columns" come from "args" JSON
 pub fn parse_parameters_with_quotes(sql: &str) -> Result<Vec<Parameter>> {
     let param_names = extract_parameters_with_regex(sql, &PARAMETER_REGEX);
     let table_names = extract_parameters_with_regex(sql, &TABLE_NAME_REGEX);
     let list_names = extract_parameters_with_regex(sql, &LIST_PARAMETER_REGEX);
     let comma_list_names = extract_parameters_with_regex(sql, &COMMA_LIST_REGEX);
+    let rows_names = extract_parameters_with_regex(sql, &ROWS_PARAMETER_REGEX);
+    let sort_names = extract_parameters_with_regex(sql, &SORT_PARAMETER_REGEX);
+    let filter_names = extract_parameters_with_regex(sql, &FILTER_PARAMETER_REGEX);
 
     // Check for conflicts between parameter names
     for table_name in &table_names {
@@ -110,6 +223,38 @@ pub fn parse_parameters_with_quotes(sql: &str) -> Result<Vec<Parameter>> {
             ));
         }
     }
+    for rows_name in &rows_names {
+        if param_names.contains(rows_name)
+            || table_names.contains(rows_name)
+            || list_names.contains(rows_name)
+            || comma_list_names.contains(rows_name)
+        {
+            return Err(JankenError::new_parameter_name_conflict(rows_name.clone()));
+        }
+    }
+    for sort_name in &sort_names {
+        if param_names.contains(sort_name)
+            || table_names.contains(sort_name)
+            || list_names.contains(sort_name)
+            || comma_list_names.contains(sort_name)
+            || rows_names.contains(sort_name)
+        {
+            return Err(JankenError::new_parameter_name_conflict(sort_name.clone()));
+        }
+    }
+    for filter_name in &filter_names {
+        if param_names.contains(filter_name)
+            || table_names.contains(filter_name)
+            || list_names.contains(filter_name)
+            || comma_list_names.contains(filter_name)
+            || rows_names.contains(filter_name)
+            || sort_names.contains(filter_name)
+        {
+            return Err(JankenError::new_parameter_name_conflict(
+                filter_name.clone(),
+            ));
+        }
+    }
 
     let mut parameters = Vec::new();
 
@@ -149,6 +294,33 @@ pub fn parse_parameters_with_quotes(sql: &str) -> Result<Vec<Parameter>> {
         });
     }
 
+    // Add rows parameters (*[rows])
+    for name in &rows_names {
+        parameters.push(Parameter {
+            name: name.clone(),
+            param_type: ParameterType::Rows,
+            constraints: ParameterConstraints::default(),
+        });
+    }
+
+    // Add sort parameters (^[sort])
+    for name in &sort_names {
+        parameters.push(Parameter {
+            name: name.clone(),
+            param_type: ParameterType::Sort,
+            constraints: ParameterConstraints::default(),
+        });
+    }
+
+    // Add filter parameters (?[filter])
+    for name in &filter_names {
+        parameters.push(Parameter {
+            name: name.clone(),
+            param_type: ParameterType::Filter,
+            constraints: ParameterConstraints::default(),
+        });
+    }
+
     Ok(parameters)
 }
 
@@ -206,6 +378,13 @@ pub enum ParameterValue {
     Float(f64),
     Boolean(bool),
     Blob(Vec<u8>),
+    /// A whole list bound to a single placeholder as a typed array (PostgreSQL array binding)
+    Array {
+        item_type: ParameterType,
+        items: Vec<ParameterValue>,
+    },
+    /// Sanitized full-text search terms, bound as the dialect's query string
+    FtsQuery(crate::fts::FtsQuery),
 }
 
 /// Prepared statement with generic parameter values (database-agnostic)
@@ -213,6 +392,181 @@ pub enum ParameterValue {
 pub struct PreparedParameterStatement {
     pub sql: String,
     pub parameters: Vec<(String, ParameterValue)>,
+    /// Names of bound parameters whose args are marked `"sensitive": true` (list items included)
+    pub sensitive_parameters: std::collections::HashSet<String>,
+}
+
+/// SQL dialect a statement is converted to for execution or rendering
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// Named `:param` placeholders
+    Sqlite,
+    /// Positional `$n` placeholders
+    Postgresql,
+}
+
+impl Dialect {
+    /// Maximum number of values a single statement can bind: SQLite's default
+    /// `SQLITE_MAX_VARIABLE_NUMBER` and the 16-bit parameter count of the PostgreSQL protocol
+    pub fn max_host_parameters(self) -> usize {
+        match self {
+            Dialect::Sqlite => 32766,
+            Dialect::Postgresql => 65535,
+        }
+    }
+}
+
+/// Placeholder text rendered in place of sensitive values
+pub const REDACTED_LITERAL: &str = "'[REDACTED]'";
+
+impl ParameterValue {
+    /// Render the value as an escaped SQL literal for the given dialect
+    pub fn to_sql_literal(&self, dialect: Dialect) -> String {
+        match self {
+            ParameterValue::String(s) => format!("'{}'", s.replace('\'', "''")),
+            ParameterValue::Integer(i) => i.to_string(),
+            ParameterValue::Float(f) => f.to_string(),
+            ParameterValue::Boolean(b) => match dialect {
+                // SQLite stores booleans as integers
+                Dialect::Sqlite => (*b as i32).to_string(),
+                Dialect::Postgresql => b.to_string().to_uppercase(),
+            },
+            ParameterValue::Blob(bytes) => {
+                let hex = crate::encoding::encode_hex(bytes);
+                match dialect {
+                    Dialect::Sqlite => format!("X'{hex}'"),
+                    Dialect::Postgresql => format!("'\\x{hex}'::bytea"),
+                }
+            }
+            ParameterValue::Array { items, .. } if items.is_empty() => "'{}'".to_string(),
+            ParameterValue::Array { items, .. } => {
+                let literals: Vec<String> = items
+                    .iter()
+                    .map(|item| item.to_sql_literal(dialect))
+                    .collect();
+                format!("ARRAY[{}]", literals.join(", "))
+            }
+            ParameterValue::FtsQuery(query) => {
+                ParameterValue::String(query.to_query_string(dialect)).to_sql_literal(dialect)
+            }
+        }
+    }
+}
+
+/// A value bound to one placeholder of an executed statement
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundParameter {
+    /// Placeholder as it appears in the dialect SQL, e.g. `:id` or `$1`
+    pub placeholder: String,
+    pub value: ParameterValue,
+    /// Whether the value comes from an arg marked `"sensitive": true`
+    pub sensitive: bool,
+}
+
+/// A statement as sent to the database: dialect SQL with its bound values in placeholder order
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutedStatement {
+    pub sql: String,
+    pub dialect: Dialect,
+    pub parameters: Vec<BoundParameter>,
+}
+
+impl ExecutedStatement {
+    /// Render the SQL with bound values inlined as escaped literals, for logging and reproducing issues
+    /// Sensitive values are replaced with `'[REDACTED]'`. The output is meant for reading, not executing.
+    pub fn render_sql(&self) -> String {
+        let placeholder_regex = match self.dialect {
+            Dialect::Sqlite => &*SQLITE_PLACEHOLDER_REGEX,
+            Dialect::Postgresql => &*POSTGRESQL_PLACEHOLDER_REGEX,
+        };
+        placeholder_regex
+            .replace_all(&self.sql, |caps: &regex::Captures| {
+                let placeholder = caps.get(0).expect("regex capture group 0 always exists");
+                if is_in_quotes(&self.sql, placeholder.start()) {
+                    return placeholder.as_str().to_string();
+                }
+                match self
+                    .parameters
+                    .iter()
+                    .find(|bound| bound.placeholder == placeholder.as_str())
+                {
+                    Some(bound) if bound.sensitive => REDACTED_LITERAL.to_string(),
+                    Some(bound) => bound.value.to_sql_literal(self.dialect),
+                    None => placeholder.as_str().to_string(),
+                }
+            })
+            .to_string()
+    }
+}
+
+static SQLITE_PLACEHOLDER_REGEX: once_cell::sync::Lazy<Regex> = once_cell::sync::Lazy::new(|| {
+    Regex::new(r":\w+").expect("SQLITE_PLACEHOLDER_REGEX is a valid regex")
+});
+static POSTGRESQL_PLACEHOLDER_REGEX: once_cell::sync::Lazy<Regex> =
+    once_cell::sync::Lazy::new(|| {
+        Regex::new(r"\$\d+").expect("POSTGRESQL_PLACEHOLDER_REGEX is a valid regex")
+    });
+
+impl PreparedParameterStatement {
+    /// Convert `@param` placeholders (outside quotes) to the dialect's placeholder syntax
+    /// SQLite keeps the parameter names (`:param`); PostgreSQL numbers them (`$n`) in order of first appearance
+    pub fn to_executed_statement(&self, dialect: Dialect) -> ExecutedStatement {
+        let placeholder = |name: &str, position: usize| match dialect {
+            Dialect::Sqlite => format!(":{name}"),
+            Dialect::Postgresql => format!("${}", position + 1),
+        };
+
+        let values: std::collections::HashMap<&str, &ParameterValue> = self
+            .parameters
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+            .collect();
+        let in_quotes = quote_mask(&self.sql);
+
+        // Names of bound parameters in order of first appearance in the SQL
+        let mut bound_names: Vec<String> = Vec::new();
+        let mut positions: std::collections::HashMap<String, usize> =
+            std::collections::HashMap::new();
+        let sql = PARAMETER_REGEX
+            .replace_all(&self.sql, |caps: &regex::Captures| {
+                let full_match = caps.get(0).expect("regex capture group 0 always exists");
+                let name = caps
+                    .get(1)
+                    .expect("regex capture group 1 exists when group 0 matches")
+                    .as_str();
+                if !values.contains_key(name) || in_quotes[full_match.start()] {
+                    return full_match.as_str().to_string();
+                }
+                let position = *positions.entry(name.to_string()).or_insert_with(|| {
+                    bound_names.push(name.to_string());
+                    bound_names.len() - 1
+                });
+                placeholder(name, position)
+            })
+            .to_string();
+
+        let parameters = bound_names
+            .iter()
+            .enumerate()
+            .map(|(position, name)| BoundParameter {
+                placeholder: placeholder(name, position),
+                value: match values[name.as_str()] {
+                    // Full-text queries are bound as the query string of the dialect
+                    ParameterValue::FtsQuery(query) => {
+                        ParameterValue::String(query.to_query_string(dialect))
+                    }
+                    value => value.clone(),
+                },
+                sensitive: self.sensitive_parameters.contains(name),
+            })
+            .collect();
+
+        ExecutedStatement {
+            sql,
+            dialect,
+            parameters,
+        }
+    }
 }
 
 /// Convert a JSON value to a generic ParameterValue with type inference
@@ -254,7 +608,7 @@ pub fn json_value_to_parameter_value(
     param_type: &ParameterType,
 ) -> Result<ParameterValue> {
     match param_type {
-        ParameterType::String => {
+        ParameterType::String | ParameterType::Search => {
             let s = value.as_str().ok_or_else(|| {
                 JankenError::new_parameter_type_mismatch("string", value.to_string())
             })?;
@@ -298,12 +652,41 @@ pub fn json_value_to_parameter_value(
                 "comma-list parameter (should be expanded)",
             ))
         }
+        ParameterType::Rows => {
+            // Rows parameters are expanded separately, so we shouldn't convert them here
+            Err(JankenError::new_parameter_type_mismatch(
+                "non-rows parameter",
+                "rows parameter (should be expanded)",
+            ))
+        }
+        ParameterType::Sort => {
+            // Sort parameters are expanded separately, so we shouldn't convert them here
+            Err(JankenError::new_parameter_type_mismatch(
+                "non-sort parameter",
+                "sort parameter (should be expanded)",
+            ))
+        }
+        ParameterType::Filter => {
+            // Filter parameters are compiled separately, so we shouldn't convert them here
+            Err(JankenError::new_parameter_type_mismatch(
+                "non-filter parameter",
+                "filter parameter (should be compiled)",
+            ))
+        }
+        ParameterType::FtsQuery => Ok(ParameterValue::FtsQuery(crate::fts::validate_fts_query(
+            value,
+            &crate::fts::FtsOptions::default(),
+            None,
+        )?)),
         ParameterType::Blob => {
+            if let Some(bytes) = crate::encoding::decode_encoded_blob(value) {
+                return Ok(ParameterValue::Blob(bytes));
+            }
             let bytes = value
                 .as_array()
                 .ok_or_else(|| {
                     JankenError::new_parameter_type_mismatch(
-                        "array of byte values",
+                        "array of byte values or encoded blob",
                         value.to_string(),
                     )
                 })?
@@ -322,6 +705,176 @@ pub fn prepare_parameter_statement_generic(
     all_parameters: &[Parameter],
     request_params_obj: &serde_json::Map<String, serde_json::Value>,
 ) -> Result<PreparedParameterStatement> {
+    validate_parameters(all_parameters, request_params_obj)?;
+    expand_parameter_statement(statement_sql, all_parameters, request_params_obj)
+}
+
+/// Prepare a statement like `prepare_parameter_statement_generic`, splitting it into several
+/// statements when it would bind more than `max_parameters` values.
+/// The items of the chunked parameter are spread over consecutive statements that each stay within
+/// the limit. The chunked parameter is the first rows parameter (`*[rows]`), or for read statements
+/// the single list parameter (`:[list]`) when it declares a `chunk_size`, which also caps its items
+/// per statement. Other statements are returned whole.
+pub fn prepare_parameter_statement_chunks(
+    statement_sql: &str,
+    all_parameters: &[Parameter],
+    request_params_obj: &serde_json::Map<String, serde_json::Value>,
+    max_parameters: usize,
+) -> Result<Vec<PreparedParameterStatement>> {
+    let prepared =
+        prepare_parameter_statement_generic(statement_sql, all_parameters, request_params_obj)?;
+    let Some(chunked_name) = chunked_parameter_name(statement_sql, all_parameters) else {
+        return Ok(vec![prepared]);
+    };
+    let chunk_size = all_parameters
+        .iter()
+        .find(|param| param.name == chunked_name)
+        .and_then(|param| param.constraints.chunk_size)
+        .unwrap_or(usize::MAX);
+    let items = request_params_obj
+        .get(&chunked_name)
+        .and_then(|value| value.as_array())
+        .expect("parameter type already validated as Rows or List");
+    if items.len() < 2 || (prepared.parameters.len() <= max_parameters && items.len() <= chunk_size)
+    {
+        // A single item cannot be split: leave any limit error to the database
+        return Ok(vec![prepared]);
+    }
+
+    // Measure the statement with a single item to split the bound values into a fixed part
+    // and a per-item part
+    let mut chunk_params = request_params_obj.clone();
+    chunk_params.insert(
+        chunked_name.clone(),
+        serde_json::Value::Array(items[..1].to_vec()),
+    );
+    let single_item_count =
+        expand_parameter_statement(statement_sql, all_parameters, &chunk_params)?
+            .parameters
+            .len();
+    let per_item = (prepared.parameters.len() - single_item_count) / (items.len() - 1);
+    let fixed = single_item_count - per_item;
+    let items_per_chunk = (max_parameters.saturating_sub(fixed) / per_item.max(1)).min(chunk_size);
+    if items_per_chunk == 0 {
+        // Not even a single item fits: leave the limit error to the database as well
+        return Ok(vec![prepared]);
+    }
+
+    items
+        .chunks(items_per_chunk)
+        .map(|chunk| {
+            chunk_params.insert(
+                chunked_name.clone(),
+                serde_json::Value::Array(chunk.to_vec()),
+            );
+            expand_parameter_statement(statement_sql, all_parameters, &chunk_params)
+        })
+        .collect()
+}
+
+/// `IN :[list]` / `NOT IN :[list]` comparisons, rewritten to array operators for pg_array lists
+static LIST_IN_REGEX: once_cell::sync::Lazy<Regex> = once_cell::sync::Lazy::new(|| {
+    Regex::new(r"(?i)\b(NOT\s+)?IN\s*:\[(\w+)\]").expect("LIST_IN_REGEX is a valid regex")
+});
+
+/// Rewrite the `:[list]` placeholders of pg_array list parameters so the whole list binds as one array:
+/// `IN :[ids]` becomes `= ANY(@ids)`, `NOT IN :[ids]` becomes `<> ALL(@ids)` and any other use becomes `@ids`
+pub(crate) fn bind_list_arrays<'a>(
+    statement_sql: &'a str,
+    all_parameters: &[crate::parameters::Parameter],
+) -> std::borrow::Cow<'a, str> {
+    let is_array_list = |name: &str| {
+        all_parameters.iter().any(|param| {
+            param.name == name
+                && param.param_type == ParameterType::List
+                && param.constraints.pg_array
+        })
+    };
+    if !all_parameters
+        .iter()
+        .any(|param| param.constraints.pg_array)
+    {
+        return std::borrow::Cow::Borrowed(statement_sql);
+    }
+
+    let mask = quote_mask(statement_sql);
+    let rewritten = LIST_IN_REGEX.replace_all(statement_sql, |cap: &regex::Captures| {
+        let whole = &cap[0];
+        let name = &cap[2];
+        if mask[cap.get(0).expect("capture group 0 always exists").start()] || !is_array_list(name)
+        {
+            return whole.to_string();
+        }
+        match cap.get(1) {
+            Some(_) => format!("<> ALL(@{name})"),
+            None => format!("= ANY(@{name})"),
+        }
+    });
+
+    let mask = quote_mask(&rewritten);
+    let rewritten = LIST_PARAMETER_REGEX.replace_all(&rewritten, |cap: &regex::Captures| {
+        let name = &cap[1];
+        if mask[cap.get(0).expect("capture group 0 always exists").start()] || !is_array_list(name)
+        {
+            return cap[0].to_string();
+        }
+        format!("@{name}")
+    });
+    std::borrow::Cow::Owned(rewritten.into_owned())
+}
+
+/// Prepare a statement for execution in `dialect`, split like `prepare_parameter_statement_chunks`
+/// at the dialect's parameter limit. For PostgreSQL, pg_array lists are first rewritten to bind
+/// whole (see `bind_list_arrays`), so runners and `QueryDefinitions::validate` agree on the SQL.
+pub fn prepare_dialect_statement_chunks(
+    statement_sql: &str,
+    all_parameters: &[Parameter],
+    request_params_obj: &serde_json::Map<String, serde_json::Value>,
+    dialect: Dialect,
+) -> Result<Vec<PreparedParameterStatement>> {
+    let statement_sql = match dialect {
+        Dialect::Sqlite => std::borrow::Cow::Borrowed(statement_sql),
+        Dialect::Postgresql => bind_list_arrays(statement_sql, all_parameters),
+    };
+    prepare_parameter_statement_chunks(
+        &statement_sql,
+        all_parameters,
+        request_params_obj,
+        dialect.max_host_parameters(),
+    )
+}
+
+/// The parameter whose items `prepare_parameter_statement_chunks` may spread over several statements
+fn chunked_parameter_name(statement_sql: &str, all_parameters: &[Parameter]) -> Option<String> {
+    if let Some(rows_name) = extract_parameters_with_regex(statement_sql, &ROWS_PARAMETER_REGEX)
+        .into_iter()
+        .next()
+    {
+        return Some(rows_name);
+    }
+    // Splitting a list is only transparent for reads: each chunk selects a disjoint subset.
+    // Even then ORDER BY, LIMIT, DISTINCT and aggregates apply per chunk, so lists are only
+    // split when the definition opts in with a chunk_size.
+    if looks_like_write_statement(statement_sql) {
+        return None;
+    }
+    match extract_parameters_with_regex(statement_sql, &LIST_PARAMETER_REGEX).as_slice() {
+        [list_name]
+            if all_parameters.iter().any(|param| {
+                param.name == *list_name && param.constraints.chunk_size.is_some()
+            }) =>
+        {
+            Some(list_name.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Validate every parameter of the query against its type and constraints
+fn validate_parameters(
+    all_parameters: &[Parameter],
+    request_params_obj: &serde_json::Map<String, serde_json::Value>,
+) -> Result<()> {
     // Validate all parameters first to ensure consistency and prevent SQL injection
     for param_def in all_parameters {
         let value = request_params_obj
@@ -336,9 +889,18 @@ pub fn prepare_parameter_statement_generic(
             request_params_obj,
         )?;
     }
+    Ok(())
+}
 
+/// Expand the already validated parameters of a statement into bound values and SQL text
+fn expand_parameter_statement(
+    statement_sql: &str,
+    all_parameters: &[Parameter],
+    request_params_obj: &serde_json::Map<String, serde_json::Value>,
+) -> Result<PreparedParameterStatement> {
     let mut prepared_sql = statement_sql.to_string();
     let mut parameters = Vec::new();
+    let mut sensitive_parameters = std::collections::HashSet::new();
 
     // Convert JSON parameter values to generic ParameterValue types
     let statement_param_names = extract_parameters_with_regex(&prepared_sql, &PARAMETER_REGEX);
@@ -352,8 +914,61 @@ pub fn prepare_parameter_statement_generic(
             .find(|p| p.name == *param_name)
             .ok_or_else(|| JankenError::new_parameter_not_provided(param_name.clone()))?;
 
-        let generic_value = json_value_to_parameter_value(param_value, &param_def.param_type)?;
+        // A list referenced as @name is bound whole (see the PostgreSQL array binding rewrite)
+        let generic_value = match param_def.param_type {
+            ParameterType::List => list_array_value(param_value, param_def)?,
+            ParameterType::Search => ParameterValue::String(like_pattern(
+                param_value
+                    .as_str()
+                    .expect("parameter type already validated as Search"),
+                param_def.constraints.search_mode.unwrap_or_default(),
+            )),
+            ParameterType::FtsQuery => ParameterValue::FtsQuery(crate::fts::validate_fts_query(
+                param_value,
+                &param_def.constraints.fts.clone().unwrap_or_default(),
+                None,
+            )?),
+            _ => json_value_to_parameter_value(param_value, &param_def.param_type)?,
+        };
         parameters.push((param_name.clone(), generic_value));
+        if param_def.constraints.sensitive {
+            sensitive_parameters.insert(param_name.clone());
+        }
+    }
+
+    // Search patterns are escaped with LIKE_ESCAPE_CHAR, so each LIKE declares it: LIKE @q ESCAPE '!'
+    // (QueryDef::from_sql only accepts search parameters in that position)
+    let search_names: Vec<&str> = all_parameters
+        .iter()
+        .filter(|param| {
+            param.param_type == ParameterType::Search && statement_param_names.contains(&param.name)
+        })
+        .map(|param| param.name.as_str())
+        .collect();
+    if !search_names.is_empty() {
+        let in_quotes = quote_mask(&prepared_sql);
+        prepared_sql = PARAMETER_REGEX
+            .replace_all(&prepared_sql, |caps: &regex::Captures| {
+                let full_match = caps.get(0).expect("regex capture group 0 always exists");
+                let name = caps
+                    .get(1)
+                    .expect("regex capture group 1 exists when group 0 matches")
+                    .as_str();
+                if search_names.contains(&name)
+                    && !in_quotes[full_match.start()]
+                    && is_escapable_like_pattern(
+                        &prepared_sql,
+                        &in_quotes,
+                        full_match.start(),
+                        full_match.end(),
+                    )
+                {
+                    format!("{} ESCAPE '{LIKE_ESCAPE_CHAR}'", full_match.as_str())
+                } else {
+                    full_match.as_str().to_string()
+                }
+            })
+            .to_string();
     }
 
     // Handle table name replacement (#\[table_name\])
@@ -368,8 +983,13 @@ pub fn prepare_parameter_statement_generic(
             let table_name_str = table_name_value
                 .as_str()
                 .expect("parameter type already validated as TableName at function start");
+            let table_name = if identifier_is_quoted(all_parameters, param_name) {
+                quote_identifier(table_name_str)
+            } else {
+                table_name_str.to_string()
+            };
             prepared_sql = TABLE_NAME_REGEX
-                .replace(&prepared_sql, table_name_str)
+                .replace(&prepared_sql, regex::NoExpand(&table_name))
                 .to_string();
         }
     }
@@ -389,6 +1009,10 @@ pub fn prepare_parameter_statement_generic(
                 .as_array()
                 .expect("parameter type already validated as List at function start");
 
+            let is_sensitive = all_parameters
+                .iter()
+                .any(|p| p.name == list_param_name && p.constraints.sensitive);
+
             if list_array.is_empty() {
                 return Err(JankenError::new_parameter_type_mismatch(
                     "non-empty list",
@@ -396,6 +1020,24 @@ pub fn prepare_parameter_statement_generic(
                 ));
             }
 
+            // Tuple lists expand to nested placeholder tuples: ((@keys_0_a, @keys_0_b), ...)
+            if let Some(param_def) = all_parameters
+                .iter()
+                .find(|p| p.name == list_param_name && p.constraints.fields.is_some())
+            {
+                let tuples = expand_field_tuples(
+                    param_def,
+                    list_array,
+                    true,
+                    &mut parameters,
+                    &mut sensitive_parameters,
+                )?;
+                prepared_sql = LIST_PARAMETER_REGEX
+                    .replace(&prepared_sql, format!("({})", tuples.join(", ")))
+                    .to_string();
+                continue;
+            }
+
             // Create positional placeholders and values
             // For list items, we infer the type from the JSON value itself
             let mut placeholders = Vec::new();
@@ -405,6 +1047,9 @@ pub fn prepare_parameter_statement_generic(
 
                 // Convert JSON value to generic ParameterValue with type inference
                 let generic_value = json_value_to_parameter_value_inferred(item)?;
+                if is_sensitive {
+                    sensitive_parameters.insert(param_key.clone());
+                }
                 parameters.push((param_key, generic_value));
             }
 
@@ -416,6 +1061,101 @@ pub fn prepare_parameter_statement_generic(
         }
     }
 
+    // Handle rows parameter expansion (*\[rows\])
+    for cap in ROWS_PARAMETER_REGEX.captures_iter(&prepared_sql.clone()) {
+        if let Some(full_match) = cap.get(0)
+            && is_in_quotes(&prepared_sql, full_match.start())
+        {
+            continue;
+        }
+
+        if let Some(param_name_match) = cap.get(1) {
+            let rows_param_name = param_name_match.as_str();
+
+            let param_def = all_parameters
+                .iter()
+                .find(|p| p.name == rows_param_name)
+                .ok_or_else(|| JankenError::new_parameter_not_provided(rows_param_name))?;
+
+            let rows_array = request_params_obj
+                .get(rows_param_name)
+                .and_then(|value| value.as_array())
+                .expect("parameter type already validated as Rows at function start");
+
+            if rows_array.is_empty() {
+                return Err(JankenError::new_parameter_type_mismatch(
+                    "non-empty rows",
+                    "empty array",
+                ));
+            }
+
+            let tuples = expand_field_tuples(
+                param_def,
+                rows_array,
+                false,
+                &mut parameters,
+                &mut sensitive_parameters,
+            )?;
+            prepared_sql = ROWS_PARAMETER_REGEX
+                .replace(&prepared_sql, tuples.join(", "))
+                .to_string();
+        }
+    }
+
+    // Handle filter parameter compilation (?[filter]) into a parenthesized boolean expression
+    for cap in FILTER_PARAMETER_REGEX.captures_iter(&prepared_sql.clone()) {
+        if let Some(full_match) = cap.get(0)
+            && is_in_quotes(&prepared_sql, full_match.start())
+        {
+            continue;
+        }
+
+        if let Some(param_name_match) = cap.get(1) {
+            let filter_param_name = param_name_match.as_str();
+            let param_def = all_parameters
+                .iter()
+                .find(|p| p.name == filter_param_name)
+                .ok_or_else(|| JankenError::new_parameter_not_provided(filter_param_name))?;
+            let filter_value = request_params_obj
+                .get(filter_param_name)
+                .expect("parameter presence already validated at function start");
+
+            let compiled = crate::filter::compile_filter(
+                filter_param_name,
+                &param_def.constraints,
+                filter_value,
+                request_params_obj,
+            )?;
+            sensitive_parameters.extend(compiled.sensitive_parameters);
+            parameters.extend(compiled.parameters);
+            prepared_sql = FILTER_PARAMETER_REGEX
+                .replace(
+                    &prepared_sql,
+                    regex::NoExpand(&format!("({})", compiled.sql)),
+                )
+                .to_string();
+        }
+    }
+
+    // Handle sort parameter expansion (^[sort]) into a complete ORDER BY clause
+    for cap in SORT_PARAMETER_REGEX.captures_iter(&prepared_sql.clone()) {
+        if let Some(full_match) = cap.get(0)
+            && is_in_quotes(&prepared_sql, full_match.start())
+        {
+            continue;
+        }
+
+        if let Some(param_name_match) = cap.get(1) {
+            let sort_keys = request_params_obj
+                .get(param_name_match.as_str())
+                .and_then(|value| value.as_array())
+                .expect("parameter type already validated as Sort at function start");
+            prepared_sql = SORT_PARAMETER_REGEX
+                .replace(&prepared_sql, regex::NoExpand(&order_by_clause(sort_keys)))
+                .to_string();
+        }
+    }
+
     // Handle comma list parameter replacement (~[param])
     for cap in COMMA_LIST_REGEX.captures_iter(&prepared_sql.clone()) {
         if let Some(full_match) = cap.get(0) {
@@ -445,12 +1185,17 @@ pub fn prepare_parameter_statement_generic(
             }
 
             // Validate that all elements are strings (table names)
+            let quoted = identifier_is_quoted(all_parameters, comma_list_param_name);
             let mut table_names = Vec::new();
             for item in comma_list_array {
                 let table_name = item
                     .as_str()
                     .expect("CommaList items already validated as strings at function start");
-                table_names.push(table_name);
+                table_names.push(if quoted {
+                    quote_identifier(table_name)
+                } else {
+                    table_name.to_string()
+                });
             }
 
             // Join table names with comma and replace
@@ -464,9 +1209,85 @@ pub fn prepare_parameter_statement_generic(
     Ok(PreparedParameterStatement {
         sql: prepared_sql,
         parameters,
+        sensitive_parameters,
     })
 }
 
+/// Whether the #[table] or ~[list] parameter with the given name is declared with `"quote": true`
+fn identifier_is_quoted(all_parameters: &[Parameter], name: &str) -> bool {
+    all_parameters
+        .iter()
+        .any(|param| param.name == name && param.constraints.quote)
+}
+
+/// Render validated sort keys (`{"field": ..., "dir": ..., "nulls": ...}`) as an ORDER BY clause;
+/// no sort keys render no clause at all
+fn order_by_clause(sort_keys: &[serde_json::Value]) -> String {
+    if sort_keys.is_empty() {
+        return String::new();
+    }
+    let terms: Vec<String> = sort_keys
+        .iter()
+        .map(|key| {
+            let field = key["field"]
+                .as_str()
+                .expect("sort field already validated against the column allowlist");
+            let dir = key["dir"].as_str().unwrap_or("asc").to_uppercase();
+            match key["nulls"].as_str() {
+                Some(nulls) => format!("{field} {dir} NULLS {}", nulls.to_uppercase()),
+                None => format!("{field} {dir}"),
+            }
+        })
+        .collect();
+    format!("ORDER BY {}", terms.join(", "))
+}
+
+/// Convert a list parameter value to one typed array value, its items converted with `itemtype`
+fn list_array_value(value: &serde_json::Value, param_def: &Parameter) -> Result<ParameterValue> {
+    let item_type = param_def.constraints.item_type.clone().ok_or_else(|| {
+        JankenError::new_parameter_type_mismatch(
+            "itemtype for list bound as an array",
+            param_def.name.clone(),
+        )
+    })?;
+    let items = value
+        .as_array()
+        .ok_or_else(|| JankenError::new_parameter_type_mismatch("list", value.to_string()))?
+        .iter()
+        .map(|item| json_value_to_parameter_value(item, &item_type))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(ParameterValue::Array { item_type, items })
+}
+
+/// Expand the items of a rows or tuple list parameter into one placeholder tuple per item,
+/// with the fields in the order of the "fields" definition: (@name_0_a, @name_0_b)
+fn expand_field_tuples(
+    param_def: &Parameter,
+    items: &[serde_json::Value],
+    accept_arrays: bool,
+    parameters: &mut Vec<(String, ParameterValue)>,
+    sensitive_parameters: &mut std::collections::HashSet<String>,
+) -> Result<Vec<String>> {
+    let fields = param_def.constraints.fields.as_deref().unwrap_or_default();
+    let mut tuples = Vec::with_capacity(items.len());
+    for (i, item) in items.iter().enumerate() {
+        let values = item_field_values(item, fields, &param_def.name, i, accept_arrays)?;
+        let mut placeholders = Vec::with_capacity(fields.len());
+        for (field, field_value) in fields.iter().zip(values) {
+            let param_key = format!("{}_{i}_{}", param_def.name, field.name);
+            placeholders.push(format!("@{param_key}"));
+
+            let generic_value = json_value_to_parameter_value(field_value, &field.param_type)?;
+            if param_def.constraints.sensitive || field.constraints.sensitive {
+                sensitive_parameters.insert(param_key.clone());
+            }
+            parameters.push((param_key, generic_value));
+        }
+        tuples.push(format!("({})", placeholders.join(", ")));
+    }
+    Ok(tuples)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -854,4 +1675,193 @@ mod tests {
             Err(JankenError::ParameterTypeMismatch { .. })
         ));
     }
+
+    #[test]
+    fn test_prepare_parameter_statement_chunks_splits_rows() {
+        let sql =
+            "INSERT INTO items (batch, a, b) SELECT @batch, column1, column2 FROM (VALUES *[rows])";
+        let field = |name: &str| Parameter {
+            name: name.to_string(),
+            param_type: ParameterType::Integer,
+            constraints: ParameterConstraints::default(),
+        };
+        let parameters = vec![
+            Parameter {
+                name: "batch".to_string(),
+                param_type: ParameterType::Integer,
+                constraints: ParameterConstraints::default(),
+            },
+            Parameter {
+                name: "rows".to_string(),
+                param_type: ParameterType::Rows,
+                constraints: ParameterConstraints {
+                    fields: Some(vec![field("a"), field("b")]),
+                    ..Default::default()
+                },
+            },
+        ];
+        let rows: Vec<_> = (0..5).map(|i| json!({"a": i, "b": i * 10})).collect();
+        let request_params = json!({"batch": 7, "rows": rows})
+            .as_object()
+            .unwrap()
+            .clone();
+
+        // 1 fixed value + 2 per row: a limit of 6 fits 2 rows per statement
+        let chunks =
+            prepare_parameter_statement_chunks(sql, &parameters, &request_params, 6).unwrap();
+        let row_counts: Vec<usize> = chunks
+            .iter()
+            .map(|c| (c.parameters.len() - 1) / 2)
+            .collect();
+        assert_eq!(row_counts, vec![2, 2, 1]);
+        assert!(chunks[2].sql.ends_with("(VALUES (@rows_0_a, @rows_0_b))"));
+        assert_eq!(
+            chunks[2].parameters[1],
+            ("rows_0_a".to_string(), ParameterValue::Integer(4))
+        );
+
+        // Statements within the limit are returned whole
+        let chunks =
+            prepare_parameter_statement_chunks(sql, &parameters, &request_params, 11).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].parameters.len(), 11);
+    }
+
+    #[test]
+    fn test_prepare_parameter_statement_chunks_splits_read_lists() {
+        let list = |chunk_size: Option<usize>| {
+            vec![
+                Parameter {
+                    name: "owner".to_string(),
+                    param_type: ParameterType::Integer,
+                    constraints: ParameterConstraints::default(),
+                },
+                Parameter {
+                    name: "ids".to_string(),
+                    param_type: ParameterType::List,
+                    constraints: ParameterConstraints {
+                        item_type: Some(ParameterType::Integer),
+                        chunk_size,
+                        ..Default::default()
+                    },
+                },
+            ]
+        };
+        let request_params = json!({"owner": 1, "ids": [1, 2, 3, 4, 5]})
+            .as_object()
+            .unwrap()
+            .clone();
+        let item_counts = |chunks: Vec<PreparedParameterStatement>| -> Vec<usize> {
+            chunks.iter().map(|c| c.parameters.len() - 1).collect()
+        };
+
+        // Without chunk_size, a list over the limit is left whole for the database to reject
+        let sql = "SELECT * FROM items WHERE owner = @owner AND id IN :[ids]";
+        let chunks =
+            prepare_parameter_statement_chunks(sql, &list(None), &request_params, 3).unwrap();
+        assert_eq!(item_counts(chunks), vec![5]);
+
+        // 1 fixed value + 1 per item: a limit of 3 fits 2 items per statement
+        let chunks =
+            prepare_parameter_statement_chunks(sql, &list(Some(100)), &request_params, 3).unwrap();
+        assert_eq!(
+            chunks[2].sql,
+            "SELECT * FROM items WHERE owner = @owner AND id IN (@ids_0)"
+        );
+        assert_eq!(item_counts(chunks), vec![2, 2, 1]);
+
+        // chunk_size splits lists within the limit, and caps the chunks over it
+        let chunks =
+            prepare_parameter_statement_chunks(sql, &list(Some(4)), &request_params, 100).unwrap();
+        assert_eq!(item_counts(chunks), vec![4, 1]);
+        let chunks =
+            prepare_parameter_statement_chunks(sql, &list(Some(1)), &request_params, 3).unwrap();
+        assert_eq!(item_counts(chunks), vec![1, 1, 1, 1, 1]);
+
+        // Write statements are never split on a list
+        let sql = "DELETE FROM items WHERE owner = @owner AND id IN :[ids]";
+        let chunks =
+            prepare_parameter_statement_chunks(sql, &list(Some(1)), &request_params, 3).unwrap();
+        assert_eq!(item_counts(chunks), vec![5]);
+    }
+
+    #[test]
+    fn test_to_executed_statement_postgresql_numbers_by_appearance() {
+        let sql = "SELECT '@name' AS label FROM users WHERE name = @name AND id IN :[ids] OR alias = @name";
+        let parameters = vec![
+            Parameter {
+                name: "name".to_string(),
+                param_type: ParameterType::String,
+                constraints: ParameterConstraints::default(),
+            },
+            Parameter {
+                name: "ids".to_string(),
+                param_type: ParameterType::List,
+                constraints: ParameterConstraints::default(),
+            },
+        ];
+        let ids: Vec<i64> = (0..11).collect();
+        let request_params = json!({"name": "o'neil", "ids": ids})
+            .as_object()
+            .unwrap()
+            .clone();
+
+        let prepared =
+            prepare_parameter_statement_generic(sql, &parameters, &request_params).unwrap();
+        let executed = prepared.to_executed_statement(Dialect::Postgresql);
+
+        // @ids_1 must not clobber @ids_10, quoted text is left alone and repeats reuse $1
+        assert_eq!(
+            executed.sql,
+            "SELECT '@name' AS label FROM users WHERE name = $1 AND id IN ($2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12) OR alias = $1"
+        );
+        assert_eq!(executed.parameters.len(), 12);
+        assert_eq!(
+            executed.parameters[11],
+            BoundParameter {
+                placeholder: "$12".to_string(),
+                value: ParameterValue::Integer(10),
+                sensitive: false,
+            }
+        );
+        assert_eq!(
+            executed.render_sql(),
+            "SELECT '@name' AS label FROM users WHERE name = 'o''neil' AND id IN (0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10) OR alias = 'o''neil'"
+        );
+    }
+
+    #[test]
+    fn test_render_sql_sqlite_literals_and_redaction() {
+        let prepared = PreparedParameterStatement {
+            sql: "INSERT INTO t VALUES (@s, @i, @f, @b, @data, @secret, ':s')".to_string(),
+            parameters: vec![
+                ("s".to_string(), ParameterValue::String("it's".to_string())),
+                ("i".to_string(), ParameterValue::Integer(-3)),
+                ("f".to_string(), ParameterValue::Float(1.5)),
+                ("b".to_string(), ParameterValue::Boolean(true)),
+                ("data".to_string(), ParameterValue::Blob(vec![0, 171])),
+                (
+                    "secret".to_string(),
+                    ParameterValue::String("pw".to_string()),
+                ),
+            ],
+            sensitive_parameters: ["secret".to_string()].into_iter().collect(),
+        };
+
+        let executed = prepared.to_executed_statement(Dialect::Sqlite);
+        assert_eq!(
+            executed.sql,
+            "INSERT INTO t VALUES (:s, :i, :f, :b, :data, :secret, ':s')"
+        );
+        assert_eq!(
+            executed.render_sql(),
+            "INSERT INTO t VALUES ('it''s', -3, 1.5, 1, X'00ab', '[REDACTED]', ':s')"
+        );
+
+        let executed = prepared.to_executed_statement(Dialect::Postgresql);
+        assert_eq!(
+            executed.render_sql(),
+            "INSERT INTO t VALUES ('it''s', -3, 1.5, TRUE, '\\x00ab'::bytea, '[REDACTED]', ':s')"
+        );
+    }
 }
@@ -1,5 +1,5 @@
 pub mod query_def;
 pub mod query_definitions;
 
-pub use query_def::{QueryDef, ReturnsSpec};
+pub use query_def::{QueryDef, ReturnsSpec, RowLimit, RowLimitAction};
 pub use query_definitions::QueryDefinitions;
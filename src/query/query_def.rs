@@ -1,8 +1,12 @@
 use crate::{
     ParameterType,
+    encoding::BlobEncoding,
+    pagination::Pagination,
     parameter_constraints::parse_constraints,
     parameters::{self, Parameter},
     result::{JankenError, Result},
+    str_utils::{quote_mask, split_sql_statements},
+    transaction::TransactionOptions,
 };
 use std::str::FromStr;
 
@@ -13,6 +17,131 @@ pub enum ReturnsSpec {
     Static(Vec<String>),
     /// Dynamic reference to a comma_list parameter: ~[param_name]
     Dynamic(String), // Stores the parameter name (without ~[])
+    /// One returns specification per statement of a multi-statement query (aligned with `split_sql_statements`)
+    PerStatement(Vec<ReturnsSpec>),
+}
+
+impl ReturnsSpec {
+    /// Resolve the returns specification to actual field names for a request
+    pub fn resolve(
+        &self,
+        request_params_obj: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<Vec<String>> {
+        match self {
+            ReturnsSpec::Static(fields) => Ok(fields.clone()),
+            ReturnsSpec::Dynamic(param_name) => {
+                // Get the comma_list parameter value
+                let param_value = request_params_obj
+                    .get(param_name)
+                    .ok_or_else(|| JankenError::new_parameter_not_provided(param_name.clone()))?;
+
+                let fields_array = param_value.as_array().ok_or_else(|| {
+                    JankenError::new_parameter_type_mismatch(
+                        "array for comma_list parameter",
+                        param_value.to_string(),
+                    )
+                })?;
+
+                // Convert to vector of strings
+                let fields: Vec<String> = fields_array
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|s| s.to_string())
+                    .collect();
+
+                Ok(fields)
+            }
+            ReturnsSpec::PerStatement(_) => Err(JankenError::new_parameter_type_mismatch(
+                "single returns specification",
+                "per-statement returns",
+            )),
+        }
+    }
+}
+
+/// What a read query does once it has more rows than its `max_rows`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RowLimitAction {
+    /// Fail with a `RowLimitExceeded` error
+    #[default]
+    Error,
+    /// Stop reading and return the first `max_rows` rows with `truncated` set
+    Truncate,
+}
+
+impl FromStr for RowLimitAction {
+    type Err = JankenError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(RowLimitAction::Error),
+            "truncate" => Ok(RowLimitAction::Truncate),
+            _ => Err(JankenError::new_parameter_type_mismatch(
+                "max_rows on_exceed error or truncate",
+                s,
+            )),
+        }
+    }
+}
+
+/// Upper bound on the rows a read query collects into a `QueryResult`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RowLimit {
+    pub max_rows: usize,
+    pub on_exceed: RowLimitAction,
+}
+
+impl RowLimit {
+    /// Parse the `max_rows` field of a query definition:
+    /// `1000`, or `{"rows": 1000, "on_exceed": "truncate"}` (`on_exceed` defaults to error)
+    pub fn from_json(query_name: &str, value: &serde_json::Value) -> Result<Self> {
+        let limit_error = || {
+            JankenError::new_parameter_type_mismatch(
+                "positive integer or {\"rows\", \"on_exceed\"} object for max_rows",
+                format!("{query_name}: {value}"),
+            )
+        };
+        let positive = |rows: &serde_json::Value| {
+            rows.as_u64()
+                .filter(|rows| *rows > 0)
+                .map(|rows| rows as usize)
+        };
+
+        if let Some(max_rows) = positive(value) {
+            return Ok(RowLimit {
+                max_rows,
+                on_exceed: RowLimitAction::default(),
+            });
+        }
+        let map = value
+            .as_object()
+            .filter(|map| map.keys().all(|key| key == "rows" || key == "on_exceed"))
+            .ok_or_else(limit_error)?;
+        let max_rows = map.get("rows").and_then(positive).ok_or_else(limit_error)?;
+        let on_exceed = match map.get("on_exceed") {
+            Some(action_val) => {
+                RowLimitAction::from_str(action_val.as_str().ok_or_else(|| {
+                    JankenError::new_parameter_type_mismatch(
+                        "max_rows on_exceed error or truncate",
+                        action_val.to_string(),
+                    )
+                })?)?
+            }
+            None => RowLimitAction::default(),
+        };
+        Ok(RowLimit {
+            max_rows,
+            on_exceed,
+        })
+    }
+
+    /// Called when a statement has a row beyond the limit: an error, or Ok to truncate
+    pub(crate) fn exceeded(&self, sql: &str) -> Result<()> {
+        match self.on_exceed {
+            RowLimitAction::Error => Err(JankenError::new_row_limit_exceeded(self.max_rows, sql)),
+            RowLimitAction::Truncate => Ok(()),
+        }
+    }
 }
 
 /// Represents a parsed SQL query with parameters
@@ -21,6 +150,16 @@ pub struct QueryDef {
     pub sql: String,
     pub parameters: Vec<Parameter>,
     pub returns: ReturnsSpec,
+    /// How BLOB/BYTEA columns are represented in returned rows
+    pub blob_encoding: BlobEncoding,
+    /// Whether the query is declared read-only; the runners refuse statements that write
+    pub readonly: bool,
+    /// Options the convenience entry points apply when they open the transaction
+    pub transaction: TransactionOptions,
+    /// Page selection applied to the query's rows, driven by the `page` request parameter
+    pub paginate: Option<Pagination>,
+    /// Most rows a read statement collects before failing or truncating
+    pub max_rows: Option<RowLimit>,
 }
 
 impl QueryDef {
@@ -38,14 +177,76 @@ impl QueryDef {
         for param in &mut parameters {
             Self::process_parameter_with_args(param, &augmented_args)?;
         }
+        Self::check_search_placements(sql, &parameters)?;
 
         Ok(QueryDef {
             sql: sql.to_string(),
             parameters,
             returns: ReturnsSpec::Static(Vec::new()),
+            blob_encoding: BlobEncoding::default(),
+            readonly: false,
+            transaction: TransactionOptions::default(),
+            paginate: None,
+            max_rows: None,
         })
     }
 
+    /// The statements the runners execute for a request, mirroring how they split the SQL:
+    /// the whole SQL for queries with returns, split statements for multi-statement
+    /// mutations and for per-statement returns
+    pub fn statement_sqls(
+        &self,
+        request_params_obj: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<Vec<String>> {
+        match &self.returns {
+            ReturnsSpec::PerStatement(statement_returns) => {
+                for returns_spec in statement_returns {
+                    returns_spec.resolve(request_params_obj)?;
+                }
+                Ok(split_sql_statements(&self.sql))
+            }
+            returns_spec => {
+                let returns_fields = returns_spec.resolve(request_params_obj)?;
+                if returns_fields.is_empty() && self.sql.contains(';') {
+                    Ok(split_sql_statements(&self.sql))
+                } else {
+                    Ok(vec![self.sql.clone()])
+                }
+            }
+        }
+    }
+
+    /// Search patterns are escaped with their own ESCAPE clause, so a search parameter may only
+    /// be the pattern of a LIKE or ILIKE that does not already declare one
+    fn check_search_placements(sql: &str, parameters: &[Parameter]) -> Result<()> {
+        let in_quotes = quote_mask(sql);
+        for cap in parameters::PARAMETER_REGEX.captures_iter(sql) {
+            let full_match = cap.get(0).expect("regex capture group 0 always exists");
+            let name = cap
+                .get(1)
+                .expect("regex capture group 1 exists when group 0 matches")
+                .as_str();
+            let is_search = parameters
+                .iter()
+                .any(|param| param.name == name && param.param_type == ParameterType::Search);
+            if is_search
+                && !in_quotes[full_match.start()]
+                && !parameters::is_escapable_like_pattern(
+                    sql,
+                    &in_quotes,
+                    full_match.start(),
+                    full_match.end(),
+                )
+            {
+                return Err(JankenError::new_parameter_type_mismatch(
+                    "search parameter as the pattern of LIKE or ILIKE without an ESCAPE clause",
+                    name,
+                ));
+            }
+        }
+        Ok(())
+    }
+
     fn check_transaction_keywords(sql: &str) -> Result<()> {
         let got = "Query contains BEGIN, COMMIT, ROLLBACK, START TRANSACTION, or END TRANSACTION";
         if parameters::contains_transaction_keywords(sql) {
@@ -67,11 +268,14 @@ impl QueryDef {
         };
 
         // For each parameter that doesn't have an arg definition, add default string type
-        // Skip parameters that are not String type (i.e., TableName, List, CommaList are auto-detected)
+        // Skip parameters that are not String type (i.e., TableName, List, CommaList, Rows are auto-detected)
         let skip_types = [
             ParameterType::TableName,
             ParameterType::List,
             ParameterType::CommaList,
+            ParameterType::Rows,
+            ParameterType::Sort,
+            ParameterType::Filter,
         ];
         for param in parameters {
             if !skip_types.contains(&param.param_type) && !augmented_args.contains_key(&param.name)
@@ -91,11 +295,24 @@ impl QueryDef {
         if param.param_type == ParameterType::TableName
             || param.param_type == ParameterType::List
             || param.param_type == ParameterType::CommaList
+            || param.param_type == ParameterType::Rows
+            || param.param_type == ParameterType::Sort
+            || param.param_type == ParameterType::Filter
         {
             Self::process_automatic_parameter(param, args)?;
         } else {
             Self::process_regular_parameter(param, args)?;
         }
+        // Only identifiers are written into the SQL, so only they can be quoted
+        if param.constraints.quote
+            && param.param_type != ParameterType::TableName
+            && param.param_type != ParameterType::CommaList
+        {
+            return Err(JankenError::new_parameter_type_mismatch(
+                "type table_name or comma_list for parameter with 'quote'",
+                param.name.clone(),
+            ));
+        }
         Ok(())
     }
 
@@ -106,6 +323,62 @@ impl QueryDef {
         if let Some(arg_def) = args.get(&param.name) {
             parse_constraints(&mut param.constraints, arg_def)?;
         }
+        // A list holds either scalar items or tuples, never both
+        if param.param_type == ParameterType::List
+            && param.constraints.fields.is_some()
+            && param.constraints.item_type.is_some()
+        {
+            return Err(JankenError::new_parameter_type_mismatch(
+                "either 'itemtype' or 'fields' for list parameter",
+                param.name.clone(),
+            ));
+        }
+        // Array binding needs the item type to build a typed array
+        if param.param_type == ParameterType::List
+            && param.constraints.pg_array
+            && !matches!(
+                param.constraints.item_type,
+                Some(
+                    ParameterType::Integer
+                        | ParameterType::String
+                        | ParameterType::Float
+                        | ParameterType::Boolean
+                        | ParameterType::Blob
+                )
+            )
+        {
+            return Err(JankenError::new_parameter_type_mismatch(
+                "itemtype integer, string, float, boolean or blob for pg_array list parameter",
+                param.name.clone(),
+            ));
+        }
+        // Rows parameters cannot be expanded without knowing the fields of each row
+        if param.param_type == ParameterType::Rows && param.constraints.fields.is_none() {
+            return Err(JankenError::new_parameter_type_mismatch(
+                "'fields' definition in args for rows parameter",
+                param.name.clone(),
+            ));
+        }
+        // Filter parameters only ever compare columns from their allowlist
+        if param.param_type == ParameterType::Filter && param.constraints.filter_columns.is_none() {
+            return Err(JankenError::new_parameter_type_mismatch(
+                "'columns' definition in args for filter parameter",
+                param.name.clone(),
+            ));
+        }
+        // Sort parameters only ever expand to columns from their allowlist
+        if param.param_type == ParameterType::Sort
+            && !param
+                .constraints
+                .enum_values
+                .as_ref()
+                .is_some_and(|columns| !columns.is_empty() && columns.iter().all(|c| c.is_string()))
+        {
+            return Err(JankenError::new_parameter_type_mismatch(
+                "'enum' allowlist of column names in args for sort parameter",
+                param.name.clone(),
+            ));
+        }
         Ok(())
     }
 
@@ -119,6 +392,19 @@ impl QueryDef {
             .expect("augmented args creation ensures this parameter exists");
         Self::parse_regular_parameter_type(param, arg_def)?;
         parse_constraints(&mut param.constraints, arg_def)?;
+        // The search mode decides how the term is wrapped, so it needs a search parameter
+        if param.constraints.search_mode.is_some() && param.param_type != ParameterType::Search {
+            return Err(JankenError::new_parameter_type_mismatch(
+                "type search for parameter with 'mode'",
+                param.name.clone(),
+            ));
+        }
+        if param.constraints.fts.is_some() && param.param_type != ParameterType::FtsQuery {
+            return Err(JankenError::new_parameter_type_mismatch(
+                "type fts_query for parameter with 'prefix', 'operator' or 'term_length'",
+                param.name.clone(),
+            ));
+        }
 
         Ok(())
     }
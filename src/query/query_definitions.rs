@@ -1,16 +1,29 @@
 use crate::QueryDef;
-use crate::parameters::COMMA_LIST_REGEX;
+use crate::encoding::BlobEncoding;
+use crate::pagination::{PageQuery, Pagination};
+use crate::parameters::{
+    COMMA_LIST_REGEX, Dialect, ExecutedStatement, prepare_dialect_statement_chunks,
+};
+use crate::query::{ReturnsSpec, RowLimit};
 use crate::result::JankenError;
+use crate::str_utils::{looks_like_write_statement, split_sql_statements};
+use crate::transaction::TransactionOptions;
+use crate::workflow::WorkflowDef;
 use anyhow;
 use serde_json;
 use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::str::FromStr;
 
 /// Collection of parsed SQL query definitions loaded from JSON configuration
 #[derive(Debug)]
 pub struct QueryDefinitions {
     /// Named query definitions keyed by their identifying name
     pub definitions: HashMap<String, QueryDef>,
+    /// Named workflows (ordered steps over the query definitions) keyed by their identifying name
+    pub workflows: HashMap<String, WorkflowDef>,
+    /// Non-fatal issues found while loading, such as readonly queries whose statements look like writes
+    pub warnings: Vec<String>,
 }
 
 impl QueryDefinitions {
@@ -34,6 +47,9 @@ impl QueryDefinitions {
         };
 
         let mut definitions = HashMap::new();
+        let mut workflows = HashMap::new();
+        let mut declared_transactions = HashSet::new();
+        let mut warnings = Vec::new();
         for (name, value) in json_map {
             let map = value.as_object().ok_or_else(|| {
                 let expected = "object";
@@ -41,6 +57,17 @@ impl QueryDefinitions {
                 JankenError::new_parameter_type_mismatch(expected, got)
             })?;
 
+            // Workflow definitions reference queries instead of declaring their own SQL
+            if let Some(workflow_val) = map.get("workflow") {
+                let mut workflow = WorkflowDef::from_json(name, workflow_val)?;
+                if let Some(transaction_val) = map.get("transaction") {
+                    workflow.transaction = TransactionOptions::from_json(name, transaction_val)?;
+                    declared_transactions.insert(name.clone());
+                }
+                workflows.insert(name.clone(), workflow);
+                continue;
+            }
+
             let sql = map.get("query").and_then(|q| q.as_str()).ok_or_else(|| {
                 let expected = "required 'query' field with string value";
                 let got = format!("{name}: missing 'query' field");
@@ -51,85 +78,285 @@ impl QueryDefinitions {
             let mut query_def = QueryDef::from_sql(sql, args)?;
 
             // Parse returns field
-            if let Some(returns_val) = map.get("returns") {
-                if let Some(returns_array) = returns_val.as_array() {
-                    // Static array of field names
-                    let returns: Vec<String> = returns_array
-                        .iter()
-                        .filter_map(|v| v.as_str())
-                        .map(|s| s.to_string())
-                        .collect();
-                    // Deduplicate using a set but maintain order
-                    let mut seen = HashSet::new();
-                    let unique_returns: Vec<String> = returns
+            query_def.returns = match map.get("returns") {
+                Some(returns_val) => Self::parse_returns(name, sql, returns_val, &query_def)?,
+                // No returns specified - empty static array
+                None => ReturnsSpec::Static(Vec::new()),
+            };
+
+            // Parse blob_encoding field for BLOB/BYTEA result columns
+            if let Some(encoding_val) = map.get("blob_encoding") {
+                let encoding_str = encoding_val.as_str().ok_or_else(|| {
+                    let expected = "string for blob_encoding";
+                    let got = format!("{name}: {encoding_val}");
+                    JankenError::new_parameter_type_mismatch(expected, got)
+                })?;
+                query_def.blob_encoding = BlobEncoding::from_str(encoding_str)?;
+            }
+
+            // Parse transaction options applied when the runners open the transaction
+            if let Some(transaction_val) = map.get("transaction") {
+                query_def.transaction = TransactionOptions::from_json(name, transaction_val)?;
+            }
+
+            // Parse pagination applied to the query's rows
+            if let Some(paginate_val) = map.get("paginate") {
+                query_def.paginate = Some(Pagination::from_json(name, paginate_val)?);
+                Pagination::check_query(name, &query_def)?;
+            }
+
+            // Parse the row limit of read statements
+            if let Some(max_rows_val) = map.get("max_rows") {
+                query_def.max_rows = Some(RowLimit::from_json(name, max_rows_val)?);
+            }
+
+            // Parse readonly field; the runners enforce it, this only flags likely mistakes early
+            if let Some(readonly_val) = map.get("readonly") {
+                query_def.readonly = readonly_val.as_bool().ok_or_else(|| {
+                    let expected = "boolean for readonly";
+                    let got = format!("{name}: {readonly_val}");
+                    JankenError::new_parameter_type_mismatch(expected, got)
+                })?;
+            }
+            if query_def.readonly {
+                warnings.extend(
+                    split_sql_statements(sql)
                         .into_iter()
-                        .filter(|item| seen.insert(item.clone()))
-                        .collect();
-                    query_def.returns = crate::query::ReturnsSpec::Static(unique_returns);
-                } else if let Some(returns_str) = returns_val.as_str() {
-                    // Dynamic reference to a comma_list parameter: ~[param_name]
-                    // Use the existing COMMA_LIST_REGEX to validate and extract parameter name
-                    if let Some(cap) = COMMA_LIST_REGEX.captures(returns_str) {
-                        // Ensure the entire string matches (no extra characters)
-                        if cap
-                            .get(0)
-                            .expect("regex capture group 0 always exists when captures matches")
-                            .as_str()
-                            == returns_str
-                        {
-                            let param_name = cap
-                                .get(1)
-                                .expect("COMMA_LIST_REGEX always has capture group 1")
-                                .as_str();
-
-                            // Verify this parameter exists and is a CommaList type
-                            let param_exists = query_def.parameters.iter().any(|p| {
-                                p.name == param_name
-                                    && p.param_type == crate::ParameterType::CommaList
-                            });
-
-                            if param_exists {
-                                query_def.returns =
-                                    crate::query::ReturnsSpec::Dynamic(param_name.to_string());
-                            } else {
-                                let expected = format!(
-                                    "returns reference ~[{}] to point to an existing comma_list parameter",
-                                    param_name
-                                );
-                                let got = format!(
-                                    "parameter '{}' not found or not a comma_list type",
-                                    param_name
-                                );
-                                return Err(JankenError::new_parameter_type_mismatch(
-                                    &expected, &got,
-                                )
-                                .into());
-                            }
-                        } else {
-                            let expected = "array of strings or ~[param_name] format";
-                            let got =
-                                format!("string not in ~[param_name] format: {}", returns_str);
-                            return Err(
-                                JankenError::new_parameter_type_mismatch(expected, &got).into()
-                            );
-                        }
-                    } else {
-                        let expected = "array of strings or ~[param_name] format";
-                        let got = format!("string not in ~[param_name] format: {}", returns_str);
-                        return Err(JankenError::new_parameter_type_mismatch(expected, &got).into());
-                    }
-                } else {
-                    let expected = "array of strings or ~[param_name] format";
-                    let got = returns_val.to_string();
+                        .filter(|statement| looks_like_write_statement(statement))
+                        .map(|statement| {
+                            format!(
+                                "query '{name}' is declared readonly but statement looks like a write: {statement}"
+                            )
+                        }),
+                );
+            }
+
+            definitions.insert(name.clone(), query_def);
+        }
+        for (name, workflow) in &mut workflows {
+            for step in &workflow.steps {
+                // Every workflow step must reference a query definition
+                let Some(query_def) = definitions.get(&step.query) else {
+                    let expected = "workflow step referencing an existing query";
+                    let got = format!("{name}: {}", step.query);
                     return Err(JankenError::new_parameter_type_mismatch(expected, got).into());
+                };
+
+                // The steps share one transaction, so their transaction options must agree
+                let options = &query_def.transaction;
+                if *options == TransactionOptions::default() || *options == workflow.transaction {
+                    continue;
                 }
-            } else {
-                // No returns specified - empty static array
-                query_def.returns = crate::query::ReturnsSpec::Static(Vec::new());
+                if !declared_transactions.contains(name)
+                    && workflow.transaction == TransactionOptions::default()
+                {
+                    workflow.transaction = options.clone();
+                    continue;
+                }
+                let expected = "workflow steps with the transaction options of the workflow";
+                let got = format!("{name}: step '{}' ({})", step.name, step.query);
+                return Err(JankenError::new_parameter_type_mismatch(expected, got).into());
             }
+        }
 
-            definitions.insert(name.clone(), query_def);
+        Ok(QueryDefinitions {
+            definitions,
+            workflows,
+            warnings,
+        })
+    }
+
+    /// Apply `limit` to every query definition that does not declare its own `max_rows`,
+    /// as a global safeguard against unbounded reads
+    pub fn set_default_max_rows(&mut self, limit: RowLimit) {
+        for query_def in self.definitions.values_mut() {
+            query_def.max_rows.get_or_insert(limit);
+        }
+    }
+
+    /// Validate request parameters for a query without touching the database.
+    /// Runs the same checks as the query runners and returns the statements that would be
+    /// executed, with their SQL and bound values converted to the given dialect.
+    /// Statements split into chunks to stay within the dialect's parameter limit are listed
+    /// once per chunk. Paginated queries list the page query, then the count query if any.
+    pub fn validate(
+        &self,
+        query_name: &str,
+        request_params: &serde_json::Value,
+        dialect: Dialect,
+    ) -> crate::Result<Vec<ExecutedStatement>> {
+        let query = self
+            .definitions
+            .get(query_name)
+            .ok_or_else(|| JankenError::new_query_not_found(query_name.to_string()))?;
+
+        let request_params_obj = request_params
+            .as_object()
+            .ok_or_else(|| JankenError::new_parameter_type_mismatch("object", "not object"))?;
+
+        let mut statements = Vec::new();
+        match &query.paginate {
+            Some(pagination) => {
+                let page = PageQuery::resolve(query, pagination, request_params_obj)?;
+                Self::validate_statements(&page.query, &page.params, dialect, &mut statements)?;
+                if let Some(count_query) = &page.count_query {
+                    Self::validate_statements(
+                        count_query,
+                        request_params_obj,
+                        dialect,
+                        &mut statements,
+                    )?;
+                }
+            }
+            None => Self::validate_statements(query, request_params_obj, dialect, &mut statements)?,
+        }
+        Ok(statements)
+    }
+
+    /// Prepare the statements of one query definition for `validate`
+    fn validate_statements(
+        query: &QueryDef,
+        request_params_obj: &serde_json::Map<String, serde_json::Value>,
+        dialect: Dialect,
+        statements: &mut Vec<ExecutedStatement>,
+    ) -> crate::Result<()> {
+        for statement_sql in query.statement_sqls(request_params_obj)? {
+            let chunks = prepare_dialect_statement_chunks(
+                &statement_sql,
+                &query.parameters,
+                request_params_obj,
+                dialect,
+            )?;
+            statements.extend(
+                chunks
+                    .iter()
+                    .map(|prepared| prepared.to_executed_statement(dialect)),
+            );
+        }
+        Ok(())
+    }
+
+    /// Parse the returns field: a single spec, or an array of specs aligned with the statements of a multi-statement query
+    fn parse_returns(
+        name: &str,
+        sql: &str,
+        returns_val: &serde_json::Value,
+        query_def: &QueryDef,
+    ) -> anyhow::Result<ReturnsSpec> {
+        // An array entry or a whole ~[param_name] entry can only describe a statement
+        let is_per_statement = returns_val.as_array().is_some_and(|items| {
+            items.iter().any(|item| {
+                item.is_array() || item.as_str().is_some_and(Self::is_returns_reference)
+            })
+        });
+        if !is_per_statement {
+            return Self::parse_single_returns(returns_val, query_def);
+        }
+
+        let items = returns_val
+            .as_array()
+            .expect("per-statement returns already verified as array");
+        let statement_count = split_sql_statements(sql).len();
+        if items.len() != statement_count {
+            let expected = format!("one returns entry per statement ({statement_count})");
+            let got = format!("{name}: {} returns entries", items.len());
+            return Err(JankenError::new_parameter_type_mismatch(expected, got).into());
+        }
+
+        let specs = items
+            .iter()
+            .map(|item| {
+                if item.is_array() || item.is_string() {
+                    Self::parse_single_returns(item, query_def)
+                } else {
+                    let expected = "array of strings or ~[param_name] format for each statement";
+                    let got = format!("{name}: {item}");
+                    Err(JankenError::new_parameter_type_mismatch(expected, got).into())
+                }
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(ReturnsSpec::PerStatement(specs))
+    }
+
+    /// Whether a returns string is exactly a ~[param_name] reference
+    fn is_returns_reference(returns_str: &str) -> bool {
+        COMMA_LIST_REGEX
+            .find(returns_str)
+            .is_some_and(|found| found.as_str() == returns_str)
+    }
+
+    /// Parse a single returns spec: a static array of field names or a ~[param_name] reference
+    fn parse_single_returns(
+        returns_val: &serde_json::Value,
+        query_def: &QueryDef,
+    ) -> anyhow::Result<ReturnsSpec> {
+        if let Some(returns_array) = returns_val.as_array() {
+            // Static array of field names
+            if let Some(reference) = returns_array
+                .iter()
+                .filter_map(|v| v.as_str())
+                .find(|s| COMMA_LIST_REGEX.is_match(s))
+            {
+                let expected = "field names without ~[param_name] references in returns array";
+                return Err(JankenError::new_parameter_type_mismatch(expected, reference).into());
+            }
+            let returns: Vec<String> = returns_array
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(|s| s.to_string())
+                .collect();
+            // Deduplicate using a set but maintain order
+            let mut seen = HashSet::new();
+            let unique_returns: Vec<String> = returns
+                .into_iter()
+                .filter(|item| seen.insert(item.clone()))
+                .collect();
+            return Ok(ReturnsSpec::Static(unique_returns));
+        }
+
+        let Some(returns_str) = returns_val.as_str() else {
+            let expected = "array of strings or ~[param_name] format";
+            let got = returns_val.to_string();
+            return Err(JankenError::new_parameter_type_mismatch(expected, got).into());
+        };
+
+        // Dynamic reference to a comma_list parameter: ~[param_name]
+        // Use the existing COMMA_LIST_REGEX to validate and extract parameter name
+        // and ensure the entire string matches (no extra characters)
+        let Some(cap) = COMMA_LIST_REGEX.captures(returns_str).filter(|cap| {
+            cap.get(0)
+                .expect("regex capture group 0 always exists when captures matches")
+                .as_str()
+                == returns_str
+        }) else {
+            let expected = "array of strings or ~[param_name] format";
+            let got = format!("string not in ~[param_name] format: {}", returns_str);
+            return Err(JankenError::new_parameter_type_mismatch(expected, &got).into());
+        };
+
+        let param_name = cap
+            .get(1)
+            .expect("COMMA_LIST_REGEX always has capture group 1")
+            .as_str();
+
+        // Verify this parameter exists and is a CommaList type
+        let param_exists = query_def
+            .parameters
+            .iter()
+            .any(|p| p.name == param_name && p.param_type == crate::ParameterType::CommaList);
+
+        if param_exists {
+            Ok(ReturnsSpec::Dynamic(param_name.to_string()))
+        } else {
+            let expected = format!(
+                "returns reference ~[{}] to point to an existing comma_list parameter",
+                param_name
+            );
+            let got = format!(
+                "parameter '{}' not found or not a comma_list type",
+                param_name
+            );
+            Err(JankenError::new_parameter_type_mismatch(&expected, &got).into())
         }
-        Ok(QueryDefinitions { definitions })
     }
 }
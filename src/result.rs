@@ -1,3 +1,4 @@
+use crate::parameters::ExecutedStatement;
 use thiserror::Error;
 
 /// Common error data structure for all Janken errors
@@ -18,6 +19,12 @@ pub enum JankenError {
     ParameterTypeMismatch { data: ErrorData },
     #[error("Parameter name conflict")]
     ParameterNameConflict { data: ErrorData },
+    #[error("Readonly violation")]
+    ReadonlyViolation { data: ErrorData },
+    #[error("Batch item failed")]
+    BatchItemFailed { data: ErrorData },
+    #[error("Row limit exceeded")]
+    RowLimitExceeded { data: ErrorData },
 }
 
 /// Type alias for Results using JankenError
@@ -28,6 +35,55 @@ pub type Result<T> = std::result::Result<T, JankenError>;
 pub struct QueryResult {
     pub sql_statements: Vec<String>,
     pub data: Vec<serde_json::Value>,
+    /// Rows of each statement for queries with per-statement `returns` (empty otherwise)
+    /// Statements without returns contribute an empty result set
+    pub result_sets: Vec<Vec<serde_json::Value>>,
+    /// Executed statements with their bound values, aligned with `sql_statements`
+    pub statements: Vec<ExecutedStatement>,
+    /// Number of times the transaction was run, greater than 1 when a retry policy re-ran it
+    pub attempts: u32,
+    /// Cursor of the next page for keyset-paginated queries, `None` on the last page
+    pub next_cursor: Option<String>,
+    /// Total number of rows of a paginated query declared with `"count": true`
+    pub total: Option<u64>,
+    /// Whether rows beyond the query's `max_rows` were dropped (`"on_exceed": "truncate"`)
+    pub truncated: bool,
+}
+
+impl QueryResult {
+    /// Build a result from the executed statements, deriving `sql_statements` from them
+    pub(crate) fn from_statements(
+        statements: Vec<ExecutedStatement>,
+        data: Vec<serde_json::Value>,
+        result_sets: Vec<Vec<serde_json::Value>>,
+    ) -> Self {
+        QueryResult {
+            sql_statements: statements.iter().map(|stmt| stmt.sql.clone()).collect(),
+            data,
+            result_sets,
+            statements,
+            attempts: 1,
+            next_cursor: None,
+            total: None,
+            truncated: false,
+        }
+    }
+}
+
+/// Result of executing one query with many parameter sets
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchResult {
+    /// Rows affected by each item (summed over its statements), aligned with the parameter sets
+    pub row_counts: Vec<u64>,
+    /// Number of distinct SQL statements prepared for the whole batch
+    pub prepared_statements: usize,
+}
+
+/// Wrap the error of a batch item so it reports the item index while the underlying
+/// error stays available through `downcast`
+pub(crate) fn batch_item_error(index: usize, err: anyhow::Error) -> anyhow::Error {
+    let item_err = JankenError::new_batch_item_failed(index, err.to_string());
+    err.context(item_err)
 }
 
 /// Common metadata field names
@@ -37,12 +93,18 @@ pub const M_QUERY_NAME: &str = "query_name";
 pub const M_PARAM_NAME: &str = "parameter_name";
 pub const M_CONFLICT_NAME: &str = "conflicting_name";
 pub const M_ERROR: &str = "error";
+pub const M_SQL: &str = "sql";
+pub const M_INDEX: &str = "index";
+pub const M_MAX_ROWS: &str = "max_rows";
 
 /// Error codes for JankenError variants
 pub const ERR_CODE_QUERY_NOT_FOUND: u16 = 2000;
 pub const ERR_CODE_PARAMETER_NOT_PROVIDED: u16 = 2010;
 pub const ERR_CODE_PARAMETER_TYPE_MISMATCH: u16 = 2020;
 pub const ERR_CODE_PARAMETER_NAME_CONFLICT: u16 = 2030;
+pub const ERR_CODE_READONLY_VIOLATION: u16 = 2040;
+pub const ERR_CODE_BATCH_ITEM_FAILED: u16 = 2050;
+pub const ERR_CODE_ROW_LIMIT_EXCEEDED: u16 = 2060;
 
 /// Implementation for creating structured errors
 impl JankenError {
@@ -110,6 +172,53 @@ impl JankenError {
             },
         }
     }
+
+    pub fn new_readonly_violation(sql: impl Into<String>) -> Self {
+        let sql = sql.into();
+        JankenError::ReadonlyViolation {
+            data: ErrorData {
+                code: ERR_CODE_READONLY_VIOLATION,
+                metadata: Some(
+                    serde_json::json!({
+                        M_SQL: sql
+                    })
+                    .to_string(),
+                ),
+            },
+        }
+    }
+
+    pub fn new_batch_item_failed(index: usize, error: impl Into<String>) -> Self {
+        let error = error.into();
+        JankenError::BatchItemFailed {
+            data: ErrorData {
+                code: ERR_CODE_BATCH_ITEM_FAILED,
+                metadata: Some(
+                    serde_json::json!({
+                        M_INDEX: index,
+                        M_ERROR: error
+                    })
+                    .to_string(),
+                ),
+            },
+        }
+    }
+
+    pub fn new_row_limit_exceeded(max_rows: usize, sql: impl Into<String>) -> Self {
+        let sql = sql.into();
+        JankenError::RowLimitExceeded {
+            data: ErrorData {
+                code: ERR_CODE_ROW_LIMIT_EXCEEDED,
+                metadata: Some(
+                    serde_json::json!({
+                        M_MAX_ROWS: max_rows,
+                        M_SQL: sql
+                    })
+                    .to_string(),
+                ),
+            },
+        }
+    }
 }
 
 /// Error code mappings and descriptions
@@ -146,6 +255,24 @@ pub const ERROR_MAPPINGS: &[ErrorInfo] = &[
         category: "Parameter",
         description: "Parameter name conflicts with table name",
     },
+    ErrorInfo {
+        code: ERR_CODE_READONLY_VIOLATION,
+        name: "READONLY_VIOLATION",
+        category: "Query",
+        description: "Query declared readonly contains a statement that writes",
+    },
+    ErrorInfo {
+        code: ERR_CODE_BATCH_ITEM_FAILED,
+        name: "BATCH_ITEM_FAILED",
+        category: "Query",
+        description: "An item of a batch failed validation or execution",
+    },
+    ErrorInfo {
+        code: ERR_CODE_ROW_LIMIT_EXCEEDED,
+        name: "ROW_LIMIT_EXCEEDED",
+        category: "Query",
+        description: "Read query returned more rows than its max_rows",
+    },
 ];
 
 /// Helper function to get error data from any JankenError variant
@@ -155,6 +282,9 @@ pub fn get_error_data(err: &JankenError) -> &ErrorData {
         JankenError::ParameterNotProvided { data } => data,
         JankenError::ParameterTypeMismatch { data } => data,
         JankenError::ParameterNameConflict { data } => data,
+        JankenError::ReadonlyViolation { data } => data,
+        JankenError::BatchItemFailed { data } => data,
+        JankenError::RowLimitExceeded { data } => data,
     }
 }
 
@@ -164,10 +294,18 @@ pub fn get_error_info(code: u16) -> Option<&'static ErrorInfo> {
 }
 
 /// Helper function to extract metadata field from error data as string
+/// Non-string values (e.g. a batch item index) are returned in their JSON form
 pub fn error_meta(data: &ErrorData, field: &str) -> Option<String> {
     data.metadata.as_ref().and_then(|metadata_str| {
         serde_json::from_str::<serde_json::Value>(metadata_str)
             .ok()
-            .and_then(|metadata| metadata.get(field)?.as_str().map(|s| s.to_string()))
+            .and_then(|metadata| {
+                let value = metadata.get(field)?;
+                Some(
+                    value
+                        .as_str()
+                        .map_or_else(|| value.to_string(), str::to_string),
+                )
+            })
     })
 }
@@ -1,11 +1,20 @@
 use crate::{
-    QueryDefinitions, parameters,
-    result::{JankenError, QueryResult},
-    str_utils::split_sql_statements,
+    QueryDefinitions,
+    encoding::{BlobEncoding, encode_blob},
+    export::{ExportOptions, ResultWriter},
+    pagination::PageQuery,
+    parameters::{self, Dialect, ExecutedStatement},
+    result::{BatchResult, JankenError, QueryResult, batch_item_error},
+    statement_cache::PostgresStatementCache,
+    str_utils::{looks_like_write_statement, split_sql_statements},
+    transaction::{IsolationLevel, TransactionOptions},
+    workflow::{WorkflowResult, WorkflowStepResult},
 };
 
 // Import generic types for parameter decoupling
-use parameters::ParameterValue;
+use futures_util::{Stream, StreamExt};
+use parameters::{ParameterType, ParameterValue};
+use std::collections::HashMap;
 use tokio_postgres::Client;
 
 // PostgreSQL type OIDs for all column types
@@ -22,47 +31,117 @@ const POSTGRES_TYPE_OID_BPCHAR: u32 = 1042;
 const POSTGRES_TYPE_OID_JSON: u32 = 114;
 const POSTGRES_TYPE_OID_JSONB: u32 = 3802;
 
+impl From<IsolationLevel> for tokio_postgres::IsolationLevel {
+    fn from(isolation: IsolationLevel) -> Self {
+        match isolation {
+            IsolationLevel::ReadUncommitted => tokio_postgres::IsolationLevel::ReadUncommitted,
+            IsolationLevel::ReadCommitted => tokio_postgres::IsolationLevel::ReadCommitted,
+            IsolationLevel::RepeatableRead => tokio_postgres::IsolationLevel::RepeatableRead,
+            IsolationLevel::Serializable => tokio_postgres::IsolationLevel::Serializable,
+        }
+    }
+}
+
 /// Convert a generic ParameterValue directly to PostgreSQL ToSql trait object
 /// This provides easier testability by being a direct function call instead of a trait implementation
 fn parameter_value_to_postgresql_tosql(
     param_value: ParameterValue,
-) -> Box<dyn tokio_postgres::types::ToSql + Sync> {
-    match param_value {
+) -> anyhow::Result<Box<dyn tokio_postgres::types::ToSql + Sync>> {
+    Ok(match param_value {
         ParameterValue::String(s) => Box::new(s),
         ParameterValue::Integer(i) => Box::new(i as i32), // PostgreSQL typically uses i32 for integers
         ParameterValue::Float(f) => Box::new(f),
         ParameterValue::Boolean(b) => Box::new(b),
         ParameterValue::Blob(bytes) => Box::new(bytes),
-    }
+        ParameterValue::Array { item_type, items } => match item_type {
+            // Integer arrays bind as int4[] like integer scalars, so items must fit in 32 bits
+            ParameterType::Integer => Box::new(array_items(items, |item| match item {
+                ParameterValue::Integer(i) => i32::try_from(i).map_err(|_| {
+                    JankenError::new_parameter_type_mismatch(
+                        "integer within the 32-bit range of a PostgreSQL integer array",
+                        i.to_string(),
+                    )
+                }),
+                other => Err(array_item_mismatch(&item_type, &other)),
+            })?),
+            ParameterType::Float => Box::new(array_items(items, |item| match item {
+                ParameterValue::Float(f) => Ok(f),
+                other => Err(array_item_mismatch(&item_type, &other)),
+            })?),
+            ParameterType::Boolean => Box::new(array_items(items, |item| match item {
+                ParameterValue::Boolean(b) => Ok(b),
+                other => Err(array_item_mismatch(&item_type, &other)),
+            })?),
+            ParameterType::Blob => Box::new(array_items(items, |item| match item {
+                ParameterValue::Blob(bytes) => Ok(bytes),
+                other => Err(array_item_mismatch(&item_type, &other)),
+            })?),
+            _ => Box::new(array_items(items, |item| match item {
+                ParameterValue::String(s) => Ok(s),
+                other => Err(array_item_mismatch(&item_type, &other)),
+            })?),
+        },
+        // to_executed_statement renders full-text queries as strings
+        ParameterValue::FtsQuery(_) => {
+            unreachable!("fts queries are bound as their PostgreSQL query string")
+        }
+    })
+}
+
+/// Convert every item of an array parameter, failing on the first one that does not fit
+fn array_items<T>(
+    items: Vec<ParameterValue>,
+    convert: impl Fn(ParameterValue) -> Result<T, JankenError>,
+) -> anyhow::Result<Vec<T>> {
+    Ok(items
+        .into_iter()
+        .map(convert)
+        .collect::<Result<Vec<T>, JankenError>>()?)
 }
 
-/// Create a prepared statement from SQL using the generic parameter decoupling approach
+fn array_item_mismatch(item_type: &ParameterType, item: &ParameterValue) -> JankenError {
+    JankenError::new_parameter_type_mismatch(format!("{item_type} array item"), format!("{item:?}"))
+}
+
+/// Create prepared statements from SQL using the generic parameter decoupling approach
 /// This separates parameter analysis (generic) from database-specific conversions (PostgreSQL-specific)
+/// A statement binding more values than PostgreSQL allows is split into several chunks
 fn prepare_single_statement_postgresql(
     statement_sql: &str,
     all_parameters: &[crate::parameters::Parameter],
     request_params_obj: &serde_json::Map<String, serde_json::Value>,
-) -> anyhow::Result<PreparedStatement> {
+) -> anyhow::Result<Vec<PreparedStatement>> {
     // Use the generic parameter preparation (database-agnostic)
-    let generic_statement = parameters::prepare_parameter_statement_generic(
+    let generic_statements = parameters::prepare_dialect_statement_chunks(
         statement_sql,
         all_parameters,
         request_params_obj,
+        Dialect::Postgresql,
     )?;
 
+    generic_statements
+        .iter()
+        .map(to_postgresql_statement)
+        .collect()
+}
+
+/// Convert a generic statement to positional placeholders and PostgreSQL-specific values
+fn to_postgresql_statement(
+    generic_statement: &parameters::PreparedParameterStatement,
+) -> anyhow::Result<PreparedStatement> {
+    // Convert @param placeholders to positional $n placeholders for PostgreSQL
+    let executed = generic_statement.to_executed_statement(Dialect::Postgresql);
+
     // Convert generic parameters to PostgreSQL-specific ToSql types using direct function call
-    let pgsql_params = generic_statement
+    let pgsql_params = executed
         .parameters
-        .into_iter()
-        .map(|(name, param_value)| {
-            let to_sql = parameter_value_to_postgresql_tosql(param_value);
-            (name, to_sql)
-        })
-        .collect();
+        .iter()
+        .map(|bound| parameter_value_to_postgresql_tosql(bound.value.clone()))
+        .collect::<anyhow::Result<_>>()?;
 
     Ok(PreparedStatement {
-        sql: generic_statement.sql,
-        named_params: pgsql_params,
+        executed,
+        positional_params: pgsql_params,
     })
 }
 
@@ -76,6 +155,16 @@ pub fn postgres_type_to_json_conversion(
     column_type: &tokio_postgres::types::Type,
     row: &tokio_postgres::Row,
     idx: usize,
+) -> anyhow::Result<serde_json::Value> {
+    postgres_value_to_json(column_type, row, idx, BlobEncoding::Array)
+}
+
+// Convert a PostgreSQL column value to JSON, encoding BYTEA values with the given blob encoding
+fn postgres_value_to_json(
+    column_type: &tokio_postgres::types::Type,
+    row: &tokio_postgres::Row,
+    idx: usize,
+    blob_encoding: BlobEncoding,
 ) -> anyhow::Result<serde_json::Value> {
     let oid = column_type.oid();
     match oid {
@@ -109,7 +198,7 @@ pub fn postgres_type_to_json_conversion(
         }
         POSTGRES_TYPE_OID_BYTEA => {
             let val: Vec<u8> = row.try_get(idx)?;
-            to_json_value(val)
+            Ok(encode_blob(&val, blob_encoding))
         }
         POSTGRES_TYPE_OID_JSON | POSTGRES_TYPE_OID_JSONB => {
             let json_val: serde_json::Value = row.try_get(idx)?;
@@ -129,6 +218,7 @@ pub fn postgres_type_to_json_conversion(
 fn row_to_json_object(
     row: &tokio_postgres::Row,
     returns: &[String],
+    blob_encoding: BlobEncoding,
 ) -> anyhow::Result<serde_json::Map<String, serde_json::Value>> {
     let mut obj = serde_json::Map::new();
 
@@ -142,7 +232,7 @@ fn row_to_json_object(
         let value = match column_idx {
             Some(idx) => {
                 let col = &columns[idx];
-                postgres_type_to_json_conversion(col.type_(), row, idx)
+                postgres_value_to_json(col.type_(), row, idx, blob_encoding)
             }
             None => Ok(serde_json::Value::Null),
         };
@@ -169,7 +259,7 @@ pub fn map_rows_to_json_data(
     let mut result_data = Vec::new();
 
     for row in rows {
-        let obj = row_to_json_object(&row, returns)?;
+        let obj = row_to_json_object(&row, returns, BlobEncoding::Array)?;
         result_data.push(serde_json::Value::Object(obj));
     }
 
@@ -178,54 +268,65 @@ pub fn map_rows_to_json_data(
 
 // Prepared statement with PostgreSQL parameter handling
 struct PreparedStatement {
-    sql: String,
-    // For PostgreSQL, we'll collect parameters as named values to be converted to positional
-    named_params: Vec<(String, Box<dyn tokio_postgres::types::ToSql + Sync>)>,
+    // The SQL with positional $n placeholders, along with its bound values for reporting
+    executed: ExecutedStatement,
+    // Values for $1, $2, ... in placeholder order
+    positional_params: Vec<Box<dyn tokio_postgres::types::ToSql + Sync>>,
 }
 
-// Convert named parameters to positional for PostgreSQL ($1, $2, etc.)
 impl PreparedStatement {
-    fn as_positional_params(&self) -> (String, Vec<&(dyn tokio_postgres::types::ToSql + Sync)>) {
-        let mut positional_sql = self.sql.clone();
-        let mut positional_params = Vec::new();
-
-        // Sort parameters by name for consistent ordering (important for replacement)
-        let mut sorted_params: Vec<_> = self.named_params.iter().collect();
-        sorted_params.sort_by_key(|(name, _)| name.clone());
-
-        for (index, (_, value)) in sorted_params.iter().enumerate() {
-            let placeholder = format!("${}", index + 1);
-            // Replace the first occurrence of the parameter name with positional placeholder
-            // This assumes parameters are uniquely named in practice
-            let param_pattern = format!("@{}", sorted_params[index].0);
-            positional_sql = positional_sql.replace(&param_pattern, &placeholder);
-            positional_params.push(value.as_ref());
-        }
+    // Get parameters in the positional format expected by tokio_postgres
+    fn as_positional_params(&self) -> Vec<&(dyn tokio_postgres::types::ToSql + Sync)> {
+        self.positional_params
+            .iter()
+            .map(|value| value.as_ref())
+            .collect()
+    }
+}
 
-        (positional_sql, positional_params)
+// Look up the server-side prepared statement for the SQL in the cache, preparing it on a miss
+// Without a cache, statements are sent as SQL text and prepared unnamed on every execution
+async fn cached_statement(
+    transaction: &tokio_postgres::Transaction<'_>,
+    sql: &str,
+    cache: Option<&PostgresStatementCache>,
+) -> anyhow::Result<Option<tokio_postgres::Statement>> {
+    let Some(cache) = cache else {
+        return Ok(None);
+    };
+    if let Some(statement) = cache.get(sql) {
+        return Ok(Some(statement));
     }
+    let statement = transaction
+        .prepare(sql)
+        .await
+        .map_err(anyhow::Error::from)?;
+    cache.insert(sql, statement.clone());
+    Ok(Some(statement))
 }
 
-// Execute a single SQL statement with its appropriate parameters
+// Execute a single SQL statement with its appropriate parameters, once per chunk
 async fn execute_single_statement(
     transaction: &mut tokio_postgres::Transaction<'_>,
     statement_sql: &str,
     all_parameters: &[crate::parameters::Parameter],
     request_params_obj: &serde_json::Map<String, serde_json::Value>,
-) -> anyhow::Result<String> {
-    let prepared =
-        prepare_single_statement_postgresql(statement_sql, all_parameters, request_params_obj)?;
-
-    // Convert to positional parameters for PostgreSQL
-    let (positional_sql, positional_params) = prepared.as_positional_params();
-
-    // Execute with positional parameter values
-    transaction
-        .execute(&positional_sql, &positional_params)
-        .await
+    cache: Option<&PostgresStatementCache>,
+) -> anyhow::Result<Vec<ExecutedStatement>> {
+    let mut statements = Vec::new();
+    for prepared in
+        prepare_single_statement_postgresql(statement_sql, all_parameters, request_params_obj)?
+    {
+        // Execute with positional parameter values
+        let params = prepared.as_positional_params();
+        match cached_statement(transaction, &prepared.executed.sql, cache).await? {
+            Some(statement) => transaction.execute(&statement, &params).await,
+            None => transaction.execute(&prepared.executed.sql, &params).await,
+        }
         .map_err(anyhow::Error::from)?;
-
-    Ok(positional_sql)
+        statements.push(prepared.executed);
+    }
+    Ok(statements)
 }
 
 // Execute mutation query (INSERT/UPDATE/DELETE/etc.) - split and execute within transaction
@@ -233,37 +334,39 @@ async fn execute_mutation_query(
     query: &crate::query::QueryDef,
     request_params_obj: &serde_json::Map<String, serde_json::Value>,
     transaction: &mut tokio_postgres::Transaction<'_>,
-) -> anyhow::Result<Vec<String>> {
-    let mut sql_statements = Vec::new();
+    cache: Option<&PostgresStatementCache>,
+) -> anyhow::Result<Vec<ExecutedStatement>> {
+    let mut statements = Vec::new();
     if query.sql.contains(';') {
         // Has parameters - split into individual statements and execute each one
         let individual_statements = split_sql_statements(&query.sql);
 
         for statement_sql in individual_statements {
             // Execute each statement with the appropriate parameters
-            let sql = execute_single_statement(
+            let executed = execute_single_statement(
                 transaction,
                 &statement_sql,
                 &query.parameters,
                 request_params_obj,
+                cache,
             )
             .await?;
-            sql_statements.push(sql);
+            statements.extend(executed);
         }
     } else {
         // Single-statement mutation - prepare and execute normally with all parameters
-        let prepared =
-            prepare_single_statement_postgresql(&query.sql, &query.parameters, request_params_obj)?;
-
-        let (pos_sql, pos_params) = prepared.as_positional_params();
-        transaction
-            .execute(&pos_sql, &pos_params)
-            .await
-            .map_err(anyhow::Error::from)?;
-        sql_statements.push(pos_sql);
+        let executed = execute_single_statement(
+            transaction,
+            &query.sql,
+            &query.parameters,
+            request_params_obj,
+            cache,
+        )
+        .await?;
+        statements.extend(executed);
     }
 
-    Ok(sql_statements)
+    Ok(statements)
 }
 
 /// Resolve the returns specification to actual field names
@@ -271,31 +374,7 @@ fn resolve_returns(
     returns_spec: &crate::query::ReturnsSpec,
     request_params_obj: &serde_json::Map<String, serde_json::Value>,
 ) -> anyhow::Result<Vec<String>> {
-    match returns_spec {
-        crate::query::ReturnsSpec::Static(fields) => Ok(fields.clone()),
-        crate::query::ReturnsSpec::Dynamic(param_name) => {
-            // Get the comma_list parameter value
-            let param_value = request_params_obj
-                .get(param_name)
-                .ok_or_else(|| JankenError::new_parameter_not_provided(param_name.clone()))?;
-
-            let fields_array = param_value.as_array().ok_or_else(|| {
-                JankenError::new_parameter_type_mismatch(
-                    "array for comma_list parameter",
-                    param_value.to_string(),
-                )
-            })?;
-
-            // Convert to vector of strings
-            let fields: Vec<String> = fields_array
-                .iter()
-                .filter_map(|v| v.as_str())
-                .map(|s| s.to_string())
-                .collect();
-
-            Ok(fields)
-        }
-    }
+    Ok(returns_spec.resolve(request_params_obj)?)
 }
 
 // Execute query with both read and mutation operations within a unified transaction
@@ -304,74 +383,688 @@ pub async fn execute_query_unified(
     request_params_obj: &serde_json::Map<String, serde_json::Value>,
     transaction: &mut tokio_postgres::Transaction<'_>,
 ) -> anyhow::Result<QueryResult> {
+    execute_query_with_cache(query, request_params_obj, transaction, None).await
+}
+
+// Execute a query like `execute_query_unified`, reusing prepared statements from `cache`
+// Paginated queries run their page query, then their count query if any
+async fn execute_query_with_cache(
+    query: &crate::query::QueryDef,
+    request_params_obj: &serde_json::Map<String, serde_json::Value>,
+    transaction: &mut tokio_postgres::Transaction<'_>,
+    cache: Option<&PostgresStatementCache>,
+) -> anyhow::Result<QueryResult> {
+    let Some(pagination) = &query.paginate else {
+        return execute_unpaginated_query(query, request_params_obj, transaction, cache).await;
+    };
+    let page = PageQuery::resolve(query, pagination, request_params_obj)?;
+    let page_result =
+        execute_unpaginated_query(&page.query, &page.params, transaction, cache).await?;
+    let count_result = match &page.count_query {
+        Some(count_query) => Some(
+            execute_unpaginated_query(count_query, request_params_obj, transaction, cache).await?,
+        ),
+        None => None,
+    };
+    Ok(page.finish(page_result, count_result)?)
+}
+
+// Execute a query without applying its pagination
+async fn execute_unpaginated_query(
+    query: &crate::query::QueryDef,
+    request_params_obj: &serde_json::Map<String, serde_json::Value>,
+    transaction: &mut tokio_postgres::Transaction<'_>,
+    cache: Option<&PostgresStatementCache>,
+) -> anyhow::Result<QueryResult> {
+    if let crate::query::ReturnsSpec::PerStatement(statement_returns) = &query.returns {
+        return execute_per_statement_query(
+            query,
+            statement_returns,
+            request_params_obj,
+            transaction,
+            cache,
+        )
+        .await;
+    }
+
     // Resolve returns specification to actual field names
     let returns_fields = resolve_returns(&query.returns, request_params_obj)?;
 
     if !returns_fields.is_empty() {
         // Query with returns specified - return structured data
-        let prepared =
-            prepare_single_statement_postgresql(&query.sql, &query.parameters, request_params_obj)?;
-
-        let (positional_sql, positional_params) = prepared.as_positional_params();
+        let (executed, result_data, truncated) = execute_read_query(
+            &query.sql,
+            query,
+            request_params_obj,
+            &returns_fields,
+            transaction,
+            cache,
+        )
+        .await?;
+
+        let mut query_result = QueryResult::from_statements(executed, result_data, vec![]);
+        query_result.truncated = truncated;
+        Ok(query_result)
+    } else {
+        // Mutation query (INSERT/UPDATE/DELETE/etc.) - split and execute within transaction
+        let statements =
+            execute_mutation_query(query, request_params_obj, transaction, cache).await?;
+        Ok(QueryResult::from_statements(statements, vec![], vec![]))
+    }
+}
 
-        let rows = transaction
-            .query(&positional_sql, &positional_params)
-            .await
+// Execute a single read statement and map its rows to JSON objects as they are fetched
+// Chunked statements contribute the rows of every chunk in order
+// Reading stops at the query's `max_rows`; the returned flag tells whether rows were dropped
+// Writing statements (e.g. chunked `INSERT ... RETURNING`) still run every chunk to completion,
+// only the rows past the limit are dropped
+async fn execute_read_query(
+    statement_sql: &str,
+    query: &crate::query::QueryDef,
+    request_params_obj: &serde_json::Map<String, serde_json::Value>,
+    returns_fields: &[String],
+    transaction: &mut tokio_postgres::Transaction<'_>,
+    cache: Option<&PostgresStatementCache>,
+) -> anyhow::Result<(Vec<ExecutedStatement>, Vec<serde_json::Value>, bool)> {
+    let mut statements = Vec::new();
+    let mut result_data = Vec::new();
+    let mut truncated = false;
+    let writes = looks_like_write_statement(statement_sql);
+    for prepared in
+        prepare_single_statement_postgresql(statement_sql, &query.parameters, request_params_obj)?
+    {
+        let params = prepared.as_positional_params();
+        let row_stream =
+            match cached_statement(transaction, &prepared.executed.sql, cache).await? {
+                Some(statement) => transaction.query_raw(&statement, params).await,
+                None => transaction.query_raw(&prepared.executed.sql, params).await,
+            }
             .map_err(anyhow::Error::from)?;
 
-        let result_data = map_rows_to_json_data(rows, &returns_fields)?;
+        let mut row_stream = std::pin::pin!(row_stream);
+        while let Some(row) = row_stream.next().await {
+            let row = row.map_err(anyhow::Error::from)?;
+            if let Some(limit) = query.max_rows
+                && result_data.len() == limit.max_rows
+            {
+                limit.exceeded(&prepared.executed.sql)?;
+                truncated = true;
+                if writes {
+                    continue;
+                }
+                break;
+            }
+            let obj = row_to_json_object(&row, returns_fields, query.blob_encoding)?;
+            result_data.push(serde_json::Value::Object(obj));
+        }
+        statements.push(prepared.executed);
+        if truncated && !writes {
+            break;
+        }
+    }
 
-        Ok(QueryResult {
-            sql_statements: vec![positional_sql],
-            data: result_data,
-        })
-    } else {
-        // Mutation query (INSERT/UPDATE/DELETE/etc.) - split and execute within transaction
-        let sql_statements = execute_mutation_query(query, request_params_obj, transaction).await?;
-        Ok(QueryResult {
-            sql_statements,
-            data: vec![],
-        })
+    Ok((statements, result_data, truncated))
+}
+
+// Execute each statement of a multi-statement query with its own returns specification
+// Statements with returns produce a result set, the others are executed as mutations
+async fn execute_per_statement_query(
+    query: &crate::query::QueryDef,
+    statement_returns: &[crate::query::ReturnsSpec],
+    request_params_obj: &serde_json::Map<String, serde_json::Value>,
+    transaction: &mut tokio_postgres::Transaction<'_>,
+    cache: Option<&PostgresStatementCache>,
+) -> anyhow::Result<QueryResult> {
+    let mut statements = Vec::new();
+    let mut result_sets = Vec::new();
+    let mut truncated = false;
+
+    let statement_sqls = split_sql_statements(&query.sql);
+    for (statement_sql, returns_spec) in statement_sqls.iter().zip(statement_returns) {
+        let returns_fields = resolve_returns(returns_spec, request_params_obj)?;
+        let (executed, rows) = if returns_fields.is_empty() {
+            let executed = execute_single_statement(
+                transaction,
+                statement_sql,
+                &query.parameters,
+                request_params_obj,
+                cache,
+            )
+            .await?;
+            (executed, Vec::new())
+        } else {
+            // Each result set is limited on its own
+            let (executed, rows, statement_truncated) = execute_read_query(
+                statement_sql,
+                query,
+                request_params_obj,
+                &returns_fields,
+                transaction,
+                cache,
+            )
+            .await?;
+            truncated |= statement_truncated;
+            (executed, rows)
+        };
+        statements.extend(executed);
+        result_sets.push(rows);
     }
+
+    let mut query_result = QueryResult::from_statements(statements, vec![], result_sets);
+    query_result.truncated = truncated;
+    Ok(query_result)
+}
+
+// Look up a query definition and validate that the request parameters are an object
+fn find_query<'a>(
+    queries: &'a QueryDefinitions,
+    query_name: &str,
+    request_params: &'a serde_json::Value,
+) -> anyhow::Result<(
+    &'a crate::query::QueryDef,
+    &'a serde_json::Map<String, serde_json::Value>,
+)> {
+    let query = queries
+        .definitions
+        .get(query_name)
+        .ok_or_else(|| JankenError::new_query_not_found(query_name))?;
+
+    let request_params_obj = request_params
+        .as_object()
+        .ok_or_else(|| JankenError::new_parameter_type_mismatch("object", "not object"))?;
+
+    Ok((query, request_params_obj))
+}
+
+/// Execute a query inside a savepoint of a user-provided PostgreSQL transaction.
+/// On success the savepoint is released and the changes become part of the caller's transaction;
+/// on failure only this query is rolled back and the error is returned, so the transaction is not
+/// left aborted and can continue with further steps (e.g. optional "insert unless duplicate" steps).
+/// If the rollback itself fails, the query error is still returned, with the rollback failure
+/// attached as context.
+pub async fn query_run_postgresql_with_savepoint(
+    transaction: &mut tokio_postgres::Transaction<'_>,
+    queries: &QueryDefinitions,
+    query_name: &str,
+    request_params: &serde_json::Value,
+) -> anyhow::Result<QueryResult> {
+    let mut savepoint = transaction
+        .savepoint("janken_step")
+        .await
+        .map_err(anyhow::Error::from)?;
+    match query_run_postgresql_with_transaction(&mut savepoint, queries, query_name, request_params)
+        .await
+    {
+        Ok(query_result) => {
+            savepoint.commit().await.map_err(anyhow::Error::from)?;
+            Ok(query_result)
+        }
+        // The query error is what the caller needs; a failed rollback is attached to it as context
+        Err(err) => match savepoint.rollback().await {
+            Ok(()) => Err(err),
+            Err(rollback_err) => Err(err.context(format!(
+                "rolling back savepoint janken_step also failed: {rollback_err}"
+            ))),
+        },
+    }
+}
+
+/// Start a transaction configured with the query's transaction options.
+/// Queries declared `readonly` always get a `READ ONLY` transaction.
+async fn begin_transaction<'c>(
+    client: &'c mut Client,
+    query: &crate::query::QueryDef,
+) -> anyhow::Result<tokio_postgres::Transaction<'c>> {
+    begin_transaction_with_options(client, &query.transaction, query.readonly).await
+}
+
+/// Open a transaction with the given options, read-only when `readonly` or `options.read_only`
+async fn begin_transaction_with_options<'c>(
+    client: &'c mut Client,
+    options: &TransactionOptions,
+    readonly: bool,
+) -> anyhow::Result<tokio_postgres::Transaction<'c>> {
+    let mut builder = client
+        .build_transaction()
+        .read_only(readonly || options.read_only);
+    if let Some(isolation) = options.isolation {
+        builder = builder.isolation_level(isolation.into());
+    }
+    if options.deferrable {
+        builder = builder.deferrable(true);
+    }
+    builder.start().await.map_err(anyhow::Error::from)
 }
 
 /// Execute a query within a user-provided PostgreSQL transaction.
 /// This allows the caller to manage the transaction lifecycle (begin/commit/rollback),
 /// enabling multiple `query_run` calls within the same transaction.
+/// Queries declared `readonly` run inside a read-only savepoint that is rolled back afterwards,
+/// leaving the caller's transaction mode untouched.
 pub async fn query_run_postgresql_with_transaction(
     transaction: &mut tokio_postgres::Transaction<'_>,
     queries: &QueryDefinitions,
     query_name: &str,
     request_params: &serde_json::Value,
 ) -> anyhow::Result<QueryResult> {
+    let (query, request_params_obj) = find_query(queries, query_name, request_params)?;
+    if !query.readonly {
+        return execute_query_unified(query, request_params_obj, transaction).await;
+    }
+
+    let mut savepoint = transaction
+        .savepoint("janken_readonly")
+        .await
+        .map_err(anyhow::Error::from)?;
+    savepoint
+        .batch_execute("SET TRANSACTION READ ONLY")
+        .await
+        .map_err(anyhow::Error::from)?;
+    let query_result = execute_query_unified(query, request_params_obj, &mut savepoint).await;
+    savepoint.rollback().await.map_err(anyhow::Error::from)?;
+    query_result
+}
+
+/// Execute queries with PostgreSQL backend.
+/// This is the main entry point for PostgreSQL operations.
+/// It creates a transaction internally with the query's transaction options,
+/// executes the query, and commits.
+/// Queries declared `readonly` run in a `READ ONLY` transaction.
+/// With a retry policy, the whole transaction is re-run after serialization failures and deadlocks.
+pub async fn query_run_postgresql(
+    client: &mut Client,
+    queries: &QueryDefinitions,
+    query_name: &str,
+    request_params: &serde_json::Value,
+) -> anyhow::Result<QueryResult> {
+    run_query_postgresql(client, queries, query_name, request_params, None).await
+}
+
+/// Execute a query like `query_run_postgresql`, reusing the server-side prepared statements
+/// kept in `cache` (keyed by the rendered SQL) instead of preparing every statement again.
+/// Prepared statements belong to the connection, so keep one cache per client.
+pub async fn query_run_postgresql_cached(
+    client: &mut Client,
+    cache: &PostgresStatementCache,
+    queries: &QueryDefinitions,
+    query_name: &str,
+    request_params: &serde_json::Value,
+) -> anyhow::Result<QueryResult> {
+    run_query_postgresql(client, queries, query_name, request_params, Some(cache)).await
+}
+
+/// Run a query in its own transaction, re-running it according to its retry policy
+async fn run_query_postgresql(
+    client: &mut Client,
+    queries: &QueryDefinitions,
+    query_name: &str,
+    request_params: &serde_json::Value,
+    cache: Option<&PostgresStatementCache>,
+) -> anyhow::Result<QueryResult> {
+    let (query, request_params_obj) = find_query(queries, query_name, request_params)?;
+
+    let mut attempt = 1;
+    loop {
+        match run_in_new_transaction(client, query, request_params_obj, cache).await {
+            Ok(mut query_result) => {
+                query_result.attempts = attempt;
+                return Ok(query_result);
+            }
+            Err(err) => match query.transaction.retry {
+                Some(policy) if attempt < policy.max_attempts && is_retryable_error(&err) => {
+                    tokio::time::sleep(policy.delay_after(attempt)).await;
+                    attempt += 1;
+                }
+                _ => return Err(err),
+            },
+        }
+    }
+}
+
+/// Run a query in a transaction of its own and commit it
+async fn run_in_new_transaction(
+    client: &mut Client,
+    query: &crate::query::QueryDef,
+    request_params_obj: &serde_json::Map<String, serde_json::Value>,
+    cache: Option<&PostgresStatementCache>,
+) -> anyhow::Result<QueryResult> {
+    let mut transaction = begin_transaction(client, query).await?;
+
+    let query_result =
+        execute_query_with_cache(query, request_params_obj, &mut transaction, cache).await?;
+
+    transaction.commit().await.map_err(anyhow::Error::from)?;
+    Ok(query_result)
+}
+
+/// Whether an error is a serialization failure or deadlock that is worth re-running the transaction for
+fn is_retryable_error(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<tokio_postgres::Error>()
+        .and_then(|pg_err| pg_err.code())
+        .is_some_and(|code| {
+            *code == tokio_postgres::error::SqlState::T_R_SERIALIZATION_FAILURE
+                || *code == tokio_postgres::error::SqlState::T_R_DEADLOCK_DETECTED
+        })
+}
+
+/// Execute a workflow within a user-provided PostgreSQL transaction.
+/// Steps run in order as `query_run_postgresql_with_transaction` calls, each with parameters
+/// resolved from the request and the results of the earlier steps.
+pub async fn workflow_run_postgresql_with_transaction(
+    transaction: &mut tokio_postgres::Transaction<'_>,
+    queries: &QueryDefinitions,
+    workflow_name: &str,
+    request_params: &serde_json::Value,
+) -> anyhow::Result<WorkflowResult> {
+    let workflow = queries
+        .workflows
+        .get(workflow_name)
+        .ok_or_else(|| JankenError::new_query_not_found(workflow_name))?;
+
+    let mut steps = Vec::new();
+    for step in &workflow.steps {
+        let step_params = step.resolve_params(request_params, &steps)?;
+        let result =
+            query_run_postgresql_with_transaction(transaction, queries, &step.query, &step_params)
+                .await?;
+        steps.push(WorkflowStepResult {
+            name: step.name.clone(),
+            result,
+        });
+    }
+    Ok(WorkflowResult { steps, attempts: 1 })
+}
+
+/// Execute a workflow with PostgreSQL backend.
+/// It creates a transaction internally with the workflow's transaction options, runs every
+/// step, and commits; a failing step rolls back the whole workflow.
+/// With a retry policy, the whole workflow is re-run after serialization failures and deadlocks.
+pub async fn workflow_run_postgresql(
+    client: &mut Client,
+    queries: &QueryDefinitions,
+    workflow_name: &str,
+    request_params: &serde_json::Value,
+) -> anyhow::Result<WorkflowResult> {
+    let workflow = queries
+        .workflows
+        .get(workflow_name)
+        .ok_or_else(|| JankenError::new_query_not_found(workflow_name))?;
+    let options = &workflow.transaction;
+
+    let mut attempt = 1;
+    loop {
+        match run_workflow_in_new_transaction(
+            client,
+            options,
+            queries,
+            workflow_name,
+            request_params,
+        )
+        .await
+        {
+            Ok(mut workflow_result) => {
+                workflow_result.attempts = attempt;
+                return Ok(workflow_result);
+            }
+            Err(err) => match options.retry {
+                Some(policy) if attempt < policy.max_attempts && is_retryable_error(&err) => {
+                    tokio::time::sleep(policy.delay_after(attempt)).await;
+                    attempt += 1;
+                }
+                _ => return Err(err),
+            },
+        }
+    }
+}
+
+/// Run a workflow in a transaction of its own and commit it
+async fn run_workflow_in_new_transaction(
+    client: &mut Client,
+    options: &TransactionOptions,
+    queries: &QueryDefinitions,
+    workflow_name: &str,
+    request_params: &serde_json::Value,
+) -> anyhow::Result<WorkflowResult> {
+    let mut transaction = begin_transaction_with_options(client, options, false).await?;
+
+    let workflow_result = workflow_run_postgresql_with_transaction(
+        &mut transaction,
+        queries,
+        workflow_name,
+        request_params,
+    )
+    .await?;
+
+    transaction.commit().await.map_err(anyhow::Error::from)?;
+    Ok(workflow_result)
+}
+
+/// Validate one batch item and prepare its statements
+fn prepare_batch_item(
+    query: &crate::query::QueryDef,
+    request_params: &serde_json::Value,
+) -> anyhow::Result<Vec<PreparedStatement>> {
+    let request_params_obj = request_params
+        .as_object()
+        .ok_or_else(|| JankenError::new_parameter_type_mismatch("object", "not object"))?;
+
+    let mut prepared = Vec::new();
+    for statement_sql in query.statement_sqls(request_params_obj)? {
+        prepared.extend(prepare_single_statement_postgresql(
+            &statement_sql,
+            &query.parameters,
+            request_params_obj,
+        )?);
+    }
+    Ok(prepared)
+}
+
+/// Execute one query with many parameter sets within a user-provided PostgreSQL transaction.
+/// Every parameter set is validated before anything runs, each distinct expanded SQL (list
+/// parameters change the shape) is prepared once, and affected row counts are reported per item.
+/// A failing item is reported as `JankenError::BatchItemFailed` carrying its index; the
+/// underlying error remains available through `downcast`.
+pub async fn query_batch_postgresql_with_transaction(
+    transaction: &mut tokio_postgres::Transaction<'_>,
+    queries: &QueryDefinitions,
+    query_name: &str,
+    params_list: &[serde_json::Value],
+) -> anyhow::Result<BatchResult> {
     let query = queries
         .definitions
         .get(query_name)
         .ok_or_else(|| JankenError::new_query_not_found(query_name))?;
+    if !matches!(&query.returns, crate::query::ReturnsSpec::Static(fields) if fields.is_empty()) {
+        return Err(JankenError::new_parameter_type_mismatch(
+            "query without returns for batch",
+            format!("{query_name} has returns"),
+        )
+        .into());
+    }
 
-    let request_params_obj = request_params
-        .as_object()
-        .ok_or_else(|| JankenError::new_parameter_type_mismatch("object", "not object"))?;
+    let items = params_list
+        .iter()
+        .enumerate()
+        .map(|(index, request_params)| {
+            prepare_batch_item(query, request_params).map_err(|err| batch_item_error(index, err))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let mut statements: HashMap<String, tokio_postgres::Statement> = HashMap::new();
+    let mut row_counts = Vec::with_capacity(items.len());
+    for (index, item) in items.iter().enumerate() {
+        let mut item_rows = 0;
+        for prepared in item {
+            let sql = &prepared.executed.sql;
+            if !statements.contains_key(sql) {
+                let stmt = transaction
+                    .prepare(sql)
+                    .await
+                    .map_err(|err| batch_item_error(index, err.into()))?;
+                statements.insert(sql.clone(), stmt);
+            }
+            item_rows += transaction
+                .execute(&statements[sql], &prepared.as_positional_params())
+                .await
+                .map_err(|err| batch_item_error(index, err.into()))?;
+        }
+        row_counts.push(item_rows);
+    }
 
-    execute_query_unified(query, request_params_obj, transaction).await
+    Ok(BatchResult {
+        row_counts,
+        prepared_statements: statements.len(),
+    })
 }
 
-/// Execute queries with PostgreSQL backend.
-/// This is the main entry point for PostgreSQL operations.
-/// It creates a transaction internally, executes the query, and commits.
-pub async fn query_run_postgresql(
+/// Execute one query with many parameter sets with PostgreSQL backend.
+/// It creates a transaction internally with the query's transaction options, executes every
+/// item, and commits; a failing item rolls back the whole batch.
+pub async fn query_batch_postgresql(
+    client: &mut Client,
+    queries: &QueryDefinitions,
+    query_name: &str,
+    params_list: &[serde_json::Value],
+) -> anyhow::Result<BatchResult> {
+    let query = queries
+        .definitions
+        .get(query_name)
+        .ok_or_else(|| JankenError::new_query_not_found(query_name))?;
+    let mut transaction = begin_transaction(client, query).await?;
+
+    let batch_result =
+        query_batch_postgresql_with_transaction(&mut transaction, queries, query_name, params_list)
+            .await?;
+
+    transaction.commit().await.map_err(anyhow::Error::from)?;
+    Ok(batch_result)
+}
+
+// Resolve the returns of a query whose rows are delivered incrementally (streaming or export)
+fn resolve_streaming_returns(
+    query: &crate::query::QueryDef,
+    query_name: &str,
+    request_params_obj: &serde_json::Map<String, serde_json::Value>,
+) -> anyhow::Result<Vec<String>> {
+    let returns_fields = resolve_returns(&query.returns, request_params_obj)?;
+    if returns_fields.is_empty() {
+        return Err(JankenError::new_parameter_type_mismatch(
+            "query with returns for streaming",
+            format!("{query_name} has no returns"),
+        )
+        .into());
+    }
+    Ok(returns_fields)
+}
+
+// Start a read query with `query_raw` and map each fetched row to a JSON object
+async fn stream_read_query(
+    query: &crate::query::QueryDef,
+    request_params_obj: &serde_json::Map<String, serde_json::Value>,
+    returns_fields: Vec<String>,
+    blob_encoding: BlobEncoding,
+    transaction: &tokio_postgres::Transaction<'_>,
+) -> anyhow::Result<(
+    ExecutedStatement,
+    impl Stream<Item = anyhow::Result<serde_json::Value>> + use<>,
+)> {
+    // Streamed rows come from a single portal, so the statement is never split into chunks
+    let prepared = to_postgresql_statement(&parameters::prepare_parameter_statement_generic(
+        &parameters::bind_list_arrays(&query.sql, &query.parameters),
+        &query.parameters,
+        request_params_obj,
+    )?)?;
+
+    let row_stream = transaction
+        .query_raw(&prepared.executed.sql, prepared.as_positional_params())
+        .await
+        .map_err(anyhow::Error::from)?;
+
+    let rows = row_stream.map(move |row| {
+        let row = row.map_err(anyhow::Error::from)?;
+        let obj = row_to_json_object(&row, &returns_fields, blob_encoding)?;
+        Ok(serde_json::Value::Object(obj))
+    });
+    Ok((prepared.executed, rows))
+}
+
+/// Stream the rows of a read query within a user-provided PostgreSQL transaction.
+/// Rows are fetched with `query_raw` and mapped with the query's `returns` fields one at a time,
+/// so large result sets can be forwarded (e.g. to an HTTP response) without being collected.
+/// The stream does not borrow the transaction, but it must be drained before the transaction ends.
+pub async fn query_stream_postgresql_with_transaction(
+    transaction: &mut tokio_postgres::Transaction<'_>,
+    queries: &QueryDefinitions,
+    query_name: &str,
+    request_params: &serde_json::Value,
+) -> anyhow::Result<impl Stream<Item = anyhow::Result<serde_json::Value>> + use<>> {
+    let (query, request_params_obj) = find_query(queries, query_name, request_params)?;
+    let returns_fields = resolve_streaming_returns(query, query_name, request_params_obj)?;
+
+    let (_, rows) = stream_read_query(
+        query,
+        request_params_obj,
+        returns_fields,
+        query.blob_encoding,
+        transaction,
+    )
+    .await?;
+    Ok(rows)
+}
+
+/// Export the rows of a read query as CSV or NDJSON within a user-provided PostgreSQL transaction.
+/// Rows are streamed straight into `writer`, so the full result set is never held in memory.
+pub async fn query_export_postgresql_with_transaction(
+    transaction: &mut tokio_postgres::Transaction<'_>,
+    queries: &QueryDefinitions,
+    query_name: &str,
+    request_params: &serde_json::Value,
+    writer: impl std::io::Write,
+    options: &ExportOptions,
+) -> anyhow::Result<QueryResult> {
+    let (query, request_params_obj) = find_query(queries, query_name, request_params)?;
+    let returns_fields = resolve_streaming_returns(query, query_name, request_params_obj)?;
+
+    let mut result_writer = ResultWriter::new(writer, returns_fields.clone(), options.clone())?;
+    let (executed, rows) = stream_read_query(
+        query,
+        request_params_obj,
+        returns_fields,
+        options.blob_encoding,
+        transaction,
+    )
+    .await?;
+
+    let mut rows = std::pin::pin!(rows);
+    while let Some(row) = rows.next().await {
+        result_writer.write_row(&row?)?;
+    }
+    result_writer.finish()?;
+
+    Ok(QueryResult::from_statements(vec![executed], vec![], vec![]))
+}
+
+/// Export the rows of a read query as CSV or NDJSON with PostgreSQL backend.
+/// It creates a transaction internally with the query's transaction options,
+/// writes every row to `writer`, and commits.
+pub async fn query_export_postgresql(
     client: &mut Client,
     queries: &QueryDefinitions,
     query_name: &str,
     request_params: &serde_json::Value,
+    writer: impl std::io::Write,
+    options: &ExportOptions,
 ) -> anyhow::Result<QueryResult> {
-    let mut transaction = client.transaction().await.map_err(anyhow::Error::from)?;
+    let (query, _) = find_query(queries, query_name, request_params)?;
+    let mut transaction = begin_transaction(client, query).await?;
 
-    let query_result = query_run_postgresql_with_transaction(
+    let query_result = query_export_postgresql_with_transaction(
         &mut transaction,
         queries,
         query_name,
         request_params,
+        writer,
+        options,
     )
     .await?;
 
@@ -379,6 +1072,54 @@ pub async fn query_run_postgresql(
     Ok(query_result)
 }
 
+/// Explain a named query with PostgreSQL's `EXPLAIN (FORMAT JSON)`.
+/// Parameters are validated and bound exactly as in `query_run_postgresql`. With `analyze`,
+/// `EXPLAIN (ANALYZE, FORMAT JSON)` actually runs each statement, so everything happens inside
+/// a transaction that is always rolled back.
+/// Returns a JSON array with one `{"sql", "plan"}` object per statement, where `plan` is the
+/// JSON document produced by PostgreSQL.
+pub async fn query_explain_postgresql(
+    client: &mut Client,
+    queries: &QueryDefinitions,
+    query_name: &str,
+    request_params: &serde_json::Value,
+    analyze: bool,
+) -> anyhow::Result<serde_json::Value> {
+    let (query, request_params_obj) = find_query(queries, query_name, request_params)?;
+    let transaction = begin_transaction(client, query).await?;
+
+    let explain_options = if analyze {
+        "ANALYZE, FORMAT JSON"
+    } else {
+        "FORMAT JSON"
+    };
+
+    let mut explained = Vec::new();
+    let statement_sqls = query.statement_sqls(request_params_obj)?;
+    for statement_sql in &statement_sqls {
+        for prepared in prepare_single_statement_postgresql(
+            statement_sql,
+            &query.parameters,
+            request_params_obj,
+        )? {
+            let explain_sql = format!("EXPLAIN ({explain_options}) {}", prepared.executed.sql);
+            let row = transaction
+                .query_one(&explain_sql, &prepared.as_positional_params())
+                .await
+                .map_err(anyhow::Error::from)?;
+            let plan: serde_json::Value = row.try_get(0)?;
+
+            explained.push(serde_json::json!({
+                "sql": prepared.executed.sql,
+                "plan": plan,
+            }));
+        }
+    }
+
+    transaction.rollback().await.map_err(anyhow::Error::from)?;
+    Ok(serde_json::Value::Array(explained))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -387,7 +1128,7 @@ mod tests {
     fn test_parameter_value_float_to_postgresql_conversion() {
         let float_param = crate::parameters::ParameterValue::Float(3.15);
         let _sql_float: Box<dyn tokio_postgres::types::ToSql + Sync> =
-            parameter_value_to_postgresql_tosql(float_param);
+            parameter_value_to_postgresql_tosql(float_param).unwrap();
         // The conversion function works correctly if it doesn't panic
     }
 
@@ -395,7 +1136,7 @@ mod tests {
     fn test_parameter_value_boolean_to_postgresql_conversion() {
         let bool_param = crate::parameters::ParameterValue::Boolean(true);
         let _sql_bool: Box<dyn tokio_postgres::types::ToSql + Sync> =
-            parameter_value_to_postgresql_tosql(bool_param);
+            parameter_value_to_postgresql_tosql(bool_param).unwrap();
         // The conversion function works correctly if it doesn't panic
     }
 
@@ -403,10 +1144,54 @@ mod tests {
     fn test_parameter_value_blob_to_postgresql_conversion() {
         let blob_param = crate::parameters::ParameterValue::Blob(vec![1, 2, 3, 255]);
         let _sql_blob: Box<dyn tokio_postgres::types::ToSql + Sync> =
-            parameter_value_to_postgresql_tosql(blob_param);
+            parameter_value_to_postgresql_tosql(blob_param).unwrap();
         // The conversion function works correctly if it doesn't panic
     }
 
+    #[test]
+    fn test_parameter_value_array_to_postgresql_conversion_errors() {
+        let type_mismatch = |item_type, items| {
+            let array = ParameterValue::Array { item_type, items };
+            match parameter_value_to_postgresql_tosql(array)
+                .err()
+                .unwrap()
+                .downcast::<JankenError>()
+                .unwrap()
+            {
+                JankenError::ParameterTypeMismatch { data } => (
+                    crate::error_meta(&data, crate::M_EXPECTED).unwrap(),
+                    crate::error_meta(&data, crate::M_GOT).unwrap(),
+                ),
+                other => panic!("Expected ParameterTypeMismatch, got {other:?}"),
+            }
+        };
+
+        // An item of another type is an error rather than being left out of the array
+        assert_eq!(
+            type_mismatch(
+                ParameterType::Float,
+                vec![
+                    ParameterValue::Float(1.5),
+                    ParameterValue::String("2".to_string())
+                ]
+            ),
+            ("float array item".to_string(), "String(\"2\")".to_string())
+        );
+        assert_eq!(
+            type_mismatch(
+                ParameterType::Integer,
+                vec![
+                    ParameterValue::Integer(1),
+                    ParameterValue::Integer(i64::from(i32::MAX) + 1)
+                ]
+            ),
+            (
+                "integer within the 32-bit range of a PostgreSQL integer array".to_string(),
+                "2147483648".to_string()
+            )
+        );
+    }
+
     // Tests for resolve_returns function
     #[test]
     fn test_resolve_returns_static_multiple_fields() {
@@ -1,12 +1,31 @@
 use crate::{
-    QueryDefinitions, parameters,
-    result::{JankenError, QueryResult},
-    str_utils::split_sql_statements,
+    QueryDefinitions,
+    encoding::{BlobEncoding, encode_blob},
+    export::{ExportOptions, ResultWriter},
+    pagination::PageQuery,
+    parameters::{self, Dialect, ExecutedStatement},
+    query::RowLimit,
+    result::{BatchResult, JankenError, QueryResult, batch_item_error},
+    statement_cache::SqliteStatementCache,
+    str_utils::{looks_like_write_statement, split_sql_statements},
+    transaction::TransactionBehavior,
+    workflow::{WorkflowResult, WorkflowStepResult},
 };
 
 // Import generic types for parameter decoupling
 use parameters::ParameterValue;
 use rusqlite::Connection;
+use std::collections::{HashMap, hash_map::Entry};
+
+impl From<TransactionBehavior> for rusqlite::TransactionBehavior {
+    fn from(behavior: TransactionBehavior) -> Self {
+        match behavior {
+            TransactionBehavior::Deferred => rusqlite::TransactionBehavior::Deferred,
+            TransactionBehavior::Immediate => rusqlite::TransactionBehavior::Immediate,
+            TransactionBehavior::Exclusive => rusqlite::TransactionBehavior::Exclusive,
+        }
+    }
+}
 
 // Implement trait for converting generic ParameterValue to SQLite-specific ToSql
 impl From<ParameterValue> for Box<dyn rusqlite::ToSql> {
@@ -17,6 +36,14 @@ impl From<ParameterValue> for Box<dyn rusqlite::ToSql> {
             ParameterValue::Float(f) => Box::new(f),
             ParameterValue::Boolean(b) => Box::new(b as i32), // SQLite represents booleans as integers
             ParameterValue::Blob(bytes) => Box::new(bytes),
+            // SQLite always expands list parameters into one placeholder per item
+            ParameterValue::Array { .. } => {
+                unreachable!("array values are only bound by the PostgreSQL runner")
+            }
+            // to_executed_statement renders full-text queries as strings
+            ParameterValue::FtsQuery(_) => {
+                unreachable!("fts queries are bound as their SQLite query string")
+            }
         }
     }
 }
@@ -24,8 +51,8 @@ impl From<ParameterValue> for Box<dyn rusqlite::ToSql> {
 /// Result of preparing a single SQL statement with parameter conversion
 /// SQLite-specific due to rusqlite named parameter format
 struct PreparedStatement {
-    /// The SQL with placeholders ready for execution
-    sql: String,
+    /// The SQL with placeholders ready for execution, along with its bound values for reporting
+    executed: ExecutedStatement,
     /// Named parameters ready for rusqlite execution: (:name, value)
     named_params: Vec<(String, Box<dyn rusqlite::ToSql>)>,
 }
@@ -33,73 +60,106 @@ struct PreparedStatement {
 /// Method to convert parameters to Rusqlite named params format
 /// SQLite-specific due to rusqlite trait
 impl PreparedStatement {
-    /// Get parameters in rusqlite named parameter format
-    fn as_named_params(&self) -> Vec<(&str, &dyn rusqlite::ToSql)> {
-        self.named_params
-            .iter()
-            .map(|(name, value)| (name.as_str(), value.as_ref()))
-            .collect()
+    /// Bind the parameter values to a statement prepared from `executed.sql`
+    /// SQLite numbers named placeholders in order of first appearance, which is the order of
+    /// the bound values, so they are bound by index; looking every name up is quadratic for
+    /// statements with many placeholders (e.g. expanded rows)
+    fn bind(&self, stmt: &mut rusqlite::Statement) -> rusqlite::Result<()> {
+        let by_index = stmt.parameter_count() == self.named_params.len();
+        for (position, (name, value)) in self.named_params.iter().enumerate() {
+            let index = if by_index {
+                position + 1
+            } else {
+                stmt.parameter_index(name)?
+                    .ok_or_else(|| rusqlite::Error::InvalidParameterName(name.clone()))?
+            };
+            stmt.raw_bind_parameter(index, value)?;
+        }
+        Ok(())
     }
 }
 
-/// Create a prepared statement from SQL using the generic parameter decoupling approach
+/// Create prepared statements from SQL using the generic parameter decoupling approach
 /// This separates parameter analysis (generic) from database-specific conversions (SQLite-specific)
+/// A statement binding more values than SQLite allows is split into several chunks
 fn prepare_single_statement_sqlite(
     statement_sql: &str,
     all_parameters: &[crate::parameters::Parameter],
     request_params_obj: &serde_json::Map<String, serde_json::Value>,
-) -> anyhow::Result<PreparedStatement> {
+) -> anyhow::Result<Vec<PreparedStatement>> {
     // Use the generic parameter preparation (database-agnostic)
-    let mut generic_statement = parameters::prepare_parameter_statement_generic(
+    let generic_statements = parameters::prepare_dialect_statement_chunks(
         statement_sql,
         all_parameters,
         request_params_obj,
+        Dialect::Sqlite,
     )?;
 
-    // Convert @param placeholders to :param for SQLite
-    for param_name in &parameters::extract_parameters_with_regex(
-        &generic_statement.sql,
-        &parameters::PARAMETER_REGEX,
-    ) {
-        generic_statement.sql = generic_statement
-            .sql
-            .replace(&format!("@{param_name}"), &format!(":{param_name}"));
-    }
+    Ok(generic_statements
+        .iter()
+        .map(|generic_statement| {
+            // Convert @param placeholders to :param for SQLite
+            let executed = generic_statement.to_executed_statement(Dialect::Sqlite);
 
-    // Convert generic parameters to SQLite-specific ToSql types
-    let sqlite_params = generic_statement
-        .parameters
-        .into_iter()
-        .map(|(name, param_value)| {
-            // Parameter names from generic function are already converted to SQLite format
-            let sqlite_name = format!(":{name}");
-            let to_sql: Box<dyn rusqlite::ToSql> = param_value.into();
-            (sqlite_name, to_sql)
+            // Convert generic parameters to SQLite-specific ToSql types
+            let sqlite_params = executed
+                .parameters
+                .iter()
+                .map(|bound| {
+                    let to_sql: Box<dyn rusqlite::ToSql> = bound.value.clone().into();
+                    (bound.placeholder.clone(), to_sql)
+                })
+                .collect();
+
+            PreparedStatement {
+                executed,
+                named_params: sqlite_params,
+            }
         })
-        .collect();
+        .collect())
+}
 
-    Ok(PreparedStatement {
-        sql: generic_statement.sql,
-        named_params: sqlite_params,
-    })
+/// Prepare a statement through the connection's statement cache, refusing statements that write
+/// when the query is declared readonly (checked with SQLite's own `sqlite3_stmt_readonly`)
+/// With a `SqliteStatementCache`, the lookup is counted as a hit or a miss
+fn prepare_for_query<'tx>(
+    tx: &'tx rusqlite::Transaction,
+    query: &crate::query::QueryDef,
+    sql: &str,
+    cache: Option<&SqliteStatementCache>,
+) -> anyhow::Result<rusqlite::CachedStatement<'tx>> {
+    if let Some(cache) = cache
+        && cache.get(sql).is_none()
+    {
+        cache.insert(sql, ());
+    }
+    let stmt = tx.prepare_cached(sql)?;
+    if query.readonly && !stmt.readonly() {
+        return Err(JankenError::new_readonly_violation(sql).into());
+    }
+    Ok(stmt)
 }
 
-/// Execute a single SQL statement with its appropriate parameters
+/// Execute a single SQL statement with its appropriate parameters, once per chunk
 /// SQLite-specific due to rusqlite::Transaction
 fn execute_single_statement(
     tx: &rusqlite::Transaction,
     statement_sql: &str,
-    all_parameters: &[crate::parameters::Parameter],
+    query: &crate::query::QueryDef,
     request_params_obj: &serde_json::Map<String, serde_json::Value>,
-) -> anyhow::Result<String> {
-    let prepared =
-        prepare_single_statement_sqlite(statement_sql, all_parameters, request_params_obj)?;
-    let named_params = prepared.as_named_params();
-
-    // Now execute with the named parameter values
-    let mut stmt = tx.prepare(&prepared.sql)?;
-    stmt.execute(&named_params[..])?;
-    Ok(prepared.sql)
+    cache: Option<&SqliteStatementCache>,
+) -> anyhow::Result<Vec<ExecutedStatement>> {
+    let mut statements = Vec::new();
+    for prepared in
+        prepare_single_statement_sqlite(statement_sql, &query.parameters, request_params_obj)?
+    {
+        // Now execute with the named parameter values
+        let mut stmt = prepare_for_query(tx, query, &prepared.executed.sql, cache)?;
+        prepared.bind(&mut stmt)?;
+        stmt.raw_execute()?;
+        statements.push(prepared.executed);
+    }
+    Ok(statements)
 }
 
 /// Execute mutation query (INSERT/UPDATE/DELETE/etc.) - split and execute within transaction
@@ -108,33 +168,26 @@ fn execute_mutation_query(
     query: &crate::query::QueryDef,
     request_params_obj: &serde_json::Map<String, serde_json::Value>,
     tx: &rusqlite::Transaction,
-) -> anyhow::Result<Vec<String>> {
-    let mut sql_statements = Vec::new();
+    cache: Option<&SqliteStatementCache>,
+) -> anyhow::Result<Vec<ExecutedStatement>> {
+    let mut statements = Vec::new();
     if query.sql.contains(';') {
         // Has parameters - split into individual statements and execute each one
         let individual_statements = split_sql_statements(&query.sql);
 
         for statement_sql in individual_statements {
             // Execute each statement with the appropriate parameters
-            let sql = execute_single_statement(
-                tx,
-                &statement_sql,
-                &query.parameters,
-                request_params_obj,
-            )?;
-            sql_statements.push(sql);
+            let executed =
+                execute_single_statement(tx, &statement_sql, query, request_params_obj, cache)?;
+            statements.extend(executed);
         }
     } else {
         // Single-statement mutation - prepare and execute normally with all parameters
-        let prepared =
-            prepare_single_statement_sqlite(&query.sql, &query.parameters, request_params_obj)?;
-        let named_params = prepared.as_named_params();
-        let mut stmt = tx.prepare(&prepared.sql)?;
-        stmt.execute(&named_params[..])?;
-        sql_statements.push(prepared.sql);
+        let executed = execute_single_statement(tx, &query.sql, query, request_params_obj, cache)?;
+        statements.extend(executed);
     }
 
-    Ok(sql_statements)
+    Ok(statements)
 }
 
 /// Resolve the returns specification to actual field names
@@ -142,32 +195,107 @@ fn resolve_returns(
     returns_spec: &crate::query::ReturnsSpec,
     request_params_obj: &serde_json::Map<String, serde_json::Value>,
 ) -> anyhow::Result<Vec<String>> {
-    match returns_spec {
-        crate::query::ReturnsSpec::Static(fields) => Ok(fields.clone()),
-        crate::query::ReturnsSpec::Dynamic(param_name) => {
-            // Get the comma_list parameter value
-            let param_value = request_params_obj
-                .get(param_name)
-                .ok_or_else(|| JankenError::new_parameter_not_provided(param_name.clone()))?;
-
-            // Safe unwrap: parameter type already validated as array at definition time
-            let fields_array = param_value.as_array().ok_or_else(|| {
-                JankenError::new_parameter_type_mismatch(
-                    "array for comma_list parameter",
-                    param_value.to_string(),
-                )
-            })?;
-
-            // Convert to vector of strings
-            let fields: Vec<String> = fields_array
-                .iter()
-                .filter_map(|v| v.as_str())
-                .map(|s| s.to_string())
-                .collect();
+    Ok(returns_spec.resolve(request_params_obj)?)
+}
+
+/// Convert a single SQLite row to a JSON object containing the requested fields
+/// Fields without a matching column are mapped to null
+fn row_to_json_object(
+    row: &rusqlite::Row,
+    column_names: &[String],
+    returns: &[String],
+    blob_encoding: BlobEncoding,
+) -> serde_json::Value {
+    let mut obj = serde_json::Map::new();
+
+    for field_name in returns {
+        // Find the column index by matching the column name
+        let column_idx = column_names.iter().position(|name| name == field_name);
+
+        let value: serde_json::Value = match column_idx {
+            Some(idx) => match row.get_ref(idx).expect("column index should be valid") {
+                rusqlite::types::ValueRef::Integer(i) => serde_json::Value::Number(i.into()),
+                rusqlite::types::ValueRef::Real(r) => serde_json::Value::from(r),
+                rusqlite::types::ValueRef::Text(s) => {
+                    serde_json::Value::String(String::from_utf8_lossy(s).to_string())
+                }
+                rusqlite::types::ValueRef::Blob(b) => encode_blob(b, blob_encoding),
+                rusqlite::types::ValueRef::Null => serde_json::Value::Null,
+            },
+            None => serde_json::Value::Null,
+        };
+
+        obj.insert(field_name.clone(), value);
+    }
+    serde_json::Value::Object(obj)
+}
+
+/// How the rows of a read query are mapped to JSON objects
+struct RowMapping<'a> {
+    returns_fields: &'a [String],
+    blob_encoding: BlobEncoding,
+    /// Rows delivered before reading stops (streaming and export are never limited)
+    max_rows: Option<RowLimit>,
+}
+
+/// Execute a read query and hand each mapped row to `on_row` as soon as it is fetched
+/// Rows are never buffered here, so callers decide whether to collect or forward them
+/// Chunked statements deliver the rows of every chunk in order
+/// Reading stops at the mapping's `max_rows`; the returned flag tells whether rows were dropped
+/// Writing statements (e.g. chunked `INSERT ... RETURNING`) still run every chunk to completion,
+/// only the rows past the limit are dropped
+fn execute_read_query(
+    statement_sql: &str,
+    query: &crate::query::QueryDef,
+    request_params_obj: &serde_json::Map<String, serde_json::Value>,
+    mapping: RowMapping,
+    tx: &rusqlite::Transaction,
+    cache: Option<&SqliteStatementCache>,
+    on_row: &mut dyn FnMut(serde_json::Value) -> anyhow::Result<()>,
+) -> anyhow::Result<(Vec<ExecutedStatement>, bool)> {
+    let mut statements = Vec::new();
+    let mut delivered = 0;
+    let mut truncated = false;
+    let writes = looks_like_write_statement(statement_sql);
+    for prepared in
+        prepare_single_statement_sqlite(statement_sql, &query.parameters, request_params_obj)?
+    {
+        let mut stmt = prepare_for_query(tx, query, &prepared.executed.sql, cache)?;
+        prepared.bind(&mut stmt)?;
 
-            Ok(fields)
+        // Get column names from the prepared statement
+        let column_names: Vec<String> = stmt
+            .column_names()
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+
+        let mut rows = stmt.raw_query();
+        while let Some(row) = rows.next()? {
+            if let Some(limit) = mapping.max_rows
+                && delivered == limit.max_rows
+            {
+                limit.exceeded(&prepared.executed.sql)?;
+                truncated = true;
+                if writes {
+                    continue;
+                }
+                break;
+            }
+            on_row(row_to_json_object(
+                row,
+                &column_names,
+                mapping.returns_fields,
+                mapping.blob_encoding,
+            ))?;
+            delivered += 1;
+        }
+        statements.push(prepared.executed);
+        if truncated && !writes {
+            break;
         }
     }
+    Ok((statements, truncated))
 }
 
 /// Execute query with both read and mutation operations within a unified transaction
@@ -177,68 +305,149 @@ pub fn execute_query_unified(
     request_params_obj: &serde_json::Map<String, serde_json::Value>,
     tx: &rusqlite::Transaction,
 ) -> anyhow::Result<QueryResult> {
+    execute_query_with_cache(query, request_params_obj, tx, None)
+}
+
+/// Execute a query like `execute_query_unified`, counting statement lookups in `cache`
+/// Paginated queries run their page query, then their count query if any
+fn execute_query_with_cache(
+    query: &crate::query::QueryDef,
+    request_params_obj: &serde_json::Map<String, serde_json::Value>,
+    tx: &rusqlite::Transaction,
+    cache: Option<&SqliteStatementCache>,
+) -> anyhow::Result<QueryResult> {
+    let Some(pagination) = &query.paginate else {
+        return execute_unpaginated_query(query, request_params_obj, tx, cache);
+    };
+    let page = PageQuery::resolve(query, pagination, request_params_obj)?;
+    let page_result = execute_unpaginated_query(&page.query, &page.params, tx, cache)?;
+    let count_result = page
+        .count_query
+        .as_ref()
+        .map(|count_query| execute_unpaginated_query(count_query, request_params_obj, tx, cache))
+        .transpose()?;
+    Ok(page.finish(page_result, count_result)?)
+}
+
+/// Execute a query without applying its pagination
+fn execute_unpaginated_query(
+    query: &crate::query::QueryDef,
+    request_params_obj: &serde_json::Map<String, serde_json::Value>,
+    tx: &rusqlite::Transaction,
+    cache: Option<&SqliteStatementCache>,
+) -> anyhow::Result<QueryResult> {
+    if let crate::query::ReturnsSpec::PerStatement(statement_returns) = &query.returns {
+        return execute_per_statement_query(
+            query,
+            statement_returns,
+            request_params_obj,
+            tx,
+            cache,
+        );
+    }
+
     // Resolve returns specification to actual field names
     let returns_fields = resolve_returns(&query.returns, request_params_obj)?;
 
     if !returns_fields.is_empty() {
         // Query with returns specified - return structured data
-        let prepared =
-            prepare_single_statement_sqlite(&query.sql, &query.parameters, request_params_obj)?;
-        let mut stmt = tx.prepare(&prepared.sql)?;
-        let named_params = prepared.as_named_params();
-
-        // Get column names from the prepared statement
-        let column_names: Vec<String> = stmt
-            .column_names()
-            .iter()
-            .map(|name| name.to_string())
-            .collect();
-
-        let rows = stmt.query_map(&named_params[..], |row| {
-            let mut obj = serde_json::Map::new();
-
-            for field_name in &returns_fields {
-                // Find the column index by matching the column name
-                let column_idx = column_names.iter().position(|name| name == field_name);
-
-                let value: serde_json::Value = match column_idx {
-                    Some(idx) => match row.get_ref(idx).expect("column index should be valid") {
-                        rusqlite::types::ValueRef::Integer(i) => {
-                            serde_json::Value::Number(i.into())
-                        }
-                        rusqlite::types::ValueRef::Real(r) => serde_json::Value::from(r),
-                        rusqlite::types::ValueRef::Text(s) => {
-                            serde_json::Value::String(String::from_utf8_lossy(s).to_string())
-                        }
-                        rusqlite::types::ValueRef::Blob(b) => serde_json::Value::Array(
-                            b.iter()
-                                .map(|&byte| serde_json::Value::Number(byte.into()))
-                                .collect(),
-                        ),
-                        rusqlite::types::ValueRef::Null => serde_json::Value::Null,
-                    },
-                    None => serde_json::Value::Null,
-                };
-
-                obj.insert(field_name.clone(), value);
-            }
-            Ok(serde_json::Value::Object(obj))
-        })?;
-        let result = rows.collect::<rusqlite::Result<Vec<_>>>()?;
-        Ok(QueryResult {
-            sql_statements: vec![prepared.sql],
-            data: result,
-        })
+        let mut data = Vec::new();
+        let (executed, truncated) = execute_read_query(
+            &query.sql,
+            query,
+            request_params_obj,
+            RowMapping {
+                returns_fields: &returns_fields,
+                blob_encoding: query.blob_encoding,
+                max_rows: query.max_rows,
+            },
+            tx,
+            cache,
+            &mut |row| {
+                data.push(row);
+                Ok(())
+            },
+        )?;
+        let mut query_result = QueryResult::from_statements(executed, data, vec![]);
+        query_result.truncated = truncated;
+        Ok(query_result)
     } else {
         // Mutation query (INSERT/UPDATE/DELETE/etc.) - split and execute within transaction
-        let sql_statements = execute_mutation_query(query, request_params_obj, tx)?;
-        Ok(QueryResult {
-            sql_statements,
-            data: vec![],
-        })
+        let statements = execute_mutation_query(query, request_params_obj, tx, cache)?;
+        Ok(QueryResult::from_statements(statements, vec![], vec![]))
     }
 }
 
+/// Execute each statement of a multi-statement query with its own returns specification
+/// Statements with returns produce a result set, the others are executed as mutations
+fn execute_per_statement_query(
+    query: &crate::query::QueryDef,
+    statement_returns: &[crate::query::ReturnsSpec],
+    request_params_obj: &serde_json::Map<String, serde_json::Value>,
+    tx: &rusqlite::Transaction,
+    cache: Option<&SqliteStatementCache>,
+) -> anyhow::Result<QueryResult> {
+    let mut statements = Vec::new();
+    let mut result_sets = Vec::new();
+    let mut truncated = false;
+
+    let statement_sqls = split_sql_statements(&query.sql);
+    for (statement_sql, returns_spec) in statement_sqls.iter().zip(statement_returns) {
+        let returns_fields = resolve_returns(returns_spec, request_params_obj)?;
+        let mut rows = Vec::new();
+        let executed = if returns_fields.is_empty() {
+            execute_single_statement(tx, statement_sql, query, request_params_obj, cache)?
+        } else {
+            // Each result set is limited on its own
+            let (executed, statement_truncated) = execute_read_query(
+                statement_sql,
+                query,
+                request_params_obj,
+                RowMapping {
+                    returns_fields: &returns_fields,
+                    blob_encoding: query.blob_encoding,
+                    max_rows: query.max_rows,
+                },
+                tx,
+                cache,
+                &mut |row| {
+                    rows.push(row);
+                    Ok(())
+                },
+            )?;
+            truncated |= statement_truncated;
+            executed
+        };
+        statements.extend(executed);
+        result_sets.push(rows);
+    }
+
+    let mut query_result = QueryResult::from_statements(statements, vec![], result_sets);
+    query_result.truncated = truncated;
+    Ok(query_result)
+}
+
+/// Look up a query definition and validate that the request parameters are an object
+fn find_query<'a>(
+    queries: &'a QueryDefinitions,
+    query_name: &str,
+    request_params: &'a serde_json::Value,
+) -> anyhow::Result<(
+    &'a crate::query::QueryDef,
+    &'a serde_json::Map<String, serde_json::Value>,
+)> {
+    let query = queries
+        .definitions
+        .get(query_name)
+        .ok_or_else(|| JankenError::new_query_not_found(query_name.to_string()))?;
+
+    let request_params_obj = request_params
+        .as_object()
+        .ok_or_else(|| JankenError::new_parameter_type_mismatch("object", "not object"))?;
+
+    Ok((query, request_params_obj))
+}
+
 /// Execute a query within a user-provided SQLite transaction.
 /// This allows the caller to manage the transaction lifecycle (begin/commit/rollback),
 /// enabling multiple `query_run` calls within the same transaction.
@@ -248,35 +457,493 @@ pub fn query_run_sqlite_with_transaction(
     query_name: &str,
     request_params: &serde_json::Value,
 ) -> anyhow::Result<QueryResult> {
-    let query = queries
+    let (query, request_params_obj) = find_query(queries, query_name, request_params)?;
+    execute_query_unified(query, request_params_obj, tx)
+}
+
+/// Execute a query inside a savepoint of a user-provided SQLite transaction.
+/// On success the savepoint is released and the changes become part of the caller's transaction;
+/// on failure only this query's changes are rolled back and the error is returned, leaving the
+/// transaction usable for further steps (e.g. optional "insert unless duplicate" steps).
+/// If the rollback itself fails, the query error is still returned, with the rollback failure
+/// attached as context.
+pub fn query_run_sqlite_with_savepoint(
+    tx: &rusqlite::Transaction,
+    queries: &QueryDefinitions,
+    query_name: &str,
+    request_params: &serde_json::Value,
+) -> anyhow::Result<QueryResult> {
+    tx.execute_batch("SAVEPOINT janken_step")?;
+    match query_run_sqlite_with_transaction(tx, queries, query_name, request_params) {
+        Ok(query_result) => {
+            tx.execute_batch("RELEASE janken_step")?;
+            Ok(query_result)
+        }
+        // The query error is what the caller needs; a failed rollback is attached to it as context
+        Err(err) => match tx.execute_batch("ROLLBACK TO janken_step; RELEASE janken_step") {
+            Ok(()) => Err(err),
+            Err(rollback_err) => Err(err.context(format!(
+                "rolling back savepoint janken_step also failed: {rollback_err}"
+            ))),
+        },
+    }
+}
+
+/// Execute queries with SQLite backend.
+/// This is the main entry point for SQLite operations.
+/// It creates a transaction internally with the query's transaction options,
+/// executes the query, and commits.
+/// Queries declared `readonly` additionally run with `PRAGMA query_only` switched on.
+/// With a retry policy, the whole transaction is re-run after `SQLITE_BUSY`/`SQLITE_LOCKED`.
+pub fn query_run_sqlite(
+    conn: &mut Connection,
+    queries: &QueryDefinitions,
+    query_name: &str,
+    request_params: &serde_json::Value,
+) -> anyhow::Result<QueryResult> {
+    run_query_sqlite(conn, queries, query_name, request_params, None)
+}
+
+/// Execute a query like `query_run_sqlite`, with the connection's prepared statement cache sized
+/// to `cache.capacity()` and every statement lookup counted in `cache`.
+/// Use one cache per connection. The counters track rusqlite's cache from the outside, so they are
+/// approximate: statements prepared on the connection through other entry points are not counted
+/// and may evict statements the counters still consider cached.
+pub fn query_run_sqlite_cached(
+    conn: &mut Connection,
+    cache: &SqliteStatementCache,
+    queries: &QueryDefinitions,
+    query_name: &str,
+    request_params: &serde_json::Value,
+) -> anyhow::Result<QueryResult> {
+    conn.set_prepared_statement_cache_capacity(cache.capacity());
+    run_query_sqlite(conn, queries, query_name, request_params, Some(cache))
+}
+
+/// Run a query in its own transaction, re-running it according to its retry policy
+fn run_query_sqlite(
+    conn: &mut Connection,
+    queries: &QueryDefinitions,
+    query_name: &str,
+    request_params: &serde_json::Value,
+    cache: Option<&SqliteStatementCache>,
+) -> anyhow::Result<QueryResult> {
+    let query = queries.definitions.get(query_name);
+    let readonly = query.is_some_and(|query| query.readonly);
+    let retry = query.and_then(|query| query.transaction.retry);
+
+    let mut attempt = 1;
+    loop {
+        let outcome = with_query_only(conn, readonly, |conn| {
+            let tx = begin_transaction(conn, queries, query_name)?;
+
+            let (query, request_params_obj) = find_query(queries, query_name, request_params)?;
+            let query_result = execute_query_with_cache(query, request_params_obj, &tx, cache)?;
+
+            tx.commit()?;
+            Ok(query_result)
+        });
+
+        match outcome {
+            Ok(mut query_result) => {
+                query_result.attempts = attempt;
+                return Ok(query_result);
+            }
+            Err(err) => match retry {
+                Some(policy) if attempt < policy.max_attempts && is_retryable_error(&err) => {
+                    std::thread::sleep(policy.delay_after(attempt));
+                    attempt += 1;
+                }
+                _ => return Err(err),
+            },
+        }
+    }
+}
+
+/// Whether an error is a transient lock conflict that is worth re-running the transaction for
+fn is_retryable_error(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<rusqlite::Error>()
+            .and_then(|sqlite_err| sqlite_err.sqlite_error_code()),
+        Some(rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked)
+    )
+}
+
+/// Open a transaction with the locking behavior declared in the query's transaction options.
+/// Unknown queries get a default transaction; the lookup error is reported by the caller.
+fn begin_transaction<'c>(
+    conn: &'c mut Connection,
+    queries: &QueryDefinitions,
+    query_name: &str,
+) -> anyhow::Result<rusqlite::Transaction<'c>> {
+    let behavior = queries
         .definitions
         .get(query_name)
-        .ok_or_else(|| JankenError::new_query_not_found(query_name.to_string()))?;
+        .map(|query| query.transaction.behavior)
+        .unwrap_or_default();
+    Ok(conn.transaction_with_behavior(behavior.into())?)
+}
+
+/// Run `f` with `PRAGMA query_only` switched on when `enabled`, restoring the previous
+/// setting afterwards whether or not `f` succeeds
+fn with_query_only<T>(
+    conn: &mut Connection,
+    enabled: bool,
+    f: impl FnOnce(&mut Connection) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    if !enabled {
+        return f(conn);
+    }
+
+    let previous: bool = conn.pragma_query_value(None, "query_only", |row| row.get(0))?;
+    conn.pragma_update(None, "query_only", true)?;
+    let result = f(conn);
+    conn.pragma_update(None, "query_only", previous)?;
+    result
+}
+
+/// Execute a workflow within a user-provided SQLite transaction.
+/// Steps run in order as `query_run_sqlite_with_transaction` calls, each with parameters resolved
+/// from the request and the results of the earlier steps.
+pub fn workflow_run_sqlite_with_transaction(
+    tx: &rusqlite::Transaction,
+    queries: &QueryDefinitions,
+    workflow_name: &str,
+    request_params: &serde_json::Value,
+) -> anyhow::Result<WorkflowResult> {
+    let workflow = queries
+        .workflows
+        .get(workflow_name)
+        .ok_or_else(|| JankenError::new_query_not_found(workflow_name))?;
+
+    let mut steps = Vec::new();
+    for step in &workflow.steps {
+        let step_params = step.resolve_params(request_params, &steps)?;
+        let result = query_run_sqlite_with_transaction(tx, queries, &step.query, &step_params)?;
+        steps.push(WorkflowStepResult {
+            name: step.name.clone(),
+            result,
+        });
+    }
+    Ok(WorkflowResult { steps, attempts: 1 })
+}
 
+/// Execute a workflow with SQLite backend.
+/// It creates a transaction internally with the workflow's transaction options, runs every
+/// step, and commits; a failing step rolls back the whole workflow.
+/// With a retry policy, the whole workflow is re-run after `SQLITE_BUSY`/`SQLITE_LOCKED`.
+pub fn workflow_run_sqlite(
+    conn: &mut Connection,
+    queries: &QueryDefinitions,
+    workflow_name: &str,
+    request_params: &serde_json::Value,
+) -> anyhow::Result<WorkflowResult> {
+    let workflow = queries
+        .workflows
+        .get(workflow_name)
+        .ok_or_else(|| JankenError::new_query_not_found(workflow_name))?;
+    let options = &workflow.transaction;
+
+    let mut attempt = 1;
+    loop {
+        let outcome = (|| {
+            let tx = conn.transaction_with_behavior(options.behavior.into())?;
+            let workflow_result =
+                workflow_run_sqlite_with_transaction(&tx, queries, workflow_name, request_params)?;
+            tx.commit()?;
+            Ok(workflow_result)
+        })();
+
+        match outcome {
+            Ok(mut workflow_result) => {
+                workflow_result.attempts = attempt;
+                return Ok(workflow_result);
+            }
+            Err(err) => match options.retry {
+                Some(policy) if attempt < policy.max_attempts && is_retryable_error(&err) => {
+                    std::thread::sleep(policy.delay_after(attempt));
+                    attempt += 1;
+                }
+                _ => return Err(err),
+            },
+        }
+    }
+}
+
+/// Validate one batch item and prepare its statements
+fn prepare_batch_item(
+    query: &crate::query::QueryDef,
+    request_params: &serde_json::Value,
+) -> anyhow::Result<Vec<PreparedStatement>> {
     let request_params_obj = request_params
         .as_object()
         .ok_or_else(|| JankenError::new_parameter_type_mismatch("object", "not object"))?;
 
-    execute_query_unified(query, request_params_obj, tx)
+    let mut prepared = Vec::new();
+    for statement_sql in query.statement_sqls(request_params_obj)? {
+        prepared.extend(prepare_single_statement_sqlite(
+            &statement_sql,
+            &query.parameters,
+            request_params_obj,
+        )?);
+    }
+    Ok(prepared)
 }
 
-/// Execute queries with SQLite backend.
-/// This is the main entry point for SQLite operations.
-/// It creates a transaction internally, executes the query, and commits.
-pub fn query_run_sqlite(
+/// Execute one query with many parameter sets within a user-provided SQLite transaction.
+/// Every parameter set is validated before anything runs, each distinct expanded SQL (list
+/// parameters change the shape) is prepared once, and affected row counts are reported per item.
+/// A failing item is reported as `JankenError::BatchItemFailed` carrying its index; the
+/// underlying error remains available through `downcast`.
+pub fn query_batch_sqlite_with_transaction(
+    tx: &rusqlite::Transaction,
+    queries: &QueryDefinitions,
+    query_name: &str,
+    params_list: &[serde_json::Value],
+) -> anyhow::Result<BatchResult> {
+    let query = queries
+        .definitions
+        .get(query_name)
+        .ok_or_else(|| JankenError::new_query_not_found(query_name))?;
+    if !matches!(&query.returns, crate::query::ReturnsSpec::Static(fields) if fields.is_empty()) {
+        return Err(JankenError::new_parameter_type_mismatch(
+            "query without returns for batch",
+            format!("{query_name} has returns"),
+        )
+        .into());
+    }
+
+    let items = params_list
+        .iter()
+        .enumerate()
+        .map(|(index, request_params)| {
+            prepare_batch_item(query, request_params).map_err(|err| batch_item_error(index, err))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let mut statements: HashMap<String, rusqlite::CachedStatement> = HashMap::new();
+    let mut row_counts = Vec::with_capacity(items.len());
+    for (index, item) in items.iter().enumerate() {
+        let mut item_rows = 0;
+        for prepared in item {
+            let sql = &prepared.executed.sql;
+            let stmt = match statements.entry(sql.clone()) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(
+                    prepare_for_query(tx, query, sql, None)
+                        .map_err(|err| batch_item_error(index, err))?,
+                ),
+            };
+            let rows = prepared
+                .bind(stmt)
+                .and_then(|()| stmt.raw_execute())
+                .map_err(|err| batch_item_error(index, err.into()))?;
+            item_rows += rows as u64;
+        }
+        row_counts.push(item_rows);
+    }
+
+    Ok(BatchResult {
+        row_counts,
+        prepared_statements: statements.len(),
+    })
+}
+
+/// Execute one query with many parameter sets with SQLite backend.
+/// It creates a transaction internally with the query's transaction options, executes every
+/// item, and commits; a failing item rolls back the whole batch.
+pub fn query_batch_sqlite(
     conn: &mut Connection,
     queries: &QueryDefinitions,
     query_name: &str,
+    params_list: &[serde_json::Value],
+) -> anyhow::Result<BatchResult> {
+    let tx = begin_transaction(conn, queries, query_name)?;
+
+    let batch_result = query_batch_sqlite_with_transaction(&tx, queries, query_name, params_list)?;
+
+    tx.commit()?;
+    Ok(batch_result)
+}
+
+/// Resolve the returns of a query whose rows are delivered incrementally (streaming or export)
+fn resolve_streaming_returns(
+    query: &crate::query::QueryDef,
+    query_name: &str,
+    request_params_obj: &serde_json::Map<String, serde_json::Value>,
+) -> anyhow::Result<Vec<String>> {
+    let returns_fields = resolve_returns(&query.returns, request_params_obj)?;
+    if returns_fields.is_empty() {
+        return Err(JankenError::new_parameter_type_mismatch(
+            "query with returns for streaming",
+            format!("{query_name} has no returns"),
+        )
+        .into());
+    }
+    Ok(returns_fields)
+}
+
+/// Stream the rows of a read query within a user-provided SQLite transaction.
+/// Each row is mapped with the query's `returns` fields and passed to `on_row` as soon as
+/// it is fetched, so large result sets never have to be held in memory.
+/// The returned `QueryResult` carries the executed SQL while `data` stays empty.
+/// Returning an error from `on_row` stops the iteration and is propagated to the caller.
+pub fn query_stream_sqlite_with_transaction(
+    tx: &rusqlite::Transaction,
+    queries: &QueryDefinitions,
+    query_name: &str,
     request_params: &serde_json::Value,
+    mut on_row: impl FnMut(serde_json::Value) -> anyhow::Result<()>,
 ) -> anyhow::Result<QueryResult> {
-    let tx = conn.transaction()?;
+    let (query, request_params_obj) = find_query(queries, query_name, request_params)?;
+    let returns_fields = resolve_streaming_returns(query, query_name, request_params_obj)?;
 
-    let query_result = query_run_sqlite_with_transaction(&tx, queries, query_name, request_params)?;
+    let (executed, _) = execute_read_query(
+        &query.sql,
+        query,
+        request_params_obj,
+        RowMapping {
+            returns_fields: &returns_fields,
+            blob_encoding: query.blob_encoding,
+            max_rows: None,
+        },
+        tx,
+        None,
+        &mut on_row,
+    )?;
+    Ok(QueryResult::from_statements(executed, vec![], vec![]))
+}
+
+/// Stream the rows of a read query with SQLite backend.
+/// It creates a transaction internally with the query's transaction options,
+/// streams every row to `on_row`, and commits.
+pub fn query_stream_sqlite(
+    conn: &mut Connection,
+    queries: &QueryDefinitions,
+    query_name: &str,
+    request_params: &serde_json::Value,
+    on_row: impl FnMut(serde_json::Value) -> anyhow::Result<()>,
+) -> anyhow::Result<QueryResult> {
+    let tx = begin_transaction(conn, queries, query_name)?;
+
+    let query_result =
+        query_stream_sqlite_with_transaction(&tx, queries, query_name, request_params, on_row)?;
 
     tx.commit()?;
     Ok(query_result)
 }
 
+/// Export the rows of a read query as CSV or NDJSON within a user-provided SQLite transaction.
+/// Rows are streamed straight into `writer`, so the full result set is never held in memory.
+pub fn query_export_sqlite_with_transaction(
+    tx: &rusqlite::Transaction,
+    queries: &QueryDefinitions,
+    query_name: &str,
+    request_params: &serde_json::Value,
+    writer: impl std::io::Write,
+    options: &ExportOptions,
+) -> anyhow::Result<QueryResult> {
+    let (query, request_params_obj) = find_query(queries, query_name, request_params)?;
+    let returns_fields = resolve_streaming_returns(query, query_name, request_params_obj)?;
+
+    let mut result_writer = ResultWriter::new(writer, returns_fields.clone(), options.clone())?;
+    let (executed, _) = execute_read_query(
+        &query.sql,
+        query,
+        request_params_obj,
+        RowMapping {
+            returns_fields: &returns_fields,
+            blob_encoding: options.blob_encoding,
+            max_rows: None,
+        },
+        tx,
+        None,
+        &mut |row| result_writer.write_row(&row),
+    )?;
+    result_writer.finish()?;
+
+    Ok(QueryResult::from_statements(executed, vec![], vec![]))
+}
+
+/// Export the rows of a read query as CSV or NDJSON with SQLite backend.
+/// It creates a transaction internally with the query's transaction options,
+/// writes every row to `writer`, and commits.
+pub fn query_export_sqlite(
+    conn: &mut Connection,
+    queries: &QueryDefinitions,
+    query_name: &str,
+    request_params: &serde_json::Value,
+    writer: impl std::io::Write,
+    options: &ExportOptions,
+) -> anyhow::Result<QueryResult> {
+    let tx = begin_transaction(conn, queries, query_name)?;
+
+    let query_result = query_export_sqlite_with_transaction(
+        &tx,
+        queries,
+        query_name,
+        request_params,
+        writer,
+        options,
+    )?;
+
+    tx.commit()?;
+    Ok(query_result)
+}
+
+/// Explain the query plan of a named query with SQLite's `EXPLAIN QUERY PLAN`.
+/// Parameters are validated and bound exactly as in `query_run_sqlite`, but nothing is executed;
+/// the transaction used for preparing the statements is rolled back.
+/// Returns a JSON array with one `{"sql", "plan"}` object per statement, where `plan` is the
+/// tree of plan steps: `[{"id", "detail", "children": [...]}]`.
+pub fn query_explain_sqlite(
+    conn: &mut Connection,
+    queries: &QueryDefinitions,
+    query_name: &str,
+    request_params: &serde_json::Value,
+) -> anyhow::Result<serde_json::Value> {
+    let (query, request_params_obj) = find_query(queries, query_name, request_params)?;
+    let tx = conn.transaction()?;
+
+    let mut explained = Vec::new();
+    let statement_sqls = query.statement_sqls(request_params_obj)?;
+    for statement_sql in &statement_sqls {
+        for prepared in
+            prepare_single_statement_sqlite(statement_sql, &query.parameters, request_params_obj)?
+        {
+            let mut stmt = tx.prepare(&format!("EXPLAIN QUERY PLAN {}", prepared.executed.sql))?;
+            prepared.bind(&mut stmt)?;
+            let plan_rows = stmt
+                .raw_query()
+                .mapped(|row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get(3)?)))
+                .collect::<rusqlite::Result<Vec<(i64, i64, String)>>>()?;
+
+            explained.push(serde_json::json!({
+                "sql": prepared.executed.sql,
+                "plan": build_query_plan_tree(&plan_rows, 0),
+            }));
+        }
+    }
+
+    // Dropping the transaction rolls it back
+    Ok(serde_json::Value::Array(explained))
+}
+
+/// Nest `EXPLAIN QUERY PLAN` rows (id, parent, detail) under their parent steps
+fn build_query_plan_tree(plan_rows: &[(i64, i64, String)], parent: i64) -> Vec<serde_json::Value> {
+    plan_rows
+        .iter()
+        .filter(|(_, row_parent, _)| *row_parent == parent)
+        .map(|(id, _, detail)| {
+            serde_json::json!({
+                "id": id,
+                "detail": detail,
+                "children": build_query_plan_tree(plan_rows, *id),
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Hit/miss counters and occupancy of a `StatementCache`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StatementCacheStats {
+    /// Executions that reused a statement prepared earlier
+    pub hits: u64,
+    /// Executions that had to prepare their statement
+    pub misses: u64,
+    /// Statements currently cached
+    pub len: usize,
+    /// Most statements kept before the least recently used one is evicted
+    pub capacity: usize,
+}
+
+/// Per-connection cache of prepared statements keyed by the rendered SQL, evicting the least
+/// recently used statement once `capacity` is reached.
+/// Keep one cache next to each connection and pass it to the `*_cached` entry points:
+/// - `SqliteStatementCache` sizes rusqlite's own per-connection cache (`prepare_cached`) and
+///   keeps a shadow LRU of its SQL keys to count hits and misses. rusqlite does not expose its
+///   cache, so these counters are approximate: statements prepared on the same connection outside
+///   the `*_cached` entry points are not seen and can evict entries the shadow LRU still counts
+/// - `PostgresStatementCache` holds the server-side prepared `tokio_postgres::Statement`s
+pub struct StatementCache<S> {
+    capacity: usize,
+    state: Mutex<CacheState<S>>,
+}
+
+struct CacheState<S> {
+    // SQL -> (statement, last use tick)
+    entries: HashMap<String, (S, u64)>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+/// Statement cache for the SQLite runner; the statements themselves live in rusqlite's cache,
+/// so its stats are an approximation of that cache (see `StatementCache`)
+#[cfg(feature = "sqlite")]
+pub type SqliteStatementCache = StatementCache<()>;
+
+/// Statement cache for the PostgreSQL runner
+#[cfg(feature = "postgresql")]
+pub type PostgresStatementCache = StatementCache<tokio_postgres::Statement>;
+
+impl<S: Clone> StatementCache<S> {
+    /// Create an empty cache keeping at most `capacity` statements
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(CacheState {
+                entries: HashMap::new(),
+                tick: 0,
+                hits: 0,
+                misses: 0,
+            }),
+        }
+    }
+
+    /// Most statements kept before the least recently used one is evicted
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Current hit/miss counters and occupancy
+    pub fn stats(&self) -> StatementCacheStats {
+        let state = self.lock();
+        StatementCacheStats {
+            hits: state.hits,
+            misses: state.misses,
+            len: state.entries.len(),
+            capacity: self.capacity,
+        }
+    }
+
+    /// Drop every cached statement (e.g. after a schema change), keeping the counters
+    pub fn clear(&self) {
+        self.lock().entries.clear();
+    }
+
+    /// Look up the statement for `sql`, counting a hit or a miss
+    pub(crate) fn get(&self, sql: &str) -> Option<S> {
+        let mut state = self.lock();
+        state.tick += 1;
+        let tick = state.tick;
+        match state.entries.get_mut(sql) {
+            Some((statement, last_used)) => {
+                *last_used = tick;
+                let statement = statement.clone();
+                state.hits += 1;
+                Some(statement)
+            }
+            None => {
+                state.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Cache the statement prepared for `sql` after a miss, evicting the least recently used one
+    pub(crate) fn insert(&self, sql: &str, statement: S) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut state = self.lock();
+        if state.entries.len() >= self.capacity
+            && !state.entries.contains_key(sql)
+            && let Some(oldest) = state
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone())
+        {
+            state.entries.remove(&oldest);
+        }
+        let tick = state.tick;
+        state.entries.insert(sql.to_string(), (statement, tick));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState<S>> {
+        // The state stays consistent even if a holder panicked, so a poisoned lock is still usable
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statement_cache_evicts_least_recently_used() {
+        let cache = StatementCache::new(2);
+        assert_eq!(cache.get("a"), None);
+        cache.insert("a", 1);
+        assert_eq!(cache.get("b"), None);
+        cache.insert("b", 2);
+        assert_eq!(cache.get("a"), Some(1));
+
+        // "b" is the least recently used statement
+        assert_eq!(cache.get("c"), None);
+        cache.insert("c", 3);
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("a"), Some(1));
+
+        assert_eq!(
+            cache.stats(),
+            StatementCacheStats {
+                hits: 2,
+                misses: 4,
+                len: 2,
+                capacity: 2
+            }
+        );
+
+        cache.clear();
+        assert_eq!(cache.stats().len, 0);
+        assert_eq!(cache.get("a"), None);
+    }
+}
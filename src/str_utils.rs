@@ -14,16 +14,8 @@ pub fn is_in_quotes(sql: &str, pos: usize) -> bool {
 
         match ch {
             '\\' => escaped = true,
-            '\'' => {
-                if !in_double_quote {
-                    in_single_quote = !in_single_quote;
-                }
-            }
-            '"' => {
-                if !in_single_quote {
-                    in_double_quote = !in_double_quote;
-                }
-            }
+            '\'' if !in_double_quote => in_single_quote = !in_single_quote,
+            '"' if !in_single_quote => in_double_quote = !in_double_quote,
             _ => {}
         }
     }
@@ -31,6 +23,34 @@ pub fn is_in_quotes(sql: &str, pos: usize) -> bool {
     in_single_quote || in_double_quote
 }
 
+/// Whether each byte offset of the SQL is inside quotes, following the rules of `is_in_quotes`
+/// Computed in a single pass for callers that check many positions of the same (large) SQL
+pub fn quote_mask(sql: &str) -> Vec<bool> {
+    let mut mask = Vec::with_capacity(sql.len());
+    let mut in_single_quote = false;
+    let mut in_double_quote = false;
+    let mut escaped = false;
+
+    for ch in sql.chars() {
+        if escaped {
+            escaped = false;
+        } else {
+            match ch {
+                '\\' => escaped = true,
+                '\'' if !in_double_quote => in_single_quote = !in_single_quote,
+                '"' if !in_single_quote => in_double_quote = !in_double_quote,
+                _ => {}
+            }
+        }
+        mask.extend(std::iter::repeat_n(
+            in_single_quote || in_double_quote,
+            ch.len_utf8(),
+        ));
+    }
+
+    mask
+}
+
 /// Split multi-statement SQL into individual statements (respects quote boundaries)
 pub fn split_sql_statements(sql: &str) -> Vec<String> {
     let mut statements = Vec::new();
@@ -75,3 +95,41 @@ pub fn split_sql_statements(sql: &str) -> Vec<String> {
 
     statements
 }
+
+/// Leading keywords of statements that modify data or schema
+const WRITE_KEYWORDS: &[&str] = &[
+    "INSERT", "UPDATE", "DELETE", "REPLACE", "MERGE", "UPSERT", "CREATE", "DROP", "ALTER",
+    "TRUNCATE", "GRANT", "REVOKE", "VACUUM", "REINDEX", "ATTACH", "DETACH", "COPY",
+];
+
+/// Data-modifying keywords that can appear inside a WITH (common table expression) statement
+static CTE_WRITE_REGEX: once_cell::sync::Lazy<regex::Regex> = once_cell::sync::Lazy::new(|| {
+    regex::Regex::new(r"(?i)(\bFOR\s+)?\b(INSERT|UPDATE|DELETE|MERGE)\b")
+        .expect("CTE_WRITE_REGEX is a valid regex")
+});
+
+/// Heuristically check whether a single SQL statement writes data or schema.
+/// Looks at the leading keyword, and for WITH statements at data-modifying keywords outside quotes
+/// (ignoring row-locking `FOR UPDATE` clauses).
+pub fn looks_like_write_statement(statement: &str) -> bool {
+    let first_keyword = statement
+        .trim_start()
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase();
+
+    if WRITE_KEYWORDS.contains(&first_keyword.as_str()) {
+        return true;
+    }
+    if first_keyword != "WITH" {
+        return false;
+    }
+
+    CTE_WRITE_REGEX.captures_iter(statement).any(|cap| {
+        let keyword = cap
+            .get(2)
+            .expect("CTE_WRITE_REGEX always has capture group 2");
+        cap.get(1).is_none() && !is_in_quotes(statement, keyword.start())
+    })
+}
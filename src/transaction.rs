@@ -0,0 +1,219 @@
+use crate::result::{JankenError, Result};
+use std::time::Duration;
+
+/// SQLite transaction locking behavior, as in `BEGIN DEFERRED|IMMEDIATE|EXCLUSIVE`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransactionBehavior {
+    /// Locks are acquired when the database is first read or written (SQLite's default)
+    #[default]
+    Deferred,
+    /// A write lock is acquired immediately, avoiding SQLITE_BUSY on lock upgrades
+    Immediate,
+    /// An exclusive lock is acquired immediately
+    Exclusive,
+}
+
+impl std::str::FromStr for TransactionBehavior {
+    type Err = JankenError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "deferred" => Ok(TransactionBehavior::Deferred),
+            "immediate" => Ok(TransactionBehavior::Immediate),
+            "exclusive" => Ok(TransactionBehavior::Exclusive),
+            _ => Err(JankenError::new_parameter_type_mismatch(
+                "transaction behavior deferred, immediate or exclusive",
+                s,
+            )),
+        }
+    }
+}
+
+/// PostgreSQL transaction isolation level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IsolationLevel {
+    ReadUncommitted,
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+impl std::str::FromStr for IsolationLevel {
+    type Err = JankenError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().replace('_', " ").as_str() {
+            "read uncommitted" => Ok(IsolationLevel::ReadUncommitted),
+            "read committed" => Ok(IsolationLevel::ReadCommitted),
+            "repeatable read" => Ok(IsolationLevel::RepeatableRead),
+            "serializable" => Ok(IsolationLevel::Serializable),
+            _ => Err(JankenError::new_parameter_type_mismatch(
+                "isolation level read_uncommitted, read_committed, repeatable_read or serializable",
+                s,
+            )),
+        }
+    }
+}
+
+/// Transaction options declared per query definition and applied by the convenience entry
+/// points (`query_run_sqlite`, `query_run_postgresql`, ...) when they open the transaction.
+/// Runners ignore the options that do not apply to their database.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionOptions {
+    /// SQLite locking behavior
+    pub behavior: TransactionBehavior,
+    /// PostgreSQL isolation level; `None` keeps the server default
+    pub isolation: Option<IsolationLevel>,
+    /// PostgreSQL `READ ONLY` transaction
+    pub read_only: bool,
+    /// PostgreSQL `DEFERRABLE` transaction (only effective with serializable read-only transactions)
+    pub deferrable: bool,
+    /// Opt-in policy for re-running the transaction after transient failures
+    pub retry: Option<RetryPolicy>,
+}
+
+/// Policy for re-running a whole transaction after a transient failure:
+/// `SQLITE_BUSY`/`SQLITE_LOCKED` in SQLite, serialization failures and deadlocks
+/// (SQLSTATE 40001/40P01) in PostgreSQL. Other errors are never retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled for every further retry
+    pub backoff: Duration,
+    /// Upper bound for the delay between attempts
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Parse the `retry` object of the transaction options:
+    /// `{"max_attempts": 5, "backoff_ms": 20, "max_backoff_ms": 500}`, each key optional
+    pub fn from_json(query_name: &str, value: &serde_json::Value) -> Result<Self> {
+        let map = value.as_object().ok_or_else(|| {
+            JankenError::new_parameter_type_mismatch(
+                "object for transaction retry",
+                format!("{query_name}: {value}"),
+            )
+        })?;
+
+        let integer_option = |key: &str| -> Result<Option<u64>> {
+            map.get(key)
+                .map(|val| {
+                    val.as_u64().ok_or_else(|| {
+                        JankenError::new_parameter_type_mismatch(
+                            format!("non-negative integer for retry {key}"),
+                            format!("{query_name}: {val}"),
+                        )
+                    })
+                })
+                .transpose()
+        };
+
+        let defaults = RetryPolicy::default();
+        let max_attempts = match integer_option("max_attempts")? {
+            Some(attempts) => u32::try_from(attempts)
+                .ok()
+                .filter(|&attempts| attempts >= 1)
+                .ok_or_else(|| {
+                    JankenError::new_parameter_type_mismatch(
+                        "at least 1 for retry max_attempts",
+                        format!("{query_name}: {attempts}"),
+                    )
+                })?,
+            None => defaults.max_attempts,
+        };
+
+        Ok(RetryPolicy {
+            max_attempts,
+            backoff: integer_option("backoff_ms")?.map_or(defaults.backoff, Duration::from_millis),
+            max_backoff: integer_option("max_backoff_ms")?
+                .map_or(defaults.max_backoff, Duration::from_millis),
+        })
+    }
+
+    /// Delay to wait after the given failed attempt (1-based) before running the next one
+    pub fn delay_after(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+impl TransactionOptions {
+    /// Parse the `transaction` object of a query definition:
+    /// `{"behavior": "immediate", "isolation": "serializable", "read_only": true, "deferrable": true,
+    /// "retry": {...}}`
+    pub fn from_json(query_name: &str, value: &serde_json::Value) -> Result<Self> {
+        let map = value.as_object().ok_or_else(|| {
+            JankenError::new_parameter_type_mismatch(
+                "object for transaction",
+                format!("{query_name}: {value}"),
+            )
+        })?;
+
+        let string_option = |key: &str| -> Result<Option<&str>> {
+            map.get(key)
+                .map(|val| {
+                    val.as_str().ok_or_else(|| {
+                        JankenError::new_parameter_type_mismatch(
+                            format!("string for transaction {key}"),
+                            format!("{query_name}: {val}"),
+                        )
+                    })
+                })
+                .transpose()
+        };
+        let bool_option = |key: &str| -> Result<bool> {
+            map.get(key).map_or(Ok(false), |val| {
+                val.as_bool().ok_or_else(|| {
+                    JankenError::new_parameter_type_mismatch(
+                        format!("boolean for transaction {key}"),
+                        format!("{query_name}: {val}"),
+                    )
+                })
+            })
+        };
+
+        Ok(TransactionOptions {
+            behavior: string_option("behavior")?
+                .map(str::parse)
+                .transpose()?
+                .unwrap_or_default(),
+            isolation: string_option("isolation")?.map(str::parse).transpose()?,
+            read_only: bool_option("read_only")?,
+            deferrable: bool_option("deferrable")?,
+            retry: map
+                .get("retry")
+                .map(|retry_val| RetryPolicy::from_json(query_name, retry_val))
+                .transpose()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_doubles_up_to_max_backoff() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+        };
+        assert_eq!(policy.delay_after(1), Duration::from_millis(10));
+        assert_eq!(policy.delay_after(2), Duration::from_millis(20));
+        assert_eq!(policy.delay_after(3), Duration::from_millis(40));
+        assert_eq!(policy.delay_after(4), Duration::from_millis(50));
+        assert_eq!(policy.delay_after(40), Duration::from_millis(50));
+    }
+}
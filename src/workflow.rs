@@ -0,0 +1,165 @@
+use crate::{
+    result::{JankenError, QueryResult, Result},
+    transaction::TransactionOptions,
+};
+use std::collections::HashSet;
+
+/// Key of a parameter mapping object that pulls its value from the workflow context
+const FROM_KEY: &str = "$from";
+
+/// One step of a workflow: a named query run with parameters mapped from the request
+/// and from the results of earlier steps
+#[derive(Debug, Clone)]
+pub struct WorkflowStep {
+    /// Name under which the step's result is exposed to later steps (defaults to the query name)
+    pub name: String,
+    /// Name of the query definition executed by this step
+    pub query: String,
+    /// Parameter mappings: `{"$from": "<JSON pointer>"}` objects are resolved against the
+    /// workflow context, any other value is passed as a literal
+    pub params: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Ordered list of query steps executed atomically in a single transaction
+#[derive(Debug, Clone)]
+pub struct WorkflowDef {
+    pub steps: Vec<WorkflowStep>,
+    /// Options of the workflow's transaction: the workflow's own `transaction` field, or else
+    /// the options its steps declare, which must all agree
+    pub transaction: TransactionOptions,
+}
+
+/// Result of one executed workflow step
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkflowStepResult {
+    pub name: String,
+    pub result: QueryResult,
+}
+
+/// Result of executing a workflow, one entry per step in execution order
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkflowResult {
+    pub steps: Vec<WorkflowStepResult>,
+    /// Number of times the workflow's transaction ran, counting retries (1 without retries)
+    pub attempts: u32,
+}
+
+impl WorkflowResult {
+    /// Get the result of a step by name
+    pub fn step(&self, name: &str) -> Option<&QueryResult> {
+        self.steps
+            .iter()
+            .find(|step| step.name == name)
+            .map(|step| &step.result)
+    }
+}
+
+impl WorkflowDef {
+    /// Parse the `workflow` array of a definition:
+    /// `[{"name": "order", "query": "insert_order", "params": {"id": {"$from": "/request/id"}}}, ...]`
+    pub fn from_json(workflow_name: &str, value: &serde_json::Value) -> Result<Self> {
+        let items = value.as_array().ok_or_else(|| {
+            JankenError::new_parameter_type_mismatch(
+                "array of steps for workflow",
+                format!("{workflow_name}: {value}"),
+            )
+        })?;
+
+        let mut step_names = HashSet::new();
+        let mut steps = Vec::new();
+        for item in items {
+            let query = item.get("query").and_then(|q| q.as_str()).ok_or_else(|| {
+                JankenError::new_parameter_type_mismatch(
+                    "workflow step with string 'query' field",
+                    format!("{workflow_name}: {item}"),
+                )
+            })?;
+
+            let name = match item.get("name") {
+                Some(name_val) => name_val.as_str().ok_or_else(|| {
+                    JankenError::new_parameter_type_mismatch(
+                        "string for workflow step name",
+                        format!("{workflow_name}: {name_val}"),
+                    )
+                })?,
+                None => query,
+            };
+            if !step_names.insert(name.to_string()) {
+                return Err(JankenError::new_parameter_type_mismatch(
+                    "unique workflow step names",
+                    format!("{workflow_name}: duplicate step '{name}'"),
+                ));
+            }
+
+            let params = match item.get("params") {
+                Some(params_val) => params_val.as_object().cloned().ok_or_else(|| {
+                    JankenError::new_parameter_type_mismatch(
+                        "object for workflow step params",
+                        format!("{workflow_name}: {params_val}"),
+                    )
+                })?,
+                None => serde_json::Map::new(),
+            };
+
+            steps.push(WorkflowStep {
+                name: name.to_string(),
+                query: query.to_string(),
+                params,
+            });
+        }
+
+        Ok(WorkflowDef {
+            steps,
+            transaction: TransactionOptions::default(),
+        })
+    }
+}
+
+impl WorkflowStep {
+    /// Build the request parameters of this step: the workflow request overlaid with the
+    /// step's mapped values. Mappings are resolved against
+    /// `{"request": <request params>, "steps": {<step name>: {"data", "result_sets"}}}`.
+    pub fn resolve_params(
+        &self,
+        request_params: &serde_json::Value,
+        completed_steps: &[WorkflowStepResult],
+    ) -> Result<serde_json::Value> {
+        let context = serde_json::json!({
+            "request": request_params,
+            "steps": completed_steps
+                .iter()
+                .map(|step| {
+                    let step_context = serde_json::json!({
+                        "data": step.result.data,
+                        "result_sets": step.result.result_sets,
+                    });
+                    (step.name.clone(), step_context)
+                })
+                .collect::<serde_json::Map<_, _>>(),
+        });
+
+        let mut params = request_params.as_object().cloned().unwrap_or_default();
+        for (param_name, mapping) in &self.params {
+            let value = match mapping.get(FROM_KEY) {
+                Some(pointer_val) => {
+                    let pointer = pointer_val.as_str().ok_or_else(|| {
+                        JankenError::new_parameter_type_mismatch(
+                            "JSON pointer string for $from",
+                            format!("{}.{param_name}: {pointer_val}", self.name),
+                        )
+                    })?;
+                    context.pointer(pointer).cloned().ok_or_else(|| {
+                        JankenError::new_parameter_type_mismatch(
+                            format!("value at {pointer}"),
+                            format!("{}.{param_name}: nothing found", self.name),
+                        )
+                    })?
+                }
+                None => mapping.clone(),
+            };
+            params.insert(param_name.clone(), value);
+        }
+
+        Ok(serde_json::Value::Object(params))
+    }
+}
@@ -0,0 +1,136 @@
+use jankensqlhub::{
+    JankenError, M_EXPECTED, M_GOT, M_INDEX, M_PARAM_NAME, QueryDefinitions, error_meta,
+    query_batch_sqlite,
+};
+use rusqlite::Connection;
+
+fn definitions() -> QueryDefinitions {
+    QueryDefinitions::from_json(serde_json::json!({
+        "add_user": {
+            "query": "INSERT INTO users (id, name) VALUES (@id, @name)",
+            "args": { "id": { "type": "integer" } }
+        },
+        "rename_users": {
+            "query": "UPDATE users SET name = @name WHERE id IN :[ids]",
+            "args": { "ids": { "itemtype": "integer" } }
+        },
+        "list_users": {
+            "query": "SELECT id, name FROM users",
+            "returns": ["id", "name"]
+        }
+    }))
+    .unwrap()
+}
+
+fn setup_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)", [])
+        .unwrap();
+    conn
+}
+
+fn user_count(conn: &Connection) -> i64 {
+    conn.query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))
+        .unwrap()
+}
+
+#[test]
+fn test_batch_inserts_with_one_prepared_statement() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let params_list: Vec<serde_json::Value> = (1..=100)
+        .map(|id| serde_json::json!({"id": id, "name": format!("user{id}")}))
+        .collect();
+    let result = query_batch_sqlite(&mut conn, &queries, "add_user", &params_list).unwrap();
+    assert_eq!(result.row_counts, vec![1; 100]);
+    assert_eq!(result.prepared_statements, 1);
+    assert_eq!(user_count(&conn), 100);
+
+    // List parameters of different lengths expand to different statement shapes
+    let params_list = vec![
+        serde_json::json!({"ids": [1, 2], "name": "pair"}),
+        serde_json::json!({"ids": [3, 4, 5], "name": "triple"}),
+        serde_json::json!({"ids": [6, 7], "name": "pair"}),
+        serde_json::json!({"ids": [1000], "name": "missing"}),
+    ];
+    let result = query_batch_sqlite(&mut conn, &queries, "rename_users", &params_list).unwrap();
+    assert_eq!(result.row_counts, vec![2, 3, 2, 0]);
+    assert_eq!(result.prepared_statements, 3);
+}
+
+#[test]
+fn test_batch_validation_failure_runs_nothing() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let params_list = vec![
+        serde_json::json!({"id": 1, "name": "Alice"}),
+        serde_json::json!({"id": 2}),
+    ];
+    let err = query_batch_sqlite(&mut conn, &queries, "add_user", &params_list).unwrap_err();
+    match err.downcast_ref::<JankenError>().unwrap() {
+        JankenError::BatchItemFailed { data } => {
+            assert_eq!(error_meta(data, M_INDEX).unwrap(), "1");
+        }
+        other => panic!("Expected BatchItemFailed, got: {other:?}"),
+    }
+    // The underlying validation error is next in the error chain
+    let source = err.chain().nth(1).unwrap();
+    match source.downcast_ref::<JankenError>().unwrap() {
+        JankenError::ParameterNotProvided { data } => {
+            assert_eq!(error_meta(data, M_PARAM_NAME).unwrap(), "name");
+        }
+        other => panic!("Expected ParameterNotProvided, got: {other:?}"),
+    }
+    assert_eq!(user_count(&conn), 0);
+}
+
+#[test]
+fn test_batch_execution_failure_rolls_back() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let params_list = vec![
+        serde_json::json!({"id": 1, "name": "Alice"}),
+        serde_json::json!({"id": 2, "name": "Bob"}),
+        serde_json::json!({"id": 1, "name": "Duplicate"}),
+    ];
+    let err = query_batch_sqlite(&mut conn, &queries, "add_user", &params_list).unwrap_err();
+    match err.downcast_ref::<JankenError>().unwrap() {
+        JankenError::BatchItemFailed { data } => {
+            assert_eq!(error_meta(data, M_INDEX).unwrap(), "2");
+        }
+        other => panic!("Expected BatchItemFailed, got: {other:?}"),
+    }
+    assert_eq!(
+        err.downcast_ref::<rusqlite::Error>()
+            .unwrap()
+            .sqlite_error_code(),
+        Some(rusqlite::ErrorCode::ConstraintViolation)
+    );
+    assert_eq!(user_count(&conn), 0);
+}
+
+#[test]
+fn test_batch_rejects_queries_with_returns() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let err = query_batch_sqlite(&mut conn, &queries, "list_users", &[serde_json::json!({})])
+        .unwrap_err();
+    match err.downcast::<JankenError>().unwrap() {
+        JankenError::ParameterTypeMismatch { data } => {
+            assert_eq!(
+                error_meta(&data, M_EXPECTED).unwrap(),
+                "query without returns for batch"
+            );
+            assert_eq!(error_meta(&data, M_GOT).unwrap(), "list_users has returns");
+        }
+        other => panic!("Expected ParameterTypeMismatch, got: {other:?}"),
+    }
+
+    // An empty batch is a no-op
+    let result = query_batch_sqlite(&mut conn, &queries, "add_user", &[]).unwrap();
+    assert!(result.row_counts.is_empty());
+}
@@ -0,0 +1,140 @@
+use jankensqlhub::{
+    JankenError, M_EXPECTED, M_GOT, QueryDefinitions, error_meta, query_run_sqlite,
+};
+use rusqlite::Connection;
+
+fn setup() -> (QueryDefinitions, Connection) {
+    let json_definitions = serde_json::json!({
+        "insert_base64": {
+            "query": "INSERT INTO blobs VALUES (@id, @data)",
+            "args": {
+                "id": { "type": "integer" },
+                "data": { "type": "blob", "encoding": "base64", "range": [1, 3] }
+            }
+        },
+        "insert_hex": {
+            "query": "INSERT INTO blobs VALUES (@id, @data)",
+            "args": {
+                "id": { "type": "integer" },
+                "data": { "type": "blob", "encoding": "hex" }
+            }
+        },
+        "insert_array": {
+            "query": "INSERT INTO blobs VALUES (@id, @data)",
+            "args": {
+                "id": { "type": "integer" },
+                "data": { "type": "blob" }
+            }
+        },
+        "select_base64": {
+            "query": "SELECT id, data FROM blobs ORDER BY id",
+            "returns": ["id", "data"],
+            "blob_encoding": "base64"
+        },
+        "select_hex": {
+            "query": "SELECT data FROM blobs ORDER BY id",
+            "returns": ["data"],
+            "blob_encoding": "hex"
+        }
+    });
+    let queries = QueryDefinitions::from_json(json_definitions).unwrap();
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute("CREATE TABLE blobs (id INTEGER, data BLOB)", [])
+        .unwrap();
+    (queries, conn)
+}
+
+fn assert_type_mismatch(err: anyhow::Error, expected: &str, got: &str) {
+    match err.downcast::<JankenError>() {
+        Ok(JankenError::ParameterTypeMismatch { data }) => {
+            assert_eq!(error_meta(&data, M_EXPECTED).unwrap(), expected);
+            assert_eq!(error_meta(&data, M_GOT).unwrap(), got);
+        }
+        other => panic!("Expected ParameterTypeMismatch, got: {other:?}"),
+    }
+}
+
+#[test]
+fn test_encoded_blob_parameters_and_results() {
+    let (queries, mut conn) = setup();
+
+    let params = serde_json::json!({"id": 1, "data": {"base64": "AQL/"}});
+    query_run_sqlite(&mut conn, &queries, "insert_base64", &params).unwrap();
+    let params = serde_json::json!({"id": 2, "data": {"hex": "CAFE"}});
+    query_run_sqlite(&mut conn, &queries, "insert_hex", &params).unwrap();
+    // Byte arrays are still accepted when an encoding is selected
+    let params = serde_json::json!({"id": 3, "data": [7]});
+    query_run_sqlite(&mut conn, &queries, "insert_hex", &params).unwrap();
+
+    let result =
+        query_run_sqlite(&mut conn, &queries, "select_base64", &serde_json::json!({})).unwrap();
+    assert_eq!(
+        result.data,
+        vec![
+            serde_json::json!({"id": 1, "data": "AQL/"}),
+            serde_json::json!({"id": 2, "data": "yv4="}),
+            serde_json::json!({"id": 3, "data": "Bw=="}),
+        ]
+    );
+
+    let result =
+        query_run_sqlite(&mut conn, &queries, "select_hex", &serde_json::json!({})).unwrap();
+    assert_eq!(
+        result.data,
+        vec![
+            serde_json::json!({"data": "0102ff"}),
+            serde_json::json!({"data": "cafe"}),
+            serde_json::json!({"data": "07"}),
+        ]
+    );
+}
+
+#[test]
+fn test_encoded_blob_parameter_errors() {
+    let (queries, mut conn) = setup();
+
+    // Encoding not selected by the arg definition
+    let params = serde_json::json!({"id": 1, "data": {"hex": "01"}});
+    let err = query_run_sqlite(&mut conn, &queries, "insert_base64", &params).unwrap_err();
+    assert_type_mismatch(
+        err,
+        "array of byte values or base64 encoded blob",
+        r#"{"hex":"01"}"#,
+    );
+
+    let params = serde_json::json!({"id": 1, "data": {"base64": "AQ=="}});
+    let err = query_run_sqlite(&mut conn, &queries, "insert_array", &params).unwrap_err();
+    assert_type_mismatch(err, "array of byte values", r#"{"base64":"AQ=="}"#);
+
+    // Invalid encoded text
+    let params = serde_json::json!({"id": 1, "data": {"hex": "0g"}});
+    let err = query_run_sqlite(&mut conn, &queries, "insert_hex", &params).unwrap_err();
+    assert_type_mismatch(err, "blob", r#"{"hex":"0g"}"#);
+
+    // Range applies to the decoded size
+    let params = serde_json::json!({"id": 1, "data": {"base64": "AQIDBA=="}});
+    let err = query_run_sqlite(&mut conn, &queries, "insert_base64", &params).unwrap_err();
+    assert_type_mismatch(err, "blob size between 1 and 3 bytes", "4 bytes");
+}
+
+#[test]
+fn test_invalid_blob_encoding_definitions() {
+    let err = QueryDefinitions::from_json(serde_json::json!({
+        "bad": {
+            "query": "INSERT INTO blobs VALUES (@data)",
+            "args": { "data": { "type": "blob", "encoding": "base32" } }
+        }
+    }))
+    .unwrap_err();
+    assert_type_mismatch(err, "blob encoding array, base64 or hex", "base32");
+
+    let err = QueryDefinitions::from_json(serde_json::json!({
+        "bad": {
+            "query": "SELECT data FROM blobs",
+            "returns": ["data"],
+            "blob_encoding": 64
+        }
+    }))
+    .unwrap_err();
+    assert_type_mismatch(err, "string for blob_encoding", "bad: 64");
+}
@@ -0,0 +1,297 @@
+use jankensqlhub::{
+    Dialect, JankenError, M_EXPECTED, M_GOT, M_PARAM_NAME, QueryDefinitions, error_meta,
+    query_run_sqlite,
+};
+use rusqlite::Connection;
+
+fn definitions() -> QueryDefinitions {
+    QueryDefinitions::from_json(serde_json::json!({
+        "add_products": {
+            "query": "INSERT INTO products (id, name, price) VALUES *[rows]",
+            "args": {
+                "rows": {
+                    "range": [1, 50000],
+                    "fields": [
+                        { "name": "id", "type": "integer" },
+                        { "name": "name", "pattern": "^[a-z ]+$" },
+                        { "name": "price", "type": "float", "range": [0, 1000] }
+                    ]
+                }
+            }
+        },
+        "add_products_returning": {
+            "query": "INSERT INTO products (id, name, price) VALUES *[rows] RETURNING id, name",
+            "returns": ["id", "name"],
+            "args": {
+                "rows": {
+                    "fields": [
+                        { "name": "id", "type": "integer" },
+                        { "name": "name" },
+                        { "name": "price", "type": "float" }
+                    ]
+                }
+            }
+        },
+        "add_tagged": {
+            "query": "INSERT INTO tags (product_id, tag) SELECT column1, column2 FROM (VALUES *[tags]) WHERE column1 > @min_id",
+            "args": {
+                "min_id": { "type": "integer" },
+                "tags": {
+                    "fields": [
+                        { "name": "product_id", "type": "integer" },
+                        { "name": "tag", "sensitive": true }
+                    ]
+                }
+            }
+        }
+    }))
+    .unwrap()
+}
+
+fn setup_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute(
+        "CREATE TABLE products (id INTEGER PRIMARY KEY, name TEXT, price REAL)",
+        [],
+    )
+    .unwrap();
+    conn.execute("CREATE TABLE tags (product_id INTEGER, tag TEXT)", [])
+        .unwrap();
+    conn
+}
+
+fn product_count(conn: &Connection) -> i64 {
+    conn.query_row("SELECT COUNT(*) FROM products", [], |row| row.get(0))
+        .unwrap()
+}
+
+fn type_mismatch(err: anyhow::Error) -> (String, String) {
+    match err.downcast::<JankenError>().unwrap() {
+        JankenError::ParameterTypeMismatch { data } => (
+            error_meta(&data, M_EXPECTED).unwrap(),
+            error_meta(&data, M_GOT).unwrap(),
+        ),
+        other => panic!("Expected ParameterTypeMismatch, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_rows_expand_to_values_tuples() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let params = serde_json::json!({
+        "rows": [
+            {"id": 1, "name": "apple", "price": 1.5},
+            {"id": 2, "name": "pear", "price": 2.25, "ignored": true}
+        ]
+    });
+    let result = query_run_sqlite(&mut conn, &queries, "add_products", &params).unwrap();
+    assert_eq!(
+        result.sql_statements,
+        vec![
+            "INSERT INTO products (id, name, price) VALUES (:rows_0_id, :rows_0_name, :rows_0_price), (:rows_1_id, :rows_1_name, :rows_1_price)"
+        ]
+    );
+
+    let stored: Vec<(i64, String, f64)> = conn
+        .prepare("SELECT id, name, price FROM products ORDER BY id")
+        .unwrap()
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .unwrap()
+        .collect::<rusqlite::Result<_>>()
+        .unwrap();
+    assert_eq!(
+        stored,
+        vec![(1, "apple".to_string(), 1.5), (2, "pear".to_string(), 2.25)]
+    );
+}
+
+#[test]
+fn test_rows_with_returning_and_regular_parameters() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let params = serde_json::json!({
+        "rows": [{"id": 7, "name": "plum", "price": 3.0}, {"id": 8, "name": "fig", "price": 4.0}]
+    });
+    let result = query_run_sqlite(&mut conn, &queries, "add_products_returning", &params).unwrap();
+    assert_eq!(
+        result.data,
+        vec![
+            serde_json::json!({"id": 7, "name": "plum"}),
+            serde_json::json!({"id": 8, "name": "fig"})
+        ]
+    );
+
+    // Rows mix with @params, and sensitive fields are redacted in the rendered SQL
+    let params = serde_json::json!({
+        "min_id": 1,
+        "tags": [{"product_id": 1, "tag": "secret"}, {"product_id": 2, "tag": "hidden"}]
+    });
+    let result = query_run_sqlite(&mut conn, &queries, "add_tagged", &params).unwrap();
+    let rendered = result.statements[0].render_sql();
+    assert!(rendered.contains("(2, '[REDACTED]')"), "{rendered}");
+    assert!(!rendered.contains("secret"));
+    let tag_count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM tags", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(tag_count, 1);
+}
+
+#[test]
+fn test_rows_field_validation_errors() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let run = |conn: &mut Connection, rows: serde_json::Value| {
+        query_run_sqlite(
+            conn,
+            &queries,
+            "add_products",
+            &serde_json::json!({ "rows": rows }),
+        )
+        .unwrap_err()
+    };
+
+    let err = run(
+        &mut conn,
+        serde_json::json!([{"id": "one", "name": "a", "price": 1}]),
+    );
+    assert_eq!(
+        type_mismatch(err),
+        (
+            "integer for field id at index 0".to_string(),
+            "\"one\"".to_string()
+        )
+    );
+
+    let err = run(
+        &mut conn,
+        serde_json::json!([
+            {"id": 1, "name": "a", "price": 1},
+            {"id": 2, "name": "b", "price": 5000}
+        ]),
+    );
+    assert_eq!(
+        type_mismatch(err).0,
+        "value between 0 and 1000 for field price at index 1"
+    );
+
+    let err = run(
+        &mut conn,
+        serde_json::json!([{"id": 1, "name": "Bad1", "price": 1}]),
+    );
+    assert_eq!(
+        type_mismatch(err).0,
+        "string matching pattern '^[a-z ]+$' for field name at index 0"
+    );
+
+    let err = run(&mut conn, serde_json::json!([{"id": 1, "price": 1}]));
+    match err.downcast::<JankenError>().unwrap() {
+        JankenError::ParameterNotProvided { data } => {
+            assert_eq!(error_meta(&data, M_PARAM_NAME).unwrap(), "rows[0].name");
+        }
+        other => panic!("Expected ParameterNotProvided, got {other:?}"),
+    }
+
+    let err = run(&mut conn, serde_json::json!([[1, "a", 1.0]]));
+    assert_eq!(type_mismatch(err).0, "object at index 0");
+
+    let err = run(&mut conn, serde_json::json!({"id": 1}));
+    assert_eq!(type_mismatch(err).0, "rows");
+
+    let err = run(&mut conn, serde_json::json!([]));
+    assert_eq!(
+        type_mismatch(err),
+        (
+            "rows size between 1 and 50000 rows".to_string(),
+            "0 rows".to_string()
+        )
+    );
+
+    // Nothing was inserted by the failed requests
+    assert_eq!(product_count(&conn), 0);
+}
+
+#[test]
+fn test_rows_definition_errors() {
+    let cases = [
+        (
+            serde_json::json!({"q": {"query": "INSERT INTO t (a) VALUES *[rows]"}}),
+            "'fields' definition in args for rows parameter",
+        ),
+        (
+            serde_json::json!({"q": {
+                "query": "INSERT INTO t (a) VALUES *[rows]",
+                "args": {"rows": {"fields": []}}
+            }}),
+            "non-empty array of field definitions for fields",
+        ),
+        (
+            serde_json::json!({"q": {
+                "query": "INSERT INTO t (a) VALUES *[rows]",
+                "args": {"rows": {"fields": [{"type": "integer"}]}}
+            }}),
+            "field definition with an alphanumeric 'name'",
+        ),
+        (
+            serde_json::json!({"q": {
+                "query": "INSERT INTO t (a) VALUES *[rows]",
+                "args": {"rows": {"fields": [{"name": "a"}, {"name": "a"}]}}
+            }}),
+            "unique field names",
+        ),
+        (
+            serde_json::json!({"q": {
+                "query": "INSERT INTO t (a) VALUES *[rows]",
+                "args": {"rows": {"fields": [{"name": "a", "type": "table_name"}]}}
+            }}),
+            "field type integer, string, float, boolean or blob",
+        ),
+    ];
+
+    for (definition, expected) in cases {
+        let err = QueryDefinitions::from_json(definition).unwrap_err();
+        assert_eq!(type_mismatch(err).0, expected);
+    }
+
+    let err = QueryDefinitions::from_json(serde_json::json!({"q": {
+        "query": "INSERT INTO t (a) VALUES *[rows] RETURNING @rows",
+        "args": {"rows": {"fields": [{"name": "a"}]}}
+    }}))
+    .unwrap_err();
+    assert!(matches!(
+        err.downcast::<JankenError>().unwrap(),
+        JankenError::ParameterNameConflict { .. }
+    ));
+}
+
+#[test]
+fn test_rows_are_chunked_at_the_sqlite_parameter_limit() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    // 11000 rows x 3 fields = 33000 values, over SQLite's 32766 host parameters
+    let rows: Vec<serde_json::Value> = (1..=11000)
+        .map(|id| serde_json::json!({"id": id, "name": "bulk item", "price": 1.0}))
+        .collect();
+    let params = serde_json::json!({ "rows": rows });
+
+    let validated = queries
+        .validate("add_products_returning", &params, Dialect::Sqlite)
+        .unwrap();
+    assert_eq!(validated.len(), 2);
+    assert_eq!(validated[0].parameters.len(), 10922 * 3);
+    assert_eq!(validated[1].parameters.len(), (11000 - 10922) * 3);
+
+    // Each chunk runs as its own statement and the RETURNING rows of all chunks are concatenated
+    let result = query_run_sqlite(&mut conn, &queries, "add_products_returning", &params).unwrap();
+    assert_eq!(result.sql_statements.len(), 2);
+    assert_eq!(result.data.len(), 11000);
+    assert_eq!(
+        result.data[10999],
+        serde_json::json!({"id": 11000, "name": "bulk item"})
+    );
+    assert_eq!(product_count(&conn), 11000);
+}
@@ -22,3 +22,45 @@ fn test_str_utils_functionality() {
     assert_eq!(params.len(), 1);
     assert_eq!(params[0], "param");
 }
+
+#[test]
+fn test_looks_like_write_statement() {
+    use jankensqlhub::str_utils::looks_like_write_statement;
+
+    assert!(looks_like_write_statement("insert into t VALUES (1)"));
+    assert!(looks_like_write_statement("  DROP TABLE t"));
+    assert!(looks_like_write_statement(
+        "WITH gone AS (DELETE FROM t RETURNING id) SELECT id FROM gone"
+    ));
+
+    assert!(!looks_like_write_statement("SELECT * FROM updates"));
+    assert!(!looks_like_write_statement("SELECT * FROM t FOR UPDATE"));
+    assert!(!looks_like_write_statement(
+        "WITH x AS (SELECT 'UPDATE' AS word) SELECT word FROM x"
+    ));
+    assert!(!looks_like_write_statement(""));
+}
+
+#[test]
+fn test_quote_mask_matches_is_in_quotes() {
+    use jankensqlhub::str_utils::{is_in_quotes, quote_mask};
+
+    let sql = "SELECT 'a\\'@b' AS \"@c\", @d FROM t WHERE x = 'é@' AND y = @e";
+    let mask = quote_mask(sql);
+    assert_eq!(mask.len(), sql.len());
+    for (pos, _) in sql.match_indices('@') {
+        // is_in_quotes counts characters, so compare on the ASCII prefix only
+        if sql[..pos].is_ascii() {
+            assert_eq!(mask[pos], is_in_quotes(sql, pos), "position {pos}");
+        }
+    }
+    let unquoted: Vec<usize> = sql
+        .match_indices('@')
+        .map(|(pos, _)| pos)
+        .filter(|&pos| !mask[pos])
+        .collect();
+    assert_eq!(
+        unquoted,
+        vec![sql.find("@d").unwrap(), sql.find("@e").unwrap()]
+    );
+}
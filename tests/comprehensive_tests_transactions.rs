@@ -1,4 +1,4 @@
-use jankensqlhub::{QueryDefinitions, query_run_sqlite};
+use jankensqlhub::{QueryDefinitions, query_run_sqlite, query_run_sqlite_with_savepoint};
 use rusqlite::Connection;
 
 fn setup_db() -> Connection {
@@ -284,3 +284,68 @@ fn test_multi_statement_table_name_parameters() {
     );
     assert_eq!(result.data[1], serde_json::json!({"id": 2, "name": "BOB"}));
 }
+
+#[test]
+fn test_savepoint_rolls_back_only_the_failing_step() {
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "add_user": {
+            "query": "INSERT INTO audit (entry) VALUES (@name); INSERT INTO users (id, name) VALUES (@id, @name)",
+            "args": { "id": { "type": "integer" } }
+        }
+    }))
+    .unwrap();
+
+    let mut conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+         CREATE TABLE audit (entry TEXT);",
+    )
+    .unwrap();
+
+    let tx = conn.transaction().unwrap();
+    query_run_sqlite_with_savepoint(
+        &tx,
+        &queries,
+        "add_user",
+        &serde_json::json!({"id": 1, "name": "Alice"}),
+    )
+    .unwrap();
+
+    // The duplicate id fails in the second statement; the audit entry written by the
+    // first statement is rolled back with it while earlier steps are kept
+    let err = query_run_sqlite_with_savepoint(
+        &tx,
+        &queries,
+        "add_user",
+        &serde_json::json!({"id": 1, "name": "Duplicate"}),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.downcast::<rusqlite::Error>()
+            .unwrap()
+            .sqlite_error_code(),
+        Some(rusqlite::ErrorCode::ConstraintViolation)
+    );
+
+    query_run_sqlite_with_savepoint(
+        &tx,
+        &queries,
+        "add_user",
+        &serde_json::json!({"id": 2, "name": "Bob"}),
+    )
+    .unwrap();
+    tx.commit().unwrap();
+
+    let users: Vec<String> = conn
+        .prepare("SELECT name FROM users ORDER BY id")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(users, vec!["Alice", "Bob"]);
+    let audit_count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM audit", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(audit_count, 2);
+}
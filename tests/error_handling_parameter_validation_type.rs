@@ -63,7 +63,7 @@ fn test_invalid_parameter_type_error() {
         let got = error_meta(&data, M_GOT).unwrap();
         assert_eq!(
             expected,
-            "integer, string, float, boolean, table_name, list or blob"
+            "integer, string, float, boolean, table_name, list, blob, search or fts_query"
         );
         assert_eq!(got, "invalid_type");
     } else {
@@ -419,7 +419,9 @@ fn test_invalid_itemtype_definition_error() {
     if let Ok(JankenError::ParameterTypeMismatch { data }) = err.downcast::<JankenError>() {
         let expected = error_meta(&data, M_EXPECTED).unwrap();
         let got = error_meta(&data, M_GOT).unwrap();
-        assert!(expected.contains("integer, string, float, boolean, table_name, list or blob"));
+        assert!(expected.contains(
+            "integer, string, float, boolean, table_name, list, blob, search or fts_query"
+        ));
         assert_eq!(got, "invalid_type_not_supported");
     } else {
         panic!("Expected ParameterTypeMismatch for invalid type string, got: {err_str}");
@@ -1,6 +1,7 @@
 use jankensqlhub::{
-    ERR_CODE_PARAMETER_NAME_CONFLICT, ERR_CODE_PARAMETER_NOT_PROVIDED,
-    ERR_CODE_PARAMETER_TYPE_MISMATCH, ERR_CODE_QUERY_NOT_FOUND, JankenError, get_error_data,
+    ERR_CODE_BATCH_ITEM_FAILED, ERR_CODE_PARAMETER_NAME_CONFLICT, ERR_CODE_PARAMETER_NOT_PROVIDED,
+    ERR_CODE_PARAMETER_TYPE_MISMATCH, ERR_CODE_QUERY_NOT_FOUND, ERR_CODE_READONLY_VIOLATION,
+    ERR_CODE_ROW_LIMIT_EXCEEDED, JankenError, M_ERROR, M_INDEX, error_meta, get_error_data,
     get_error_info,
 };
 
@@ -37,6 +38,9 @@ fn test_all_error_codes_are_present() {
         ERR_CODE_PARAMETER_NOT_PROVIDED,
         ERR_CODE_PARAMETER_TYPE_MISMATCH,
         ERR_CODE_PARAMETER_NAME_CONFLICT,
+        ERR_CODE_READONLY_VIOLATION,
+        ERR_CODE_BATCH_ITEM_FAILED,
+        ERR_CODE_ROW_LIMIT_EXCEEDED,
     ];
 
     for &code in &expected_codes {
@@ -95,6 +99,22 @@ fn test_get_error_data() {
     let data = get_error_data(&err);
     assert_eq!(data.code, 2030); // ERR_CODE_PARAMETER_NAME_CONFLICT
     assert!(data.metadata.is_some());
+
+    // Test ReadonlyViolation variant
+    let err = JankenError::new_readonly_violation("DELETE FROM users");
+    let data = get_error_data(&err);
+    assert_eq!(data.code, 2040); // ERR_CODE_READONLY_VIOLATION
+    assert!(data.metadata.is_some());
+
+    // Test BatchItemFailed variant
+    let err = JankenError::new_batch_item_failed(3, "UNIQUE constraint failed");
+    let data = get_error_data(&err);
+    assert_eq!(data.code, 2050); // ERR_CODE_BATCH_ITEM_FAILED
+    assert_eq!(error_meta(data, M_INDEX).unwrap(), "3");
+    assert_eq!(
+        error_meta(data, M_ERROR).unwrap(),
+        "UNIQUE constraint failed"
+    );
 }
 
 #[test]
@@ -158,6 +178,39 @@ fn test_get_error_info() {
         "Parameter name conflicts with table name"
     );
 
+    // ReadonlyViolation error
+    let readonly_err = JankenError::new_readonly_violation("DELETE FROM users");
+    let readonly_info = get_error_info(get_error_data(&readonly_err).code).unwrap();
+    assert_eq!(readonly_info.code, ERR_CODE_READONLY_VIOLATION);
+    assert_eq!(readonly_info.name, "READONLY_VIOLATION");
+    assert_eq!(readonly_info.category, "Query");
+    assert_eq!(
+        readonly_info.description,
+        "Query declared readonly contains a statement that writes"
+    );
+
+    // BatchItemFailed error
+    let batch_err = JankenError::new_batch_item_failed(0, "failed");
+    let batch_info = get_error_info(get_error_data(&batch_err).code).unwrap();
+    assert_eq!(batch_info.code, ERR_CODE_BATCH_ITEM_FAILED);
+    assert_eq!(batch_info.name, "BATCH_ITEM_FAILED");
+    assert_eq!(batch_info.category, "Query");
+    assert_eq!(
+        batch_info.description,
+        "An item of a batch failed validation or execution"
+    );
+
+    // RowLimitExceeded error
+    let row_limit_err = JankenError::new_row_limit_exceeded(10, "SELECT * FROM users");
+    let row_limit_info = get_error_info(get_error_data(&row_limit_err).code).unwrap();
+    assert_eq!(row_limit_info.code, ERR_CODE_ROW_LIMIT_EXCEEDED);
+    assert_eq!(row_limit_info.name, "ROW_LIMIT_EXCEEDED");
+    assert_eq!(row_limit_info.category, "Query");
+    assert_eq!(
+        row_limit_info.description,
+        "Read query returned more rows than its max_rows"
+    );
+
     // Test invalid code
     let invalid_info = get_error_info(9999);
     assert!(invalid_info.is_none());
@@ -0,0 +1,89 @@
+use jankensqlhub::{
+    BoundParameter, Dialect, JankenError, M_EXPECTED, M_GOT, ParameterValue, QueryDefinitions,
+    error_meta, query_run_sqlite,
+};
+use rusqlite::Connection;
+
+#[test]
+fn test_executed_statements_report_bound_values() {
+    let json_definitions = serde_json::json!({
+        "create_user": {
+            "query": "INSERT INTO users (name, password) VALUES (@name, @password); UPDATE users SET name = name WHERE id IN :[ids]",
+            "args": {
+                "password": { "sensitive": true },
+                "ids": { "itemtype": "integer", "sensitive": true }
+            }
+        },
+        "find_user": {
+            "query": "SELECT id, name FROM users WHERE name = @name",
+            "returns": ["id", "name"]
+        }
+    });
+    let queries = QueryDefinitions::from_json(json_definitions).unwrap();
+    let mut conn = Connection::open_in_memory().unwrap();
+    conn.execute(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, password TEXT)",
+        [],
+    )
+    .unwrap();
+
+    let params = serde_json::json!({"name": "O'Brien", "password": "hunter2", "ids": [1]});
+    let result = query_run_sqlite(&mut conn, &queries, "create_user", &params).unwrap();
+
+    assert_eq!(result.statements.len(), 2);
+    let insert = &result.statements[0];
+    assert_eq!(insert.dialect, Dialect::Sqlite);
+    assert_eq!(insert.sql, result.sql_statements[0]);
+    assert_eq!(
+        insert.parameters,
+        vec![
+            BoundParameter {
+                placeholder: ":name".to_string(),
+                value: ParameterValue::String("O'Brien".to_string()),
+                sensitive: false,
+            },
+            BoundParameter {
+                placeholder: ":password".to_string(),
+                value: ParameterValue::String("hunter2".to_string()),
+                sensitive: true,
+            },
+        ]
+    );
+    assert_eq!(
+        insert.render_sql(),
+        "INSERT INTO users (name, password) VALUES ('O''Brien', '[REDACTED]')"
+    );
+    assert_eq!(
+        result.statements[1].render_sql(),
+        "UPDATE users SET name = name WHERE id IN ('[REDACTED]')"
+    );
+
+    let params = serde_json::json!({"name": "O'Brien"});
+    let result = query_run_sqlite(&mut conn, &queries, "find_user", &params).unwrap();
+    assert_eq!(
+        result.statements[0].render_sql(),
+        "SELECT id, name FROM users WHERE name = 'O''Brien'"
+    );
+    assert_eq!(result.data.len(), 1);
+}
+
+#[test]
+fn test_sensitive_must_be_boolean() {
+    let err = QueryDefinitions::from_json(serde_json::json!({
+        "bad": {
+            "query": "SELECT 1 WHERE 1 = @token",
+            "args": { "token": { "sensitive": "yes" } }
+        }
+    }))
+    .unwrap_err();
+    match err.downcast::<JankenError>() {
+        Ok(JankenError::ParameterTypeMismatch { data }) => {
+            assert_eq!(
+                error_meta(&data, M_EXPECTED).unwrap(),
+                "boolean for sensitive"
+            );
+            assert_eq!(error_meta(&data, M_GOT).unwrap(), "\"yes\"");
+        }
+        other => panic!("Expected ParameterTypeMismatch, got: {other:?}"),
+    }
+}
@@ -0,0 +1,49 @@
+use jankensqlhub::{QueryDefinitions, query_explain_sqlite};
+use rusqlite::Connection;
+
+#[test]
+fn test_explain_query_plan_sqlite() {
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "get_user": {
+            "query": "SELECT id, name FROM users WHERE id = @id",
+            "returns": ["id", "name"],
+            "args": { "id": { "type": "integer" } }
+        },
+        "add_users": {
+            "query": "INSERT INTO users (name) VALUES (@name); INSERT INTO users (name) SELECT name FROM users WHERE id IN :[ids]",
+            "args": { "ids": { "itemtype": "integer" } }
+        }
+    }))
+    .unwrap();
+
+    let mut conn = Connection::open_in_memory().unwrap();
+    conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)", [])
+        .unwrap();
+
+    let params = serde_json::json!({"id": 1});
+    let explained = query_explain_sqlite(&mut conn, &queries, "get_user", &params).unwrap();
+    assert_eq!(
+        explained,
+        serde_json::json!([{
+            "sql": "SELECT id, name FROM users WHERE id = :id",
+            "plan": [{
+                "id": explained[0]["plan"][0]["id"],
+                "detail": "SEARCH users USING INTEGER PRIMARY KEY (rowid=?)",
+                "children": []
+            }]
+        }])
+    );
+
+    // Every statement is explained but nothing is executed
+    let params = serde_json::json!({"name": "Alice", "ids": [1, 2]});
+    let explained = query_explain_sqlite(&mut conn, &queries, "add_users", &params).unwrap();
+    assert_eq!(explained.as_array().unwrap().len(), 2);
+    assert_eq!(
+        explained[1]["sql"],
+        "INSERT INTO users (name) SELECT name FROM users WHERE id IN (:ids_0, :ids_1)"
+    );
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(count, 0);
+}
@@ -0,0 +1,87 @@
+use jankensqlhub::{
+    BlobEncoding, ExportFormat, ExportOptions, NullEncoding, QueryDefinitions, query_export_sqlite,
+};
+use rusqlite::Connection;
+use serde_json::json;
+
+fn setup_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute(
+        "CREATE TABLE files (id INTEGER, name TEXT, content BLOB)",
+        [],
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO files VALUES (1, 'report, final', X'48690A'), (2, NULL, X'FF')",
+        [],
+    )
+    .unwrap();
+    conn
+}
+
+fn export_definitions() -> QueryDefinitions {
+    QueryDefinitions::from_json(json!({
+        "list_files": {
+            "query": "SELECT id, name, content FROM files ORDER BY id",
+            "returns": ["name", "id", "content"]
+        }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_export_sqlite_csv_with_base64_blobs() {
+    let mut conn = setup_db();
+    let queries = export_definitions();
+    let options = ExportOptions {
+        format: ExportFormat::Csv,
+        blob_encoding: BlobEncoding::Base64,
+        null_encoding: NullEncoding::Text("\\N".to_string()),
+    };
+
+    let mut out = Vec::new();
+    let result = query_export_sqlite(
+        &mut conn,
+        &queries,
+        "list_files",
+        &json!({}),
+        &mut out,
+        &options,
+    )
+    .unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "name,id,content\n\"report, final\",1,SGkK\n\\N,2,/w==\n"
+    );
+    assert_eq!(
+        result.sql_statements,
+        vec!["SELECT id, name, content FROM files ORDER BY id"]
+    );
+}
+
+#[test]
+fn test_export_sqlite_ndjson_with_hex_blobs() {
+    let mut conn = setup_db();
+    let queries = export_definitions();
+    let options = ExportOptions {
+        blob_encoding: BlobEncoding::Hex,
+        ..Default::default()
+    };
+
+    let mut out = Vec::new();
+    query_export_sqlite(
+        &mut conn,
+        &queries,
+        "list_files",
+        &json!({}),
+        &mut out,
+        &options,
+    )
+    .unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "{\"name\":\"report, final\",\"id\":1,\"content\":\"48690a\"}\n{\"name\":null,\"id\":2,\"content\":\"ff\"}\n"
+    );
+}
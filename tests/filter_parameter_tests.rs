@@ -0,0 +1,224 @@
+use jankensqlhub::{
+    JankenError, M_EXPECTED, M_GOT, M_PARAM_NAME, QueryDefinitions, error_meta, query_run_sqlite,
+};
+use rusqlite::Connection;
+
+fn definitions() -> QueryDefinitions {
+    QueryDefinitions::from_json(serde_json::json!({
+        "search_orders": {
+            "query": "SELECT id FROM orders WHERE tenant_id = @tenant AND ?[filter] ORDER BY id",
+            "returns": ["id"],
+            "args": {
+                "tenant": { "type": "integer" },
+                "filter": {
+                    "range": [0, 5],
+                    "columns": {
+                        "status": { "ops": ["eq", "ne", "in"], "enum": ["open", "paid", "void"] },
+                        "total": { "type": "float", "ops": ["lt", "gt"] },
+                        "note": { "ops": ["like", "is_null"], "sensitive": true }
+                    }
+                }
+            }
+        }
+    }))
+    .unwrap()
+}
+
+fn setup_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE orders (id INTEGER PRIMARY KEY, tenant_id INTEGER, status TEXT, total REAL, note TEXT);
+         INSERT INTO orders VALUES
+           (1, 1, 'open', 10.0, 'rush'),
+           (2, 1, 'paid', 99.5, NULL),
+           (3, 1, 'void', 5.0, 'rush order'),
+           (4, 2, 'open', 50.0, NULL);",
+    )
+    .unwrap();
+    conn
+}
+
+fn ids(data: &[serde_json::Value]) -> Vec<i64> {
+    data.iter().map(|row| row["id"].as_i64().unwrap()).collect()
+}
+
+fn type_mismatch(err: anyhow::Error) -> (String, String) {
+    match err.downcast::<JankenError>().unwrap() {
+        JankenError::ParameterTypeMismatch { data } => (
+            error_meta(&data, M_EXPECTED).unwrap(),
+            error_meta(&data, M_GOT).unwrap(),
+        ),
+        other => panic!("Expected ParameterTypeMismatch, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_filter_compiles_to_parameterized_where_clause() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let params = serde_json::json!({
+        "tenant": 1,
+        "filter": {"or": [
+            {"and": [
+                {"column": "status", "op": "in", "value": ["open", "paid"]},
+                {"column": "total", "op": "gt", "value": 20}
+            ]},
+            {"not": {"column": "note", "op": "is_null"}}
+        ]}
+    });
+    let result = query_run_sqlite(&mut conn, &queries, "search_orders", &params).unwrap();
+    assert_eq!(
+        result.sql_statements,
+        vec![
+            "SELECT id FROM orders WHERE tenant_id = :tenant AND (((status IN (:filter_0, :filter_1) AND total > :filter_2) OR NOT (note IS NULL))) ORDER BY id"
+        ]
+    );
+    assert_eq!(ids(&result.data), vec![1, 2, 3]);
+
+    // Sensitive column values are redacted in the rendered SQL
+    let params = serde_json::json!({
+        "tenant": 1,
+        "filter": {"and": [
+            {"column": "note", "op": "like", "value": "rush%"},
+            {"column": "status", "op": "ne", "value": "void"}
+        ]}
+    });
+    let result = query_run_sqlite(&mut conn, &queries, "search_orders", &params).unwrap();
+    assert_eq!(ids(&result.data), vec![1]);
+    assert_eq!(
+        result.statements[0].render_sql(),
+        "SELECT id FROM orders WHERE tenant_id = 1 AND ((note LIKE '[REDACTED]' AND status <> 'void')) ORDER BY id"
+    );
+
+    // An empty and-group matches every row
+    let params = serde_json::json!({"tenant": 2, "filter": {"and": []}});
+    let result = query_run_sqlite(&mut conn, &queries, "search_orders", &params).unwrap();
+    assert_eq!(ids(&result.data), vec![4]);
+}
+
+#[test]
+fn test_filter_validation_errors() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let run = |conn: &mut Connection, filter: serde_json::Value| {
+        query_run_sqlite(
+            conn,
+            &queries,
+            "search_orders",
+            &serde_json::json!({ "tenant": 1, "filter": filter }),
+        )
+        .unwrap_err()
+    };
+
+    let cases = [
+        (
+            serde_json::json!({"column": "id; DROP TABLE orders", "op": "eq", "value": 1}),
+            "one of [note, status, total] for column at filter",
+            "\"id; DROP TABLE orders\"",
+        ),
+        (
+            serde_json::json!({"and": [{"column": "total", "op": "eq", "value": 1}]}),
+            "one of [lt, gt] for total at filter.and[0]",
+            "\"eq\"",
+        ),
+        (
+            serde_json::json!({"or": [{"not": {"column": "total", "op": "lt", "value": "1"}}]}),
+            "float for total at filter.or[0].not.value",
+            "\"1\"",
+        ),
+        (
+            serde_json::json!({"column": "status", "op": "in", "value": ["open", "lost"]}),
+            "one of [\"open\", \"paid\", \"void\"] for status at filter.value[1]",
+            "\"lost\"",
+        ),
+        (
+            serde_json::json!({"column": "status", "op": "in", "value": []}),
+            "non-empty array for in at filter",
+            "[]",
+        ),
+        (
+            serde_json::json!({"and": {"column": "status"}}),
+            "array of filter expressions at filter.and",
+            "{\"column\":\"status\"}",
+        ),
+        (
+            serde_json::json!({"xor": []}),
+            "filter expression (and, or, not or a column condition) at filter",
+            "{\"xor\":[]}",
+        ),
+        (
+            serde_json::json!({"column": "note", "op": "is_null", "value": true}),
+            "no value for is_null at filter",
+            "true",
+        ),
+        (
+            serde_json::json!({"and": [
+                {"column": "total", "op": "gt", "value": 1},
+                {"column": "total", "op": "gt", "value": 2},
+                {"column": "total", "op": "gt", "value": 3},
+                {"column": "total", "op": "gt", "value": 4},
+                {"column": "total", "op": "gt", "value": 5},
+                {"column": "total", "op": "gt", "value": 6}
+            ]}),
+            "filter size between 0 and 5 conditions",
+            "6 conditions",
+        ),
+    ];
+    for (filter, expected, got) in cases {
+        assert_eq!(
+            type_mismatch(run(&mut conn, filter)),
+            (expected.to_string(), got.to_string())
+        );
+    }
+
+    let err = run(
+        &mut conn,
+        serde_json::json!({"column": "status", "op": "eq"}),
+    );
+    match err.downcast::<JankenError>().unwrap() {
+        JankenError::ParameterNotProvided { data } => {
+            assert_eq!(error_meta(&data, M_PARAM_NAME).unwrap(), "filter.value");
+        }
+        other => panic!("Expected ParameterNotProvided, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_filter_definition_errors() {
+    let cases = [
+        (
+            serde_json::json!({}),
+            "'columns' definition in args for filter parameter",
+        ),
+        (
+            serde_json::json!({"filter": {"columns": {}}}),
+            "non-empty object of column definitions for columns",
+        ),
+        (
+            serde_json::json!({"filter": {"columns": {"a b": {"ops": ["eq"]}}}}),
+            "column name with alphanumeric characters, underscores and dots",
+        ),
+        (
+            serde_json::json!({"filter": {"columns": {"a": {}}}}),
+            "non-empty array of operators for column a",
+        ),
+        (
+            serde_json::json!({"filter": {"columns": {"a": {"ops": ["between"]}}}}),
+            "filter operator eq, ne, lt, gt, in, like or is_null",
+        ),
+        (
+            serde_json::json!({"filter": {"columns": {"a": {"type": "integer", "ops": ["like"]}}}}),
+            "string column for like operator",
+        ),
+    ];
+    for (args, expected) in cases {
+        let err = QueryDefinitions::from_json(serde_json::json!({"q": {
+            "query": "SELECT * FROM orders WHERE ?[filter]",
+            "args": args
+        }}))
+        .unwrap_err();
+        assert_eq!(type_mismatch(err).0, expected);
+    }
+}
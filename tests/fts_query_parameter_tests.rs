@@ -0,0 +1,160 @@
+use jankensqlhub::{
+    Dialect, FtsOperator, FtsOptions, FtsQuery, JankenError, M_EXPECTED, M_GOT, QueryDefinitions,
+    error_meta, query_run_sqlite,
+};
+use rusqlite::Connection;
+
+fn definitions() -> QueryDefinitions {
+    QueryDefinitions::from_json(serde_json::json!({
+        "search_docs": {
+            "query": "SELECT rowid AS id FROM docs WHERE docs MATCH @q ORDER BY rowid",
+            "returns": ["id"],
+            "args": { "q": { "type": "fts_query", "range": [1, 3], "term_length": [2, 20] } }
+        },
+        "search_docs_any": {
+            "query": "SELECT rowid AS id FROM docs WHERE docs MATCH @q ORDER BY rowid",
+            "returns": ["id"],
+            "args": { "q": { "type": "fts_query", "prefix": true, "operator": "or" } }
+        }
+    }))
+    .unwrap()
+}
+
+fn setup_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE docs USING fts5(body);
+         INSERT INTO docs (rowid, body) VALUES
+           (1, 'rust database drivers'),
+           (2, 'near or far, rust never sleeps'),
+           (3, 'sqlite full text search'),
+           (4, 'postgresql databases');",
+    )
+    .unwrap();
+    conn
+}
+
+fn ids(data: &[serde_json::Value]) -> Vec<i64> {
+    data.iter().map(|row| row["id"].as_i64().unwrap()).collect()
+}
+
+fn type_mismatch(err: anyhow::Error) -> (String, String) {
+    match err.downcast::<JankenError>().unwrap() {
+        JankenError::ParameterTypeMismatch { data } => (
+            error_meta(&data, M_EXPECTED).unwrap(),
+            error_meta(&data, M_GOT).unwrap(),
+        ),
+        other => panic!("Expected ParameterTypeMismatch, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_fts_query_renders_per_dialect() {
+    let options = FtsOptions {
+        prefix: true,
+        operator: FtsOperator::Or,
+        term_length: None,
+    };
+    let query = FtsQuery::from_text("data* \"OR\" it's", &options);
+    assert_eq!(query.terms, vec!["data", "OR", "it", "s"]);
+    assert_eq!(
+        query.to_query_string(Dialect::Sqlite),
+        "\"data\"* OR \"OR\"* OR \"it\"* OR \"s\"*"
+    );
+    assert_eq!(
+        query.to_query_string(Dialect::Postgresql),
+        "'data':* | 'OR':* | 'it':* | 's':*"
+    );
+
+    let query = FtsQuery::from_text("rust-lang", &FtsOptions::default());
+    assert_eq!(
+        query.to_query_string(Dialect::Sqlite),
+        "\"rust\" AND \"lang\""
+    );
+    assert_eq!(
+        query.to_query_string(Dialect::Postgresql),
+        "'rust' & 'lang'"
+    );
+}
+
+#[test]
+fn test_fts_query_sanitizes_free_text() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    // Operators and unbalanced quotes in raw input would be FTS5 syntax errors
+    let params = serde_json::json!({"q": "rust \"NEAR"});
+    let result = query_run_sqlite(&mut conn, &queries, "search_docs", &params).unwrap();
+    assert_eq!(ids(&result.data), vec![2]);
+    assert_eq!(
+        result.statements[0].parameters[0].value,
+        jankensqlhub::ParameterValue::String("\"rust\" AND \"NEAR\"".to_string())
+    );
+
+    let params = serde_json::json!({"q": "data sqlite"});
+    let result = query_run_sqlite(&mut conn, &queries, "search_docs_any", &params).unwrap();
+    assert_eq!(ids(&result.data), vec![1, 3, 4]);
+}
+
+#[test]
+fn test_fts_query_validation_errors() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let params = serde_json::json!({"q": "-- !!"});
+    let err = query_run_sqlite(&mut conn, &queries, "search_docs", &params).unwrap_err();
+    assert_eq!(
+        type_mismatch(err),
+        (
+            "fts_query with at least one search term".to_string(),
+            "\"-- !!\"".to_string()
+        )
+    );
+
+    let params = serde_json::json!({"q": "one two three four"});
+    let err = query_run_sqlite(&mut conn, &queries, "search_docs", &params).unwrap_err();
+    assert_eq!(
+        type_mismatch(err),
+        (
+            "fts_query size between 1 and 3 terms".to_string(),
+            "4 terms".to_string()
+        )
+    );
+
+    let params = serde_json::json!({"q": "rust a"});
+    let err = query_run_sqlite(&mut conn, &queries, "search_docs", &params).unwrap_err();
+    assert_eq!(
+        type_mismatch(err),
+        (
+            "fts_query term length between 2 and 20 characters".to_string(),
+            "1 characters at term 1".to_string()
+        )
+    );
+
+    let err = QueryDefinitions::from_json(serde_json::json!({
+        "bad_operator": {
+            "query": "SELECT rowid FROM docs WHERE docs MATCH @q",
+            "args": { "q": { "type": "fts_query", "operator": "near" } }
+        }
+    }))
+    .unwrap_err();
+    assert_eq!(
+        type_mismatch(err),
+        ("fts operator 'and' or 'or'".to_string(), "near".to_string())
+    );
+
+    let err = QueryDefinitions::from_json(serde_json::json!({
+        "prefix_on_string": {
+            "query": "SELECT rowid FROM docs WHERE docs MATCH @q",
+            "args": { "q": { "type": "string", "prefix": true } }
+        }
+    }))
+    .unwrap_err();
+    assert_eq!(
+        type_mismatch(err),
+        (
+            "type fts_query for parameter with 'prefix', 'operator' or 'term_length'".to_string(),
+            "q".to_string()
+        )
+    );
+}
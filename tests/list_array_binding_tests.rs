@@ -0,0 +1,67 @@
+use jankensqlhub::{
+    JankenError, M_EXPECTED, M_GOT, QueryDefinitions, error_meta, query_run_sqlite,
+};
+use rusqlite::Connection;
+
+#[test]
+fn test_pg_array_list_still_expands_in_sqlite() {
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "find_items": {
+            "query": "SELECT id FROM items WHERE id IN :[ids] ORDER BY id",
+            "returns": ["id"],
+            "args": { "ids": { "itemtype": "integer", "pg_array": true } }
+        }
+    }))
+    .unwrap();
+    let mut conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch("CREATE TABLE items (id INTEGER); INSERT INTO items VALUES (1), (2), (3);")
+        .unwrap();
+
+    let params = serde_json::json!({"ids": [1, 3]});
+    let result = query_run_sqlite(&mut conn, &queries, "find_items", &params).unwrap();
+    assert_eq!(
+        result.sql_statements,
+        vec!["SELECT id FROM items WHERE id IN (:ids_0, :ids_1) ORDER BY id"]
+    );
+    assert_eq!(
+        result.data,
+        vec![serde_json::json!({"id": 1}), serde_json::json!({"id": 3})]
+    );
+}
+
+#[test]
+fn test_pg_array_definition_errors() {
+    let cases = [
+        (
+            serde_json::json!({"q": {
+                "query": "SELECT * FROM items WHERE id IN :[ids]",
+                "args": {"ids": {"pg_array": true}}
+            }}),
+            (
+                "itemtype integer, string, float, boolean or blob for pg_array list parameter",
+                "ids",
+            ),
+        ),
+        (
+            serde_json::json!({"q": {
+                "query": "SELECT * FROM items WHERE id IN :[ids]",
+                "args": {"ids": {"itemtype": "integer", "pg_array": "yes"}}
+            }}),
+            ("boolean for pg_array", "\"yes\""),
+        ),
+    ];
+
+    for (definition, (expected, got)) in cases {
+        match QueryDefinitions::from_json(definition)
+            .unwrap_err()
+            .downcast::<JankenError>()
+            .unwrap()
+        {
+            JankenError::ParameterTypeMismatch { data } => {
+                assert_eq!(error_meta(&data, M_EXPECTED).unwrap(), expected);
+                assert_eq!(error_meta(&data, M_GOT).unwrap(), got);
+            }
+            other => panic!("Expected ParameterTypeMismatch, got {other:?}"),
+        }
+    }
+}
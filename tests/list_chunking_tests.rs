@@ -0,0 +1,115 @@
+use jankensqlhub::{
+    Dialect, JankenError, M_EXPECTED, M_GOT, QueryDefinitions, error_meta, query_run_sqlite,
+};
+use rusqlite::Connection;
+
+fn definitions() -> QueryDefinitions {
+    QueryDefinitions::from_json(serde_json::json!({
+        "find_items": {
+            "query": "SELECT id, name FROM items WHERE id IN :[ids] AND name <> @skip",
+            "returns": ["id", "name"],
+            "args": {
+                "ids": { "itemtype": "integer", "range": [1, 100000], "chunk_size": 100000 },
+                "skip": { "type": "string" }
+            }
+        },
+        "count_items": {
+            "query": "SELECT COUNT(*) AS total FROM items WHERE id IN :[ids]",
+            "returns": ["total"],
+            "args": { "ids": { "itemtype": "integer" } }
+        },
+        "find_items_small_chunks": {
+            "query": "SELECT id FROM items WHERE id IN :[ids]",
+            "returns": ["id"],
+            "args": { "ids": { "itemtype": "integer", "chunk_size": 2 } }
+        }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_read_list_chunks_concatenate_data() {
+    let queries = definitions();
+    let mut conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT);
+         INSERT INTO items VALUES (1, 'a'), (2, 'b'), (3, 'c'), (4, 'd'), (5, 'e');",
+    )
+    .unwrap();
+
+    let params = serde_json::json!({"ids": [5, 1, 3, 9, 4]});
+    let result = query_run_sqlite(&mut conn, &queries, "find_items_small_chunks", &params).unwrap();
+    assert_eq!(
+        result.sql_statements,
+        vec![
+            "SELECT id FROM items WHERE id IN (:ids_0, :ids_1)",
+            "SELECT id FROM items WHERE id IN (:ids_0, :ids_1)",
+            "SELECT id FROM items WHERE id IN (:ids_0)"
+        ]
+    );
+    assert_eq!(
+        result.data,
+        vec![
+            serde_json::json!({"id": 1}),
+            serde_json::json!({"id": 5}),
+            serde_json::json!({"id": 3}),
+            serde_json::json!({"id": 4})
+        ]
+    );
+}
+
+#[test]
+fn test_read_list_chunks_at_the_sqlite_parameter_limit() {
+    let queries = definitions();
+
+    // 50000 ids and one regular parameter: 32765 ids fit next to @skip in the first statement
+    let ids: Vec<i64> = (1..=50000).collect();
+    let params = serde_json::json!({"ids": ids, "skip": "x"});
+    let validated = queries
+        .validate("find_items", &params, Dialect::Sqlite)
+        .unwrap();
+    assert_eq!(validated.len(), 2);
+    assert_eq!(validated[0].parameters.len(), 32766);
+    assert_eq!(validated[1].parameters.len(), 50000 - 32765 + 1);
+
+    // The range is still the hard upper bound on the whole list
+    let ids: Vec<i64> = (1..=100001).collect();
+    let params = serde_json::json!({"ids": ids, "skip": "x"});
+    let err = queries
+        .validate("find_items", &params, Dialect::Sqlite)
+        .unwrap_err();
+    assert!(matches!(err, JankenError::ParameterTypeMismatch { .. }));
+}
+
+#[test]
+fn test_read_lists_without_chunk_size_are_never_split() {
+    let queries = definitions();
+
+    // Splitting would count each chunk separately, so the statement is left whole
+    let ids: Vec<i64> = (1..=50000).collect();
+    let params = serde_json::json!({"ids": ids});
+    let validated = queries
+        .validate("count_items", &params, Dialect::Sqlite)
+        .unwrap();
+    assert_eq!(validated.len(), 1);
+    assert_eq!(validated[0].parameters.len(), 50000);
+}
+
+#[test]
+fn test_chunk_size_definition_error() {
+    let err = QueryDefinitions::from_json(serde_json::json!({"q": {
+        "query": "SELECT * FROM items WHERE id IN :[ids]",
+        "args": {"ids": {"itemtype": "integer", "chunk_size": 0}}
+    }}))
+    .unwrap_err();
+    match err.downcast::<JankenError>().unwrap() {
+        JankenError::ParameterTypeMismatch { data } => {
+            assert_eq!(
+                error_meta(&data, M_EXPECTED).unwrap(),
+                "positive integer for chunk_size"
+            );
+            assert_eq!(error_meta(&data, M_GOT).unwrap(), "0");
+        }
+        other => panic!("Expected ParameterTypeMismatch, got {other:?}"),
+    }
+}
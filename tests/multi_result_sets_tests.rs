@@ -0,0 +1,184 @@
+use jankensqlhub::{
+    JankenError, M_EXPECTED, M_GOT, QueryDefinitions, error_meta, query_run_sqlite,
+    query_stream_sqlite,
+};
+use rusqlite::Connection;
+
+fn setup() -> (QueryDefinitions, Connection) {
+    let json_definitions = serde_json::json!({
+        "insert_and_list": {
+            "query": "INSERT INTO users (name) VALUES (@name); SELECT id, name FROM users ORDER BY id",
+            "returns": [[], ["id", "name"]]
+        },
+        "page_with_total": {
+            "query": "SELECT ~[fields] FROM users ORDER BY id LIMIT @limit; SELECT COUNT(*) AS total FROM users",
+            "returns": ["~[fields]", ["total"]],
+            "args": {
+                "limit": { "type": "integer" },
+                "fields": { "enum": ["id", "name"] }
+            }
+        }
+    });
+    let queries = QueryDefinitions::from_json(json_definitions).unwrap();
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+         INSERT INTO users (name) VALUES ('Alice'), ('Bob');",
+    )
+    .unwrap();
+    (queries, conn)
+}
+
+fn assert_type_mismatch(err: anyhow::Error, expected: &str, got: &str) {
+    match err.downcast::<JankenError>() {
+        Ok(JankenError::ParameterTypeMismatch { data }) => {
+            assert_eq!(error_meta(&data, M_EXPECTED).unwrap(), expected);
+            assert_eq!(error_meta(&data, M_GOT).unwrap(), got);
+        }
+        other => panic!("Expected ParameterTypeMismatch, got: {other:?}"),
+    }
+}
+
+#[test]
+fn test_mutation_then_select_result_sets() {
+    let (queries, mut conn) = setup();
+
+    let params = serde_json::json!({"name": "Carol"});
+    let result = query_run_sqlite(&mut conn, &queries, "insert_and_list", &params).unwrap();
+
+    assert_eq!(
+        result.sql_statements,
+        vec![
+            "INSERT INTO users (name) VALUES (:name)",
+            "SELECT id, name FROM users ORDER BY id"
+        ]
+    );
+    assert!(result.data.is_empty());
+    assert_eq!(
+        result.result_sets,
+        vec![
+            vec![],
+            vec![
+                serde_json::json!({"id": 1, "name": "Alice"}),
+                serde_json::json!({"id": 2, "name": "Bob"}),
+                serde_json::json!({"id": 3, "name": "Carol"}),
+            ]
+        ]
+    );
+}
+
+#[test]
+fn test_page_and_total_result_sets() {
+    let (queries, mut conn) = setup();
+
+    let params = serde_json::json!({"limit": 1, "fields": ["name"]});
+    let result = query_run_sqlite(&mut conn, &queries, "page_with_total", &params).unwrap();
+    assert_eq!(
+        result.result_sets,
+        vec![
+            vec![serde_json::json!({"name": "Alice"})],
+            vec![serde_json::json!({"total": 2})],
+        ]
+    );
+
+    // Per-statement returns cannot be streamed as a single row sequence
+    let err = query_stream_sqlite(&mut conn, &queries, "page_with_total", &params, |_| Ok(()))
+        .unwrap_err();
+    assert_type_mismatch(err, "single returns specification", "per-statement returns");
+}
+
+#[test]
+fn test_per_statement_returns_definition_errors() {
+    let err = QueryDefinitions::from_json(serde_json::json!({
+        "mismatch": {
+            "query": "SELECT 1 AS a; SELECT 2 AS b; SELECT 3 AS c",
+            "returns": [["a"], ["b"]]
+        }
+    }))
+    .unwrap_err();
+    assert_type_mismatch(
+        err,
+        "one returns entry per statement (3)",
+        "mismatch: 2 returns entries",
+    );
+
+    let err = QueryDefinitions::from_json(serde_json::json!({
+        "bad_entry": {
+            "query": "SELECT 1 AS a; SELECT 2 AS b",
+            "returns": [["a"], 2]
+        }
+    }))
+    .unwrap_err();
+    assert_type_mismatch(
+        err,
+        "array of strings or ~[param_name] format for each statement",
+        "bad_entry: 2",
+    );
+
+    let err = QueryDefinitions::from_json(serde_json::json!({
+        "bad_reference": {
+            "query": "SELECT 1 AS a; SELECT 2 AS b",
+            "returns": [["a"], "~[missing]"]
+        }
+    }))
+    .unwrap_err();
+    assert_type_mismatch(
+        err,
+        "returns reference ~[missing] to point to an existing comma_list parameter",
+        "parameter 'missing' not found or not a comma_list type",
+    );
+
+    // References only select a statement's fields as whole entries, never inside a field list
+    let err = QueryDefinitions::from_json(serde_json::json!({
+        "one_statement": {
+            "query": "SELECT ~[a] FROM users",
+            "returns": ["~[a]", "~[b]"],
+            "args": { "a": { "enum": ["id"] }, "b": { "enum": ["name"] } }
+        }
+    }))
+    .unwrap_err();
+    assert_type_mismatch(
+        err,
+        "one returns entry per statement (1)",
+        "one_statement: 2 returns entries",
+    );
+
+    let err = QueryDefinitions::from_json(serde_json::json!({
+        "nested_reference": {
+            "query": "SELECT 1 AS a; SELECT 2 AS b",
+            "returns": [["a"], ["~[b]"]]
+        }
+    }))
+    .unwrap_err();
+    assert_type_mismatch(
+        err,
+        "field names without ~[param_name] references in returns array",
+        "~[b]",
+    );
+}
+
+#[test]
+fn test_reference_returns_per_statement() {
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "two_selections": {
+            "query": "SELECT ~[a] FROM users ORDER BY id; SELECT ~[b] FROM users ORDER BY id",
+            "returns": ["~[a]", "~[b]"],
+            "args": { "a": { "enum": ["id", "name"] }, "b": { "enum": ["id", "name"] } }
+        }
+    }))
+    .unwrap();
+    let (_, mut conn) = setup();
+
+    let params = serde_json::json!({"a": ["id"], "b": ["name"]});
+    let result = query_run_sqlite(&mut conn, &queries, "two_selections", &params).unwrap();
+    assert_eq!(
+        result.result_sets,
+        vec![
+            vec![serde_json::json!({"id": 1}), serde_json::json!({"id": 2})],
+            vec![
+                serde_json::json!({"name": "Alice"}),
+                serde_json::json!({"name": "Bob"})
+            ],
+        ]
+    );
+}
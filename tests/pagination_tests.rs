@@ -0,0 +1,319 @@
+use jankensqlhub::{
+    Dialect, JankenError, M_EXPECTED, M_GOT, QueryDefinitions, error_meta, query_run_sqlite,
+};
+use rusqlite::Connection;
+
+fn definitions() -> QueryDefinitions {
+    QueryDefinitions::from_json(serde_json::json!({
+        "list_posts": {
+            "query": "SELECT id, author, score FROM posts WHERE author <> @excluded ORDER BY id",
+            "returns": ["id", "author", "score"],
+            "args": { "excluded": { "type": "string" } },
+            "paginate": { "max_page_size": 3, "default_page_size": 2, "count": true }
+        },
+        "feed": {
+            "query": "SELECT id, author, score FROM posts",
+            "returns": ["id", "score"],
+            "paginate": {
+                "mode": "keyset",
+                "keys": [{"column": "score", "dir": "desc"}, {"column": "id"}],
+                "max_page_size": 10
+            }
+        }
+    }))
+    .unwrap()
+}
+
+fn setup_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE posts (id INTEGER PRIMARY KEY, author TEXT, score INTEGER);
+         INSERT INTO posts VALUES
+           (1, 'amy', 5), (2, 'bob', 9), (3, 'amy', 5), (4, 'cat', 7), (5, 'dan', 9), (6, 'bob', 1);",
+    )
+    .unwrap();
+    conn
+}
+
+fn ids(data: &[serde_json::Value]) -> Vec<i64> {
+    data.iter().map(|row| row["id"].as_i64().unwrap()).collect()
+}
+
+fn type_mismatch(err: anyhow::Error) -> (String, String) {
+    match err.downcast::<JankenError>().unwrap() {
+        JankenError::ParameterTypeMismatch { data } => (
+            error_meta(&data, M_EXPECTED).unwrap(),
+            error_meta(&data, M_GOT).unwrap(),
+        ),
+        other => panic!("Expected ParameterTypeMismatch, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_offset_pagination_with_total() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let params = serde_json::json!({"excluded": "cat", "page": {"limit": 3, "offset": 3}});
+    let result = query_run_sqlite(&mut conn, &queries, "list_posts", &params).unwrap();
+    assert_eq!(ids(&result.data), vec![5, 6]);
+    assert_eq!(result.total, Some(5));
+    assert_eq!(result.next_cursor, None);
+    assert_eq!(
+        result.sql_statements,
+        vec![
+            "SELECT * FROM (SELECT id, author, score FROM posts WHERE author <> :excluded ORDER BY id) AS janken_page LIMIT CAST(:janken_page_limit AS INTEGER) OFFSET CAST(:janken_page_offset AS INTEGER)",
+            "SELECT COUNT(*) AS total FROM (SELECT id, author, score FROM posts WHERE author <> :excluded ORDER BY id) AS janken_count",
+        ]
+    );
+
+    // Without a page, the default page size applies from the first row
+    let params = serde_json::json!({"excluded": "cat"});
+    let result = query_run_sqlite(&mut conn, &queries, "list_posts", &params).unwrap();
+    assert_eq!(ids(&result.data), vec![1, 2]);
+
+    // validate reports the same statements without running them
+    let statements = queries
+        .validate("list_posts", &params, Dialect::Postgresql)
+        .unwrap();
+    assert_eq!(
+        statements[0].sql,
+        "SELECT * FROM (SELECT id, author, score FROM posts WHERE author <> $1 ORDER BY id) AS janken_page LIMIT CAST($2 AS INTEGER) OFFSET CAST($3 AS INTEGER)"
+    );
+    assert_eq!(statements.len(), 2);
+}
+
+#[test]
+fn test_offset_pagination_wraps_the_query() {
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "top_posts": {
+            "query": "SELECT id FROM posts ORDER BY score DESC, id LIMIT 4;",
+            "returns": ["id"],
+            "paginate": { "max_page_size": 10, "count": true }
+        },
+        "commented_posts": {
+            "query": "SELECT id FROM posts ORDER BY id -- newest last\n-- keep in sync with feed",
+            "returns": ["id"],
+            "paginate": { "max_page_size": 10, "count": true }
+        }
+    }))
+    .unwrap();
+    let mut conn = setup_db();
+
+    // The query's own LIMIT bounds the rows the page is taken from
+    let params = serde_json::json!({"page": {"limit": 3, "offset": 2}});
+    let result = query_run_sqlite(&mut conn, &queries, "top_posts", &params).unwrap();
+    assert_eq!(ids(&result.data), vec![4, 1]);
+    assert_eq!(result.total, Some(4));
+
+    // Trailing comments would otherwise comment out the page's LIMIT
+    let params = serde_json::json!({"page": {"limit": 2, "offset": 1}});
+    let result = query_run_sqlite(&mut conn, &queries, "commented_posts", &params).unwrap();
+    assert_eq!(ids(&result.data), vec![2, 3]);
+    assert_eq!(result.total, Some(6));
+    assert_eq!(
+        result.sql_statements[0],
+        "SELECT * FROM (SELECT id FROM posts ORDER BY id) AS janken_page LIMIT CAST(:janken_page_limit AS INTEGER) OFFSET CAST(:janken_page_offset AS INTEGER)"
+    );
+}
+
+#[test]
+fn test_pagination_ignores_chunk_size() {
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "posts_by_id": {
+            "query": "SELECT id FROM posts WHERE id IN :[ids] ORDER BY id",
+            "returns": ["id"],
+            "args": { "ids": { "itemtype": "integer", "chunk_size": 2 } },
+            "paginate": { "max_page_size": 10, "count": true }
+        }
+    }))
+    .unwrap();
+    let mut conn = setup_db();
+
+    // The page and the count each run as one statement over the whole list
+    let params = serde_json::json!({"ids": [1, 2, 3, 4, 5], "page": {"limit": 3}});
+    let result = query_run_sqlite(&mut conn, &queries, "posts_by_id", &params).unwrap();
+    assert_eq!(ids(&result.data), vec![1, 2, 3]);
+    assert_eq!(result.total, Some(5));
+    assert_eq!(result.sql_statements.len(), 2);
+
+    let validated = queries
+        .validate("posts_by_id", &params, Dialect::Sqlite)
+        .unwrap();
+    assert_eq!(validated.len(), 2);
+    assert!(
+        validated[1]
+            .sql
+            .starts_with("SELECT COUNT(*) AS total FROM")
+    );
+}
+
+#[test]
+fn test_keyset_pagination_follows_cursors() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let mut pages = Vec::new();
+    let mut page = serde_json::json!({"limit": 2});
+    loop {
+        let params = serde_json::json!({ "page": page });
+        let result = query_run_sqlite(&mut conn, &queries, "feed", &params).unwrap();
+        assert_eq!(result.total, None);
+        pages.push(ids(&result.data));
+        match result.next_cursor {
+            Some(cursor) => page = serde_json::json!({"limit": 2, "cursor": cursor}),
+            None => break,
+        }
+    }
+    assert_eq!(pages, vec![vec![2, 5], vec![4, 1], vec![3, 6]]);
+
+    let cursor_page = serde_json::json!({"page": {"limit": 2, "cursor": "WzksNV0"}});
+    let result = query_run_sqlite(&mut conn, &queries, "feed", &cursor_page).unwrap();
+    assert_eq!(
+        result.sql_statements,
+        vec![
+            "SELECT * FROM (SELECT id, author, score FROM posts) AS janken_page WHERE ((score < :janken_page_after_0) OR (score = :janken_page_after_0 AND id > :janken_page_after_1)) ORDER BY score DESC, id ASC LIMIT CAST(:janken_page_limit AS INTEGER)"
+        ]
+    );
+    assert_eq!(ids(&result.data), vec![4, 1]);
+}
+
+#[test]
+fn test_keyset_pagination_rejects_null_keys() {
+    let queries = definitions();
+    let mut conn = setup_db();
+    conn.execute("UPDATE posts SET score = NULL WHERE id IN (3, 6)", [])
+        .unwrap();
+
+    // NULL sorts last in descending SQLite order: the first page ends on post 3's NULL score
+    let params = serde_json::json!({"page": {"limit": 5}});
+    let err = query_run_sqlite(&mut conn, &queries, "feed", &params).unwrap_err();
+    assert_eq!(
+        type_mismatch(err),
+        (
+            "non-null keyset column value for the next page cursor".to_string(),
+            "null in score".to_string()
+        )
+    );
+}
+
+#[test]
+fn test_pagination_request_errors() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let cases = [
+        (
+            "list_posts",
+            serde_json::json!({"excluded": "cat", "page": {"limit": 4}}),
+            "page limit between 1 and 3",
+            "4",
+        ),
+        (
+            "list_posts",
+            serde_json::json!({"excluded": "cat", "page": {"offset": -1}}),
+            "non-negative integer for page offset",
+            "-1",
+        ),
+        (
+            "list_posts",
+            serde_json::json!({"excluded": "cat", "page": {"cursor": "WzFd"}}),
+            "page with limit and offset",
+            "cursor",
+        ),
+        (
+            "feed",
+            serde_json::json!({"page": {"offset": 2}}),
+            "page with limit and cursor",
+            "offset",
+        ),
+        (
+            "feed",
+            serde_json::json!({"page": {"cursor": "not a cursor"}}),
+            "page cursor from a previous page",
+            "\"not a cursor\"",
+        ),
+        (
+            // A cursor for a single key
+            "feed",
+            serde_json::json!({"page": {"cursor": "WzFd"}}),
+            "page cursor from a previous page",
+            "\"WzFd\"",
+        ),
+        (
+            "feed",
+            serde_json::json!({"page": 2}),
+            "object for page",
+            "2",
+        ),
+    ];
+    for (query_name, params, expected, got) in cases {
+        let err = query_run_sqlite(&mut conn, &queries, query_name, &params).unwrap_err();
+        assert_eq!(type_mismatch(err), (expected.to_string(), got.to_string()));
+    }
+}
+
+#[test]
+fn test_pagination_definition_errors() {
+    let cases = [
+        (
+            serde_json::json!({"query": "SELECT id FROM posts", "returns": ["id"], "paginate": {}}),
+            "positive integer for paginate max_page_size",
+        ),
+        (
+            serde_json::json!({"query": "SELECT id FROM posts", "returns": ["id"],
+                "paginate": {"max_page_size": 5, "default_page_size": 10}}),
+            "paginate default_page_size within max_page_size",
+        ),
+        (
+            serde_json::json!({"query": "SELECT id FROM posts", "returns": ["id"],
+                "paginate": {"mode": "pages", "max_page_size": 5}}),
+            "paginate mode offset or keyset",
+        ),
+        (
+            serde_json::json!({"query": "SELECT id FROM posts", "returns": ["id"],
+                "paginate": {"mode": "keyset", "max_page_size": 5, "keys": [{"column": "id; --"}]}}),
+            "non-empty array of {\"column\", \"dir\"} keys for keyset pagination",
+        ),
+        (
+            serde_json::json!({"query": "SELECT id FROM posts", "returns": ["id"],
+                "paginate": {"max_page_size": 5, "keys": [{"column": "id"}]}}),
+            "paginate keys only with keyset mode",
+        ),
+        (
+            serde_json::json!({"query": "DELETE FROM posts", "paginate": {"max_page_size": 5}}),
+            "single statement with returns for paginated query",
+        ),
+        (
+            serde_json::json!({"query": "SELECT id FROM posts WHERE id > @page", "returns": ["id"],
+                "args": {"page": {"type": "integer"}}, "paginate": {"max_page_size": 5}}),
+            "no 'page' parameter in paginated query",
+        ),
+        (
+            serde_json::json!({"query": "SELECT id FROM (VALUES *[rows]) AS v(id)", "returns": ["id"],
+                "args": {"rows": {"fields": [{"name": "id", "type": "integer"}]}},
+                "paginate": {"max_page_size": 5}}),
+            "no rows parameter in paginated query",
+        ),
+    ];
+    for (definition, expected) in cases {
+        let err = QueryDefinitions::from_json(serde_json::json!({ "q": definition })).unwrap_err();
+        assert_eq!(type_mismatch(err).0, expected);
+    }
+
+    // Keyset columns must be returned so the next cursor can be built from the last row
+    let queries = QueryDefinitions::from_json(serde_json::json!({"q": {
+        "query": "SELECT id, score FROM posts",
+        "returns": ["id"],
+        "paginate": {"mode": "keyset", "keys": [{"column": "score"}], "max_page_size": 5}
+    }}))
+    .unwrap();
+    let err = query_run_sqlite(&mut setup_db(), &queries, "q", &serde_json::json!({})).unwrap_err();
+    assert_eq!(
+        type_mismatch(err),
+        (
+            "returns including every keyset column".to_string(),
+            "score".to_string()
+        )
+    );
+}
@@ -0,0 +1,88 @@
+//! Pagination PostgreSQL integration tests for JankenSQLHub
+//!
+//! Tests limit/offset and keyset pages with the derived count query.
+
+use jankensqlhub::{QueryDefinitions, query_run_postgresql};
+use tokio_postgres::NoTls;
+
+// Helper function to get PostgreSQL connection string from environment
+fn get_postgres_connection_string() -> Option<String> {
+    std::env::var("POSTGRES_CONNECTION_STRING").ok()
+}
+
+// Helper function to establish PostgreSQL connection for tests
+async fn setup_postgres_connection() -> Option<tokio_postgres::Client> {
+    let connection_string = get_postgres_connection_string()?;
+    let (client, connection) = tokio_postgres::connect(&connection_string, NoTls)
+        .await
+        .ok()?;
+
+    // Run the connection in the background
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("connection error: {e}");
+        }
+    });
+
+    Some(client)
+}
+
+fn ids(data: &[serde_json::Value]) -> Vec<i64> {
+    data.iter().map(|row| row["id"].as_i64().unwrap()).collect()
+}
+
+#[tokio::test]
+async fn test_postgres_pagination() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    let values =
+        "(VALUES (1, 'a', 5), (2, 'b', 9), (3, 'c', 5), (4, 'd', 7)) AS v(id, name, score)";
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "list_items": {
+            "query": format!("SELECT id, name FROM {values} WHERE score > @min_score ORDER BY id"),
+            "returns": ["id", "name"],
+            "args": { "min_score": { "type": "integer" } },
+            "paginate": { "max_page_size": 10, "count": true }
+        },
+        "ranked_items": {
+            "query": format!("SELECT id, score FROM {values}"),
+            "returns": ["id", "score"],
+            "paginate": {
+                "mode": "keyset",
+                "keys": [{"column": "score", "dir": "desc"}, {"column": "id"}],
+                "max_page_size": 10
+            }
+        }
+    }))
+    .unwrap();
+
+    let params = serde_json::json!({"min_score": 0, "page": {"limit": 2, "offset": 1}});
+    let result = query_run_postgresql(&mut client, &queries, "list_items", &params)
+        .await
+        .unwrap();
+    assert_eq!(ids(&result.data), vec![2, 3]);
+    assert_eq!(result.total, Some(4));
+    assert_eq!(
+        result.sql_statements[0],
+        format!(
+            "SELECT * FROM (SELECT id, name FROM {values} WHERE score > $1 ORDER BY id) AS janken_page LIMIT CAST($2 AS INTEGER) OFFSET CAST($3 AS INTEGER)"
+        )
+    );
+
+    let params = serde_json::json!({"page": {"limit": 3}});
+    let result = query_run_postgresql(&mut client, &queries, "ranked_items", &params)
+        .await
+        .unwrap();
+    assert_eq!(ids(&result.data), vec![2, 4, 1]);
+    let cursor = result.next_cursor.unwrap();
+
+    let params = serde_json::json!({"page": {"limit": 3, "cursor": cursor}});
+    let result = query_run_postgresql(&mut client, &queries, "ranked_items", &params)
+        .await
+        .unwrap();
+    assert_eq!(ids(&result.data), vec![3]);
+    assert_eq!(result.next_cursor, None);
+}
@@ -0,0 +1,107 @@
+//! Statement cache PostgreSQL integration tests for JankenSQLHub
+//!
+//! Tests reuse of server-side prepared statements across query runs.
+
+use jankensqlhub::{
+    PostgresStatementCache, QueryDefinitions, StatementCacheStats, query_run_postgresql_cached,
+};
+use tokio_postgres::NoTls;
+
+// Helper function to get PostgreSQL connection string from environment
+fn get_postgres_connection_string() -> Option<String> {
+    std::env::var("POSTGRES_CONNECTION_STRING").ok()
+}
+
+// Helper function to establish PostgreSQL connection for tests
+async fn setup_postgres_connection() -> Option<tokio_postgres::Client> {
+    let connection_string = get_postgres_connection_string()?;
+    let (client, connection) = tokio_postgres::connect(&connection_string, NoTls)
+        .await
+        .ok()?;
+
+    // Run the connection in the background
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("connection error: {e}");
+        }
+    });
+
+    Some(client)
+}
+
+async fn prepared_statement_count(client: &tokio_postgres::Client) -> i64 {
+    client
+        .query_one("SELECT COUNT(*) FROM pg_prepared_statements", &[])
+        .await
+        .unwrap()
+        .get(0)
+}
+
+#[tokio::test]
+async fn test_postgres_statement_cache_reuses_prepared_statements() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "double": {
+            "query": "SELECT @value::int * 2 AS doubled",
+            "returns": ["doubled"],
+            "args": { "value": { "type": "integer" } }
+        },
+        "pick": {
+            "query": "SELECT v FROM (VALUES (1), (2), (3)) AS t(v) WHERE v IN :[ids] ORDER BY v",
+            "returns": ["v"],
+            "args": { "ids": { "itemtype": "integer" } }
+        }
+    }))
+    .unwrap();
+    let cache = PostgresStatementCache::new(2);
+    let baseline = prepared_statement_count(&client).await;
+
+    for value in [1, 2, 3] {
+        let params = serde_json::json!({ "value": value });
+        let result = query_run_postgresql_cached(&mut client, &cache, &queries, "double", &params)
+            .await
+            .unwrap();
+        assert_eq!(
+            result.data,
+            vec![serde_json::json!({ "doubled": value * 2 })]
+        );
+    }
+    assert_eq!(
+        cache.stats(),
+        StatementCacheStats {
+            hits: 2,
+            misses: 1,
+            len: 1,
+            capacity: 2
+        }
+    );
+    assert_eq!(prepared_statement_count(&client).await, baseline + 1);
+
+    // Each list length renders different SQL; the least recently used statement is evicted
+    let params = serde_json::json!({ "ids": [1, 3] });
+    query_run_postgresql_cached(&mut client, &cache, &queries, "pick", &params)
+        .await
+        .unwrap();
+    let params = serde_json::json!({ "ids": [2] });
+    let result = query_run_postgresql_cached(&mut client, &cache, &queries, "pick", &params)
+        .await
+        .unwrap();
+    assert_eq!(result.data, vec![serde_json::json!({ "v": 2 })]);
+    assert_eq!(
+        cache.stats(),
+        StatementCacheStats {
+            hits: 2,
+            misses: 3,
+            len: 2,
+            capacity: 2
+        }
+    );
+    assert_eq!(prepared_statement_count(&client).await, baseline + 2);
+
+    cache.clear();
+    assert_eq!(prepared_statement_count(&client).await, baseline);
+}
@@ -0,0 +1,110 @@
+//! Streaming PostgreSQL integration tests for JankenSQLHub
+//!
+//! Tests row-by-row delivery through `query_raw` backed streams.
+
+use jankensqlhub::{
+    BlobEncoding, ExportFormat, ExportOptions, QueryDefinitions, query_export_postgresql,
+    query_stream_postgresql_with_transaction,
+};
+use tokio_postgres::NoTls;
+
+// Helper function to get PostgreSQL connection string from environment
+fn get_postgres_connection_string() -> Option<String> {
+    std::env::var("POSTGRES_CONNECTION_STRING").ok()
+}
+
+// Helper function to establish PostgreSQL connection for tests
+async fn setup_postgres_connection() -> Option<tokio_postgres::Client> {
+    let connection_string = get_postgres_connection_string()?;
+    let (client, connection) = tokio_postgres::connect(&connection_string, NoTls)
+        .await
+        .ok()?;
+
+    // Run the connection in the background
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("connection error: {e}");
+        }
+    });
+
+    Some(client)
+}
+
+#[tokio::test]
+async fn test_postgres_stream_rows() {
+    use futures_util::StreamExt;
+
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "series": {
+            "query": "SELECT n::INT4 AS n, 'row ' || n AS label FROM generate_series(1, @total) AS n",
+            "returns": ["n", "label"],
+            "args": {"total": {"type": "integer"}}
+        }
+    }))
+    .unwrap();
+
+    let mut transaction = client.transaction().await.unwrap();
+    let stream = query_stream_postgresql_with_transaction(
+        &mut transaction,
+        &queries,
+        "series",
+        &serde_json::json!({"total": 3}),
+    )
+    .await
+    .unwrap();
+
+    let rows: Vec<serde_json::Value> = stream.map(|row| row.unwrap()).collect().await;
+    transaction.commit().await.unwrap();
+
+    assert_eq!(
+        rows,
+        vec![
+            serde_json::json!({"n": 1, "label": "row 1"}),
+            serde_json::json!({"n": 2, "label": "row 2"}),
+            serde_json::json!({"n": 3, "label": "row 3"}),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_postgres_export_csv() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "export_rows": {
+            "query": "SELECT 1::INT4 AS id, 'a \"quoted\" label' AS label, NULL::TEXT AS note, '\\x48690a'::BYTEA AS content",
+            "returns": ["id", "label", "note", "content"]
+        }
+    }))
+    .unwrap();
+    let options = ExportOptions {
+        format: ExportFormat::Csv,
+        blob_encoding: BlobEncoding::Base64,
+        ..Default::default()
+    };
+
+    let mut out = Vec::new();
+    query_export_postgresql(
+        &mut client,
+        &queries,
+        "export_rows",
+        &serde_json::json!({}),
+        &mut out,
+        &options,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "id,label,note,content\n1,\"a \"\"quoted\"\" label\",,SGkK\n"
+    );
+}
@@ -563,3 +563,644 @@ async fn test_postgres_multiple_list_parameters_across_multiple_statements() {
     assert!(remaining_names.contains(&"David".to_string()));
     assert!(!remaining_names.contains(&"Bob".to_string()));
 }
+
+#[tokio::test]
+async fn test_postgres_multiple_result_sets() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    let test_table = "test_multiple_result_sets";
+    let _ = client
+        .execute(&format!("DROP TABLE IF EXISTS {test_table}"), &[])
+        .await;
+    client
+        .execute(
+            &format!("CREATE TABLE {test_table} (id SERIAL PRIMARY KEY, name TEXT)"),
+            &[],
+        )
+        .await
+        .unwrap();
+
+    let json_definitions = serde_json::json!({
+        "insert_and_list": {
+            "query": format!(
+                "INSERT INTO {test_table} (name) VALUES (@name); SELECT id, name FROM {test_table} ORDER BY id; SELECT COUNT(*) AS total FROM {test_table}"
+            ),
+            "returns": [[], ["id", "name"], ["total"]]
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+
+    let params = serde_json::json!({"name": "Alice"});
+    let result = query_run_postgresql(&mut client, &queries, "insert_and_list", &params)
+        .await
+        .unwrap();
+
+    assert_eq!(result.sql_statements.len(), 3);
+    assert!(result.data.is_empty());
+    assert_eq!(
+        result.result_sets,
+        vec![
+            vec![],
+            vec![serde_json::json!({"id": 1, "name": "Alice"})],
+            vec![serde_json::json!({"total": 1})],
+        ]
+    );
+
+    let _ = client
+        .execute(&format!("DROP TABLE IF EXISTS {test_table}"), &[])
+        .await;
+}
+
+#[tokio::test]
+async fn test_postgres_explain_analyze_rolls_back() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    let test_table = "test_explain_analyze";
+    let _ = client
+        .execute(&format!("DROP TABLE IF EXISTS {test_table}"), &[])
+        .await;
+    client
+        .execute(
+            &format!("CREATE TABLE {test_table} (id SERIAL PRIMARY KEY, name TEXT)"),
+            &[],
+        )
+        .await
+        .unwrap();
+
+    let json_definitions = serde_json::json!({
+        "add_user": {
+            "query": format!("INSERT INTO {test_table} (name) VALUES (@name)")
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+    let params = serde_json::json!({"name": "Alice"});
+
+    let explained =
+        jankensqlhub::query_explain_postgresql(&mut client, &queries, "add_user", &params, true)
+            .await
+            .unwrap();
+    assert_eq!(
+        explained[0]["sql"],
+        format!("INSERT INTO {test_table} (name) VALUES ($1)")
+    );
+    assert_eq!(explained[0]["plan"][0]["Plan"]["Node Type"], "ModifyTable");
+    // ANALYZE reports actual execution statistics
+    assert_eq!(explained[0]["plan"][0]["Plan"]["Actual Loops"], 1);
+
+    let explained =
+        jankensqlhub::query_explain_postgresql(&mut client, &queries, "add_user", &params, false)
+            .await
+            .unwrap();
+    assert_eq!(
+        explained[0]["plan"][0]["Plan"]["Actual Loops"],
+        serde_json::Value::Null
+    );
+
+    // The analyzed INSERT was rolled back
+    let row = client
+        .query_one(&format!("SELECT COUNT(*) FROM {test_table}"), &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, i64>(0), 0);
+
+    let _ = client
+        .execute(&format!("DROP TABLE IF EXISTS {test_table}"), &[])
+        .await;
+}
+
+#[tokio::test]
+async fn test_postgres_readonly_query() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    let test_table = "test_readonly_query";
+    let _ = client
+        .execute(&format!("DROP TABLE IF EXISTS {test_table}"), &[])
+        .await;
+    client
+        .batch_execute(&format!(
+            "CREATE TABLE {test_table} (id SERIAL PRIMARY KEY, name TEXT);
+             INSERT INTO {test_table} (name) VALUES ('Alice');"
+        ))
+        .await
+        .unwrap();
+
+    let json_definitions = serde_json::json!({
+        "list_users": {
+            "query": format!("SELECT name FROM {test_table}"),
+            "returns": ["name"],
+            "readonly": true
+        },
+        "purge_users": {
+            "query": format!("DELETE FROM {test_table}"),
+            "readonly": true
+        },
+        "add_user": {
+            "query": format!("INSERT INTO {test_table} (name) VALUES (@name)")
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+    let no_params = serde_json::json!({});
+
+    let result = query_run_postgresql(&mut client, &queries, "list_users", &no_params)
+        .await
+        .unwrap();
+    assert_eq!(result.data, vec![serde_json::json!({"name": "Alice"})]);
+
+    let assert_read_only_error = |err: anyhow::Error| {
+        let pg_err = err.downcast::<tokio_postgres::Error>().unwrap();
+        assert_eq!(
+            pg_err.code(),
+            Some(&tokio_postgres::error::SqlState::READ_ONLY_SQL_TRANSACTION)
+        );
+    };
+
+    let err = query_run_postgresql(&mut client, &queries, "purge_users", &no_params)
+        .await
+        .unwrap_err();
+    assert_read_only_error(err);
+
+    // Within a caller-managed transaction, only the readonly query is restricted
+    let mut transaction = client.transaction().await.unwrap();
+    let err = jankensqlhub::query_run_postgresql_with_transaction(
+        &mut transaction,
+        &queries,
+        "purge_users",
+        &no_params,
+    )
+    .await
+    .unwrap_err();
+    assert_read_only_error(err);
+    jankensqlhub::query_run_postgresql_with_transaction(
+        &mut transaction,
+        &queries,
+        "add_user",
+        &serde_json::json!({"name": "Bob"}),
+    )
+    .await
+    .unwrap();
+    transaction.commit().await.unwrap();
+
+    let row = client
+        .query_one(&format!("SELECT COUNT(*) FROM {test_table}"), &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, i64>(0), 2);
+
+    let _ = client
+        .execute(&format!("DROP TABLE IF EXISTS {test_table}"), &[])
+        .await;
+}
+
+#[tokio::test]
+async fn test_postgres_transaction_options() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    let settings_query = "SELECT current_setting('transaction_isolation') AS isolation, current_setting('transaction_read_only') AS read_only, current_setting('transaction_deferrable') AS deferrable";
+    let json_definitions = serde_json::json!({
+        "default_settings": {
+            "query": settings_query,
+            "returns": ["isolation", "read_only", "deferrable"]
+        },
+        "serializable_settings": {
+            "query": settings_query,
+            "returns": ["isolation", "read_only", "deferrable"],
+            "transaction": { "isolation": "serializable", "read_only": true, "deferrable": true }
+        },
+        "repeatable_read_settings": {
+            "query": settings_query,
+            "returns": ["isolation", "read_only", "deferrable"],
+            "transaction": { "isolation": "repeatable_read" }
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+    let no_params = serde_json::json!({});
+
+    let result = query_run_postgresql(&mut client, &queries, "default_settings", &no_params)
+        .await
+        .unwrap();
+    assert_eq!(
+        result.data,
+        vec![
+            serde_json::json!({"isolation": "read committed", "read_only": "off", "deferrable": "off"})
+        ]
+    );
+
+    let result = query_run_postgresql(&mut client, &queries, "serializable_settings", &no_params)
+        .await
+        .unwrap();
+    assert_eq!(
+        result.data,
+        vec![
+            serde_json::json!({"isolation": "serializable", "read_only": "on", "deferrable": "on"})
+        ]
+    );
+
+    let result = query_run_postgresql(
+        &mut client,
+        &queries,
+        "repeatable_read_settings",
+        &no_params,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        result.data,
+        vec![
+            serde_json::json!({"isolation": "repeatable read", "read_only": "off", "deferrable": "off"})
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_postgres_retry_on_serialization_failure() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+    let Some(other_client) = setup_postgres_connection().await else {
+        return;
+    };
+
+    let test_table = "test_retry_serialization";
+    let _ = client
+        .execute(&format!("DROP TABLE IF EXISTS {test_table}"), &[])
+        .await;
+    client
+        .batch_execute(&format!(
+            "CREATE TABLE {test_table} (id INTEGER PRIMARY KEY, counter INTEGER);
+             INSERT INTO {test_table} VALUES (1, 0);"
+        ))
+        .await
+        .unwrap();
+
+    // The first statement takes the snapshot; a concurrent update committed while it sleeps
+    // makes the following UPDATE fail with a serialization failure
+    let increment =
+        format!("SELECT pg_sleep(0.2); UPDATE {test_table} SET counter = counter + 1 WHERE id = 1");
+    let json_definitions = serde_json::json!({
+        "increment": {
+            "query": increment,
+            "transaction": { "isolation": "repeatable_read" }
+        },
+        "increment_with_retry": {
+            "query": increment,
+            "transaction": { "isolation": "repeatable_read", "retry": { "max_attempts": 3 } }
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+    let no_params = serde_json::json!({});
+    let concurrent_update = format!("UPDATE {test_table} SET counter = counter + 10 WHERE id = 1");
+
+    let update = other_client.execute(&concurrent_update, &[]);
+    let run = query_run_postgresql(&mut client, &queries, "increment", &no_params);
+    let (run_result, update_result) = tokio::join!(run, async {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        update.await
+    });
+    update_result.unwrap();
+    let pg_err = run_result
+        .unwrap_err()
+        .downcast::<tokio_postgres::Error>()
+        .unwrap();
+    assert_eq!(
+        pg_err.code(),
+        Some(&tokio_postgres::error::SqlState::T_R_SERIALIZATION_FAILURE)
+    );
+
+    let update = other_client.execute(&concurrent_update, &[]);
+    let run = query_run_postgresql(&mut client, &queries, "increment_with_retry", &no_params);
+    let (run_result, update_result) = tokio::join!(run, async {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        update.await
+    });
+    update_result.unwrap();
+    assert_eq!(run_result.unwrap().attempts, 2);
+
+    let row = client
+        .query_one(
+            &format!("SELECT counter FROM {test_table} WHERE id = 1"),
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, i32>(0), 21);
+
+    let _ = client
+        .execute(&format!("DROP TABLE IF EXISTS {test_table}"), &[])
+        .await;
+}
+
+#[tokio::test]
+async fn test_postgres_savepoint_step_failure_keeps_transaction_usable() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    let test_table = "test_savepoint_steps";
+    let _ = client
+        .execute(&format!("DROP TABLE IF EXISTS {test_table}"), &[])
+        .await;
+    client
+        .execute(
+            &format!("CREATE TABLE {test_table} (id INTEGER PRIMARY KEY, name TEXT)"),
+            &[],
+        )
+        .await
+        .unwrap();
+
+    let json_definitions = serde_json::json!({
+        "add_user": {
+            "query": format!("INSERT INTO {test_table} (id, name) VALUES (@id, @name)"),
+            "args": { "id": { "type": "integer" } }
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+
+    let mut transaction = client.transaction().await.unwrap();
+    jankensqlhub::query_run_postgresql_with_savepoint(
+        &mut transaction,
+        &queries,
+        "add_user",
+        &serde_json::json!({"id": 1, "name": "Alice"}),
+    )
+    .await
+    .unwrap();
+
+    let err = jankensqlhub::query_run_postgresql_with_savepoint(
+        &mut transaction,
+        &queries,
+        "add_user",
+        &serde_json::json!({"id": 1, "name": "Duplicate"}),
+    )
+    .await
+    .unwrap_err();
+    let pg_err = err.downcast::<tokio_postgres::Error>().unwrap();
+    assert_eq!(
+        pg_err.code(),
+        Some(&tokio_postgres::error::SqlState::UNIQUE_VIOLATION)
+    );
+
+    // The transaction is not aborted by the failed step
+    jankensqlhub::query_run_postgresql_with_savepoint(
+        &mut transaction,
+        &queries,
+        "add_user",
+        &serde_json::json!({"id": 2, "name": "Bob"}),
+    )
+    .await
+    .unwrap();
+    transaction.commit().await.unwrap();
+
+    let rows = client
+        .query(&format!("SELECT name FROM {test_table} ORDER BY id"), &[])
+        .await
+        .unwrap();
+    let names: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(names, vec!["Alice", "Bob"]);
+
+    let _ = client
+        .execute(&format!("DROP TABLE IF EXISTS {test_table}"), &[])
+        .await;
+}
+
+#[tokio::test]
+async fn test_postgres_workflow() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    let _ = client
+        .batch_execute("DROP TABLE IF EXISTS test_workflow_items, test_workflow_orders")
+        .await;
+    client
+        .batch_execute(
+            "CREATE TABLE test_workflow_orders (id SERIAL PRIMARY KEY, customer TEXT NOT NULL);
+             CREATE TABLE test_workflow_items (order_id INTEGER REFERENCES test_workflow_orders(id), sku TEXT);",
+        )
+        .await
+        .unwrap();
+
+    let json_definitions = serde_json::json!({
+        "insert_order": {
+            "query": "INSERT INTO test_workflow_orders (customer) VALUES (@customer) RETURNING id",
+            "returns": ["id"]
+        },
+        "insert_item": {
+            "query": "INSERT INTO test_workflow_items (order_id, sku) VALUES (@order_id, @sku)",
+            "args": { "order_id": { "type": "integer" } }
+        },
+        "place_order": {
+            "workflow": [
+                { "name": "order", "query": "insert_order" },
+                {
+                    "name": "item",
+                    "query": "insert_item",
+                    "params": { "order_id": { "$from": "/steps/order/data/0/id" } }
+                }
+            ]
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+
+    let params = serde_json::json!({"customer": "Alice", "sku": "apple"});
+    let result =
+        jankensqlhub::workflow_run_postgresql(&mut client, &queries, "place_order", &params)
+            .await
+            .unwrap();
+    let order_id = result.step("order").unwrap().data[0]["id"]
+        .as_i64()
+        .unwrap();
+    assert_eq!(
+        result.step("item").unwrap().statements[0].render_sql(),
+        format!("INSERT INTO test_workflow_items (order_id, sku) VALUES ({order_id}, 'apple')")
+    );
+
+    // A failing step rolls back the order inserted by the first step
+    let params = serde_json::json!({"customer": "Bob", "sku": "pear"});
+    let broken_definitions = serde_json::json!({
+        "insert_order": {
+            "query": "INSERT INTO test_workflow_orders (customer) VALUES (@customer) RETURNING id",
+            "returns": ["id"]
+        },
+        "insert_item": {
+            "query": "INSERT INTO test_workflow_items (order_id, sku) VALUES (@order_id, @sku)",
+            "args": { "order_id": { "type": "integer" } }
+        },
+        "place_order": {
+            "workflow": [
+                { "name": "order", "query": "insert_order" },
+                { "query": "insert_item", "params": { "order_id": -1 } }
+            ]
+        }
+    });
+    let broken = jankensqlhub::QueryDefinitions::from_json(broken_definitions).unwrap();
+    let err = jankensqlhub::workflow_run_postgresql(&mut client, &broken, "place_order", &params)
+        .await
+        .unwrap_err();
+    let pg_err = err.downcast::<tokio_postgres::Error>().unwrap();
+    assert_eq!(
+        pg_err.code(),
+        Some(&tokio_postgres::error::SqlState::FOREIGN_KEY_VIOLATION)
+    );
+
+    let row = client
+        .query_one("SELECT COUNT(*) FROM test_workflow_orders", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, i64>(0), 1);
+
+    let _ = client
+        .batch_execute("DROP TABLE IF EXISTS test_workflow_items, test_workflow_orders")
+        .await;
+}
+
+#[tokio::test]
+async fn test_postgres_workflow_transaction_options() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    let settings_query = "SELECT current_setting('transaction_isolation') AS isolation, current_setting('transaction_read_only') AS read_only";
+    let json_definitions = serde_json::json!({
+        "settings": {
+            "query": settings_query,
+            "returns": ["isolation", "read_only"]
+        },
+        "serializable_settings": {
+            "query": settings_query,
+            "returns": ["isolation", "read_only"],
+            "transaction": { "isolation": "serializable" }
+        },
+        "declared": {
+            "workflow": [{ "query": "settings" }],
+            "transaction": { "isolation": "repeatable_read", "read_only": true }
+        },
+        "from_steps": {
+            "workflow": [{ "query": "settings" }, { "query": "serializable_settings" }]
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+    let no_params = serde_json::json!({});
+
+    let result =
+        jankensqlhub::workflow_run_postgresql(&mut client, &queries, "declared", &no_params)
+            .await
+            .unwrap();
+    assert_eq!(
+        result.step("settings").unwrap().data,
+        vec![serde_json::json!({"isolation": "repeatable read", "read_only": "on"})]
+    );
+    assert_eq!(result.attempts, 1);
+
+    // Every step runs in the transaction opened with the options a step declares
+    let result =
+        jankensqlhub::workflow_run_postgresql(&mut client, &queries, "from_steps", &no_params)
+            .await
+            .unwrap();
+    for step in ["settings", "serializable_settings"] {
+        assert_eq!(
+            result.step(step).unwrap().data,
+            vec![serde_json::json!({"isolation": "serializable", "read_only": "off"})]
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_postgres_batch() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    let test_table = "test_batch_users";
+    let _ = client
+        .execute(&format!("DROP TABLE IF EXISTS {test_table}"), &[])
+        .await;
+    client
+        .execute(
+            &format!("CREATE TABLE {test_table} (id INTEGER PRIMARY KEY, name TEXT)"),
+            &[],
+        )
+        .await
+        .unwrap();
+
+    let json_definitions = serde_json::json!({
+        "add_user": {
+            "query": format!("INSERT INTO {test_table} (id, name) VALUES (@id, @name)"),
+            "args": { "id": { "type": "integer" } }
+        },
+        "rename_users": {
+            "query": format!("UPDATE {test_table} SET name = @name WHERE id IN :[ids]"),
+            "args": { "ids": { "itemtype": "integer" } }
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+
+    let params_list: Vec<serde_json::Value> = (1..=20)
+        .map(|id| serde_json::json!({"id": id, "name": format!("user{id}")}))
+        .collect();
+    let result =
+        jankensqlhub::query_batch_postgresql(&mut client, &queries, "add_user", &params_list)
+            .await
+            .unwrap();
+    assert_eq!(result.row_counts, vec![1; 20]);
+    assert_eq!(result.prepared_statements, 1);
+
+    let params_list = vec![
+        serde_json::json!({"ids": [1, 2], "name": "pair"}),
+        serde_json::json!({"ids": [3, 4, 5], "name": "triple"}),
+    ];
+    let result =
+        jankensqlhub::query_batch_postgresql(&mut client, &queries, "rename_users", &params_list)
+            .await
+            .unwrap();
+    assert_eq!(result.row_counts, vec![2, 3]);
+    assert_eq!(result.prepared_statements, 2);
+
+    // A failing item rolls back the whole batch and reports its index
+    let params_list = vec![
+        serde_json::json!({"id": 21, "name": "new"}),
+        serde_json::json!({"id": 1, "name": "duplicate"}),
+    ];
+    let err = jankensqlhub::query_batch_postgresql(&mut client, &queries, "add_user", &params_list)
+        .await
+        .unwrap_err();
+    match err.downcast_ref::<jankensqlhub::JankenError>().unwrap() {
+        jankensqlhub::JankenError::BatchItemFailed { data } => {
+            assert_eq!(
+                jankensqlhub::error_meta(data, jankensqlhub::M_INDEX).unwrap(),
+                "1"
+            );
+        }
+        other => panic!("Expected BatchItemFailed, got: {other:?}"),
+    }
+    assert_eq!(
+        err.downcast_ref::<tokio_postgres::Error>().unwrap().code(),
+        Some(&tokio_postgres::error::SqlState::UNIQUE_VIOLATION)
+    );
+
+    let row = client
+        .query_one(&format!("SELECT COUNT(*) FROM {test_table}"), &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, i64>(0), 20);
+
+    let _ = client
+        .execute(&format!("DROP TABLE IF EXISTS {test_table}"), &[])
+        .await;
+}
@@ -562,3 +562,590 @@ async fn test_postgres_dynamic_returns_with_comma_list() {
     assert_eq!(row4.get("age"), Some(&serde_json::json!(25)));
     assert_eq!(row4.get("name"), Some(&serde_json::json!("Alice")));
 }
+
+#[tokio::test]
+async fn test_postgres_bytea_blob_encoding() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    let test_table = "test_bytea_blob_encoding";
+    let _ = client
+        .execute(&format!("DROP TABLE IF EXISTS {test_table}"), &[])
+        .await;
+    client
+        .execute(
+            &format!("CREATE TABLE {test_table} (id INTEGER, data BYTEA)"),
+            &[],
+        )
+        .await
+        .unwrap();
+
+    let json_definitions = serde_json::json!({
+        "insert_blob": {
+            "query": format!("INSERT INTO {test_table} VALUES (@id, @data)"),
+            "args": {
+                "id": { "type": "integer" },
+                "data": { "type": "blob", "encoding": "hex" }
+            }
+        },
+        "select_blob": {
+            "query": format!("SELECT id, data FROM {test_table} ORDER BY id"),
+            "returns": ["id", "data"],
+            "blob_encoding": "base64"
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+
+    let params = serde_json::json!({"id": 1, "data": {"hex": "0102ff"}});
+    query_run_postgresql(&mut client, &queries, "insert_blob", &params)
+        .await
+        .unwrap();
+
+    let result = query_run_postgresql(&mut client, &queries, "select_blob", &serde_json::json!({}))
+        .await
+        .unwrap();
+    assert_eq!(
+        result.data,
+        vec![serde_json::json!({"id": 1, "data": "AQL/"})]
+    );
+
+    let _ = client
+        .execute(&format!("DROP TABLE IF EXISTS {test_table}"), &[])
+        .await;
+}
+
+#[tokio::test]
+async fn test_postgres_executed_statements_with_large_list() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    // Eleven list items exercise $1/$10 style placeholders that share a prefix
+    let json_definitions = serde_json::json!({
+        "pick_numbers": {
+            "query": "SELECT n FROM generate_series(0, 20) AS n WHERE n IN :[ids] AND @label <> '@label' ORDER BY n",
+            "returns": ["n"],
+            "args": {
+                "ids": { "itemtype": "integer" },
+                "label": { "sensitive": true }
+            }
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+
+    let params = serde_json::json!({"ids": [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10], "label": "x"});
+    let result = query_run_postgresql(&mut client, &queries, "pick_numbers", &params)
+        .await
+        .unwrap();
+
+    assert_eq!(result.data.len(), 11);
+    assert_eq!(result.data[10], serde_json::json!({"n": 10}));
+    assert_eq!(
+        result.sql_statements,
+        vec![
+            "SELECT n FROM generate_series(0, 20) AS n WHERE n IN ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) AND $12 <> '@label' ORDER BY n"
+        ]
+    );
+    assert_eq!(
+        result.statements[0].render_sql(),
+        "SELECT n FROM generate_series(0, 20) AS n WHERE n IN (0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10) AND '[REDACTED]' <> '@label' ORDER BY n"
+    );
+}
+
+#[tokio::test]
+async fn test_postgres_rows_bulk_insert() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    let test_table = "test_rows_bulk_insert";
+    let _ = client
+        .execute(&format!("DROP TABLE IF EXISTS {test_table}"), &[])
+        .await;
+    client
+        .execute(
+            &format!("CREATE TABLE {test_table} (id INTEGER, name TEXT, active BOOLEAN)"),
+            &[],
+        )
+        .await
+        .unwrap();
+
+    let json_definitions = serde_json::json!({
+        "add_rows": {
+            "query": format!("INSERT INTO {test_table} (id, name, active) VALUES *[rows] RETURNING id, name, active"),
+            "returns": ["id", "name", "active"],
+            "args": {
+                "rows": {
+                    "fields": [
+                        { "name": "id", "type": "integer" },
+                        { "name": "name" },
+                        { "name": "active", "type": "boolean" }
+                    ]
+                }
+            }
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+
+    let params = serde_json::json!({"rows": [
+        {"id": 1, "name": "one", "active": true},
+        {"id": 2, "name": "two", "active": false}
+    ]});
+    let result = query_run_postgresql(&mut client, &queries, "add_rows", &params)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result.sql_statements,
+        vec![format!(
+            "INSERT INTO {test_table} (id, name, active) VALUES ($1, $2, $3), ($4, $5, $6) RETURNING id, name, active"
+        )]
+    );
+    assert_eq!(
+        result.data,
+        vec![
+            serde_json::json!({"id": 1, "name": "one", "active": true}),
+            serde_json::json!({"id": 2, "name": "two", "active": false})
+        ]
+    );
+
+    let _ = client
+        .execute(&format!("DROP TABLE IF EXISTS {test_table}"), &[])
+        .await;
+}
+
+#[tokio::test]
+async fn test_postgres_tuple_list_composite_keys() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    let json_definitions = serde_json::json!({
+        "find_pairs": {
+            "query": "SELECT t, s FROM (VALUES (1, 'a'), (1, 'b'), (2, 'a')) AS v(t, s) WHERE (t, s) IN :[keys] ORDER BY t, s",
+            "returns": ["t", "s"],
+            "args": {
+                "keys": {
+                    "fields": [{ "name": "t", "type": "integer" }, { "name": "s" }]
+                }
+            }
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+
+    let params = serde_json::json!({"keys": [[1, "b"], {"t": 2, "s": "a"}, [2, "b"]]});
+    let result = query_run_postgresql(&mut client, &queries, "find_pairs", &params)
+        .await
+        .unwrap();
+
+    assert!(
+        result.sql_statements[0].contains("(t, s) IN (($1, $2), ($3, $4), ($5, $6))"),
+        "{}",
+        result.sql_statements[0]
+    );
+    assert_eq!(
+        result.data,
+        vec![
+            serde_json::json!({"t": 1, "s": "b"}),
+            serde_json::json!({"t": 2, "s": "a"})
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_postgres_list_array_binding() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    let json_definitions = serde_json::json!({
+        "find_items": {
+            "query": "SELECT id, name FROM (VALUES (1, 'a'), (2, 'b'), (3, 'c'), (4, 'd')) AS v(id, name) WHERE id in :[ids] AND name NOT IN :[names] ORDER BY id",
+            "returns": ["id", "name"],
+            "args": {
+                "ids": { "itemtype": "integer", "pg_array": true },
+                "names": { "itemtype": "string", "pg_array": true, "range": [0, 10] }
+            }
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+    let expected_sql = "SELECT id, name FROM (VALUES (1, 'a'), (2, 'b'), (3, 'c'), (4, 'd')) AS v(id, name) WHERE id = ANY($1) AND name <> ALL($2) ORDER BY id";
+
+    // The SQL text stays the same whatever the list lengths, so the server can reuse its plan
+    let params = serde_json::json!({"ids": [1, 2, 3], "names": ["b"]});
+    let result = query_run_postgresql(&mut client, &queries, "find_items", &params)
+        .await
+        .unwrap();
+    assert_eq!(result.sql_statements, vec![expected_sql]);
+    assert_eq!(
+        result.data,
+        vec![
+            serde_json::json!({"id": 1, "name": "a"}),
+            serde_json::json!({"id": 3, "name": "c"})
+        ]
+    );
+    assert_eq!(
+        result.statements[0].render_sql(),
+        "SELECT id, name FROM (VALUES (1, 'a'), (2, 'b'), (3, 'c'), (4, 'd')) AS v(id, name) WHERE id = ANY(ARRAY[1, 2, 3]) AND name <> ALL(ARRAY['b']) ORDER BY id"
+    );
+
+    // Empty lists bind as empty arrays
+    let params = serde_json::json!({"ids": [4], "names": []});
+    let result = query_run_postgresql(&mut client, &queries, "find_items", &params)
+        .await
+        .unwrap();
+    assert_eq!(result.sql_statements, vec![expected_sql]);
+    assert_eq!(result.data, vec![serde_json::json!({"id": 4, "name": "d"})]);
+
+    // Items are still validated against itemtype
+    let params = serde_json::json!({"ids": [1, "two"], "names": []});
+    let err = query_run_postgresql(&mut client, &queries, "find_items", &params)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast::<jankensqlhub::JankenError>().unwrap(),
+        jankensqlhub::JankenError::ParameterTypeMismatch { .. }
+    ));
+
+    // Integer arrays bind as int4[]: an item outside the 32-bit range fails instead of wrapping
+    let params = serde_json::json!({"ids": [1, 4_294_967_297_i64], "names": []});
+    let err = query_run_postgresql(&mut client, &queries, "find_items", &params)
+        .await
+        .unwrap_err();
+    match err.downcast::<jankensqlhub::JankenError>().unwrap() {
+        jankensqlhub::JankenError::ParameterTypeMismatch { data } => {
+            assert_eq!(
+                jankensqlhub::error_meta(&data, jankensqlhub::M_EXPECTED).unwrap(),
+                "integer within the 32-bit range of a PostgreSQL integer array"
+            );
+            assert_eq!(
+                jankensqlhub::error_meta(&data, jankensqlhub::M_GOT).unwrap(),
+                "4294967297"
+            );
+        }
+        other => panic!("Expected ParameterTypeMismatch, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_postgres_filter_parameter() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    let json_definitions = serde_json::json!({
+        "find_items": {
+            "query": "SELECT id, name FROM (VALUES (1, 'a'), (2, 'b'), (3, NULL)) AS v(id, name) WHERE ?[filter] ORDER BY id",
+            "returns": ["id", "name"],
+            "args": {
+                "filter": {
+                    "columns": {
+                        "id": { "type": "integer", "ops": ["eq", "gt", "in"] },
+                        "name": { "ops": ["like", "is_null"] }
+                    }
+                }
+            }
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+
+    let params = serde_json::json!({"filter": {"or": [
+        {"and": [{"column": "id", "op": "in", "value": [1, 2]}, {"column": "name", "op": "like", "value": "b%"}]},
+        {"column": "name", "op": "is_null"}
+    ]}});
+    let result = query_run_postgresql(&mut client, &queries, "find_items", &params)
+        .await
+        .unwrap();
+    assert_eq!(
+        result.sql_statements,
+        vec![
+            "SELECT id, name FROM (VALUES (1, 'a'), (2, 'b'), (3, NULL)) AS v(id, name) WHERE (((id IN ($1, $2) AND name LIKE $3) OR name IS NULL)) ORDER BY id"
+        ]
+    );
+    assert_eq!(
+        result.data,
+        vec![
+            serde_json::json!({"id": 2, "name": "b"}),
+            serde_json::json!({"id": 3, "name": null})
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_postgres_max_rows() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    let json_definitions = serde_json::json!({
+        "series_truncated": {
+            "query": "SELECT n FROM generate_series(1, 1000) AS n",
+            "returns": ["n"],
+            "max_rows": {"rows": 3, "on_exceed": "truncate"}
+        },
+        "series_strict": {
+            "query": "SELECT n FROM generate_series(1, 1000) AS n",
+            "returns": ["n"],
+            "max_rows": 3
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+
+    let result = query_run_postgresql(
+        &mut client,
+        &queries,
+        "series_truncated",
+        &serde_json::json!({}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        result.data,
+        vec![
+            serde_json::json!({"n": 1}),
+            serde_json::json!({"n": 2}),
+            serde_json::json!({"n": 3})
+        ]
+    );
+    assert!(result.truncated);
+
+    let err = query_run_postgresql(
+        &mut client,
+        &queries,
+        "series_strict",
+        &serde_json::json!({}),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(
+        err.downcast::<jankensqlhub::JankenError>().unwrap(),
+        jankensqlhub::JankenError::RowLimitExceeded { .. }
+    ));
+
+    // The connection stays usable after reading stopped early
+    let result = query_run_postgresql(
+        &mut client,
+        &queries,
+        "series_truncated",
+        &serde_json::json!({}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.data.len(), 3);
+}
+
+#[tokio::test]
+async fn test_postgres_max_rows_truncate_runs_every_write_chunk() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    client
+        .execute("DROP TABLE IF EXISTS limited_numbers", &[])
+        .await
+        .unwrap();
+    client
+        .execute("CREATE TABLE limited_numbers (n INTEGER)", &[])
+        .await
+        .unwrap();
+
+    let json_definitions = serde_json::json!({
+        "add_numbers": {
+            "query": "INSERT INTO limited_numbers (n) VALUES *[rows] RETURNING n",
+            "returns": ["n"],
+            "args": {"rows": {"fields": [{"name": "n", "type": "integer"}], "chunk_size": 2}},
+            "max_rows": {"rows": 2, "on_exceed": "truncate"}
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+
+    let rows: Vec<_> = (1..7).map(|n| serde_json::json!({"n": n})).collect();
+    let params = serde_json::json!({"rows": rows});
+    let result = query_run_postgresql(&mut client, &queries, "add_numbers", &params)
+        .await
+        .unwrap();
+    assert_eq!(
+        result.data,
+        vec![serde_json::json!({"n": 1}), serde_json::json!({"n": 2})]
+    );
+    assert!(result.truncated);
+
+    // Only the returned rows are limited: every chunk of the insert is stored
+    let stored: i64 = client
+        .query_one("SELECT COUNT(*) FROM limited_numbers", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(stored, 6);
+}
+
+#[tokio::test]
+async fn test_postgres_search_parameter() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    client
+        .execute("DROP TABLE IF EXISTS search_products", &[])
+        .await
+        .unwrap();
+    client
+        .execute(
+            "CREATE TABLE search_products (id INTEGER PRIMARY KEY, name TEXT)",
+            &[],
+        )
+        .await
+        .unwrap();
+    client
+        .execute(
+            "INSERT INTO search_products VALUES (1, '50%_OFF coupon'), (2, '50 percent off'), (3, 'coupon 50%_off')",
+            &[],
+        )
+        .await
+        .unwrap();
+
+    let json_definitions = serde_json::json!({
+        "find_products": {
+            "query": "SELECT id FROM search_products WHERE name ILIKE @q ORDER BY id",
+            "returns": ["id"],
+            "args": { "q": { "type": "search" } }
+        },
+        "find_prefix": {
+            "query": "SELECT id FROM search_products WHERE name LIKE @q ORDER BY id",
+            "returns": ["id"],
+            "args": { "q": { "type": "search", "mode": "prefix" } }
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+
+    let params = serde_json::json!({"q": "50%_off"});
+    let result = query_run_postgresql(&mut client, &queries, "find_products", &params)
+        .await
+        .unwrap();
+    assert_eq!(
+        result.data,
+        vec![serde_json::json!({"id": 1}), serde_json::json!({"id": 3})]
+    );
+    assert_eq!(
+        result.sql_statements,
+        vec!["SELECT id FROM search_products WHERE name ILIKE $1 ESCAPE '!' ORDER BY id"]
+    );
+
+    let params = serde_json::json!({"q": "50%"});
+    let result = query_run_postgresql(&mut client, &queries, "find_prefix", &params)
+        .await
+        .unwrap();
+    assert_eq!(result.data, vec![serde_json::json!({"id": 1})]);
+
+    client
+        .execute("DROP TABLE search_products", &[])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_postgres_fts_query_parameter() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    let json_definitions = serde_json::json!({
+        "search_docs": {
+            "query": "SELECT id FROM (VALUES (1, 'rust database drivers'), (2, 'near or far, rust never sleeps'), (3, 'postgresql databases')) AS docs(id, body) WHERE to_tsvector('simple', body) @@ to_tsquery('simple', @q) ORDER BY id",
+            "returns": ["id"],
+            "args": { "q": { "type": "fts_query" } }
+        },
+        "search_docs_any": {
+            "query": "SELECT id FROM (VALUES (1, 'rust database drivers'), (2, 'near or far, rust never sleeps'), (3, 'postgresql databases')) AS docs(id, body) WHERE to_tsvector('simple', body) @@ to_tsquery('simple', @q) ORDER BY id",
+            "returns": ["id"],
+            "args": { "q": { "type": "fts_query", "prefix": true, "operator": "or" } }
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+
+    // Raw input like this is a to_tsquery syntax error
+    let params = serde_json::json!({"q": "rust & (near"});
+    let result = query_run_postgresql(&mut client, &queries, "search_docs", &params)
+        .await
+        .unwrap();
+    assert_eq!(result.data, vec![serde_json::json!({"id": 2})]);
+    assert_eq!(
+        result.statements[0].parameters[0].value,
+        jankensqlhub::ParameterValue::String("'rust' & 'near'".to_string())
+    );
+
+    let params = serde_json::json!({"q": "data sleep"});
+    let result = query_run_postgresql(&mut client, &queries, "search_docs_any", &params)
+        .await
+        .unwrap();
+    assert_eq!(
+        result.data,
+        vec![
+            serde_json::json!({"id": 1}),
+            serde_json::json!({"id": 2}),
+            serde_json::json!({"id": 3})
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_postgres_quoted_identifiers() {
+    let Some(mut client) = setup_postgres_connection().await else {
+        println!("Skipping PostgreSQL tests - POSTGRES_CONNECTION_STRING not set");
+        return;
+    };
+
+    client
+        .batch_execute(
+            "DROP SCHEMA IF EXISTS janken_analytics CASCADE;
+             CREATE SCHEMA janken_analytics;
+             CREATE TABLE janken_analytics.\"Events\" (id INTEGER PRIMARY KEY, \"order\" TEXT);
+             INSERT INTO janken_analytics.\"Events\" VALUES (1, 'first'), (2, 'second');",
+        )
+        .await
+        .unwrap();
+
+    let json_definitions = serde_json::json!({
+        "select_from": {
+            "query": "SELECT ~[fields] FROM #[table] ORDER BY id",
+            "returns": ["id", "order"],
+            "args": {
+                "table": { "enum": ["janken_analytics.Events"], "quote": true },
+                "fields": { "enum": ["id", "order"], "quote": true }
+            }
+        }
+    });
+    let queries = jankensqlhub::QueryDefinitions::from_json(json_definitions).unwrap();
+
+    let params = serde_json::json!({"table": "janken_analytics.Events", "fields": ["id", "order"]});
+    let result = query_run_postgresql(&mut client, &queries, "select_from", &params)
+        .await
+        .unwrap();
+    assert_eq!(
+        result.sql_statements,
+        vec!["SELECT \"id\",\"order\" FROM \"janken_analytics\".\"Events\" ORDER BY id"]
+    );
+    assert_eq!(
+        result.data,
+        vec![
+            serde_json::json!({"id": 1, "order": "first"}),
+            serde_json::json!({"id": 2, "order": "second"})
+        ]
+    );
+
+    client
+        .batch_execute("DROP SCHEMA janken_analytics CASCADE")
+        .await
+        .unwrap();
+}
@@ -0,0 +1,155 @@
+use jankensqlhub::{
+    JankenError, M_EXPECTED, M_GOT, QueryDefinitions, error_meta, query_run_sqlite,
+    quote_identifier,
+};
+use rusqlite::Connection;
+
+fn definitions() -> QueryDefinitions {
+    QueryDefinitions::from_json(serde_json::json!({
+        "select_from": {
+            "query": "SELECT ~[fields] FROM #[table] ORDER BY 1",
+            "returns": ["id", "select"],
+            "args": {
+                "table": { "enum": ["order", "main.order", "archive.Order"], "quote": true },
+                "fields": { "enum": ["id", "select", "order.select"], "quote": true }
+            }
+        },
+        "select_unquoted": {
+            "query": "SELECT id FROM #[table]",
+            "returns": ["id"],
+            "args": { "table": { "enum": ["main.order"] } }
+        }
+    }))
+    .unwrap()
+}
+
+fn setup_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE \"order\" (id INTEGER PRIMARY KEY, \"select\" TEXT);
+         INSERT INTO \"order\" VALUES (1, 'a'), (2, 'b');
+         ATTACH DATABASE ':memory:' AS archive;
+         CREATE TABLE archive.\"Order\" (id INTEGER PRIMARY KEY, \"select\" TEXT);
+         INSERT INTO archive.\"Order\" VALUES (3, 'c');",
+    )
+    .unwrap();
+    conn
+}
+
+fn type_mismatch(err: anyhow::Error) -> (String, String) {
+    match err.downcast::<JankenError>().unwrap() {
+        JankenError::ParameterTypeMismatch { data } => (
+            error_meta(&data, M_EXPECTED).unwrap(),
+            error_meta(&data, M_GOT).unwrap(),
+        ),
+        other => panic!("Expected ParameterTypeMismatch, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_quote_identifier() {
+    assert_eq!(quote_identifier("order"), "\"order\"");
+    assert_eq!(
+        quote_identifier("analytics.Events"),
+        "\"analytics\".\"Events\""
+    );
+}
+
+#[test]
+fn test_quoted_table_and_comma_list_identifiers() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let params = serde_json::json!({"table": "order", "fields": ["id", "select"]});
+    let result = query_run_sqlite(&mut conn, &queries, "select_from", &params).unwrap();
+    assert_eq!(
+        result.sql_statements,
+        vec!["SELECT \"id\",\"select\" FROM \"order\" ORDER BY 1"]
+    );
+    assert_eq!(
+        result.data,
+        vec![
+            serde_json::json!({"id": 1, "select": "a"}),
+            serde_json::json!({"id": 2, "select": "b"})
+        ]
+    );
+
+    // Schema-qualified names are quoted part by part
+    let params = serde_json::json!({"table": "archive.Order", "fields": ["id", "select"]});
+    let result = query_run_sqlite(&mut conn, &queries, "select_from", &params).unwrap();
+    assert_eq!(
+        result.sql_statements,
+        vec!["SELECT \"id\",\"select\" FROM \"archive\".\"Order\" ORDER BY 1"]
+    );
+    assert_eq!(
+        result.data,
+        vec![serde_json::json!({"id": 3, "select": "c"})]
+    );
+
+    let params = serde_json::json!({"table": "main.order", "fields": ["id", "order.select"]});
+    let result = query_run_sqlite(&mut conn, &queries, "select_from", &params).unwrap();
+    assert_eq!(
+        result.sql_statements,
+        vec!["SELECT \"id\",\"order\".\"select\" FROM \"main\".\"order\" ORDER BY 1"]
+    );
+}
+
+#[test]
+fn test_quoted_identifier_validation_errors() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    // The allowlist still applies to quoted identifiers
+    let params = serde_json::json!({"table": "sqlite_master", "fields": ["id"]});
+    let err = query_run_sqlite(&mut conn, &queries, "select_from", &params).unwrap_err();
+    assert!(matches!(
+        err.downcast::<JankenError>().unwrap(),
+        JankenError::ParameterTypeMismatch { .. }
+    ));
+
+    // Without quote, schema-qualified names are rejected even when allowlisted
+    let params = serde_json::json!({"table": "main.order"});
+    let err = query_run_sqlite(&mut conn, &queries, "select_unquoted", &params).unwrap_err();
+    assert_eq!(
+        type_mismatch(err),
+        (
+            "valid table name (alphanumeric and underscores only)".to_string(),
+            "main.order".to_string()
+        )
+    );
+
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "any_table": {
+            "query": "SELECT * FROM #[table]",
+            "args": { "table": { "pattern": ".*", "quote": true } }
+        }
+    }))
+    .unwrap();
+    for bad_name in ["a.b.c", "main.", "my\"table"] {
+        let params = serde_json::json!({"table": bad_name});
+        let err = query_run_sqlite(&mut conn, &queries, "any_table", &params).unwrap_err();
+        assert_eq!(
+            type_mismatch(err),
+            (
+                "valid identifier (name or schema.name, alphanumeric and underscores only)"
+                    .to_string(),
+                bad_name.to_string()
+            )
+        );
+    }
+
+    let err = QueryDefinitions::from_json(serde_json::json!({
+        "quote_on_string": {
+            "query": "SELECT * FROM users WHERE name = @name",
+            "args": { "name": { "quote": true } }
+        }
+    }))
+    .unwrap_err();
+    assert_eq!(
+        type_mismatch(err),
+        (
+            "type table_name or comma_list for parameter with 'quote'".to_string(),
+            "name".to_string()
+        )
+    );
+}
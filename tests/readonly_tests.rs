@@ -0,0 +1,142 @@
+use jankensqlhub::{
+    JankenError, M_EXPECTED, M_GOT, M_SQL, QueryDefinitions, error_meta, query_run_sqlite,
+    query_run_sqlite_with_transaction,
+};
+use rusqlite::Connection;
+
+fn definitions() -> QueryDefinitions {
+    QueryDefinitions::from_json(serde_json::json!({
+        "list_users": {
+            "query": "SELECT id, name FROM users ORDER BY id",
+            "returns": ["id", "name"],
+            "readonly": true
+        },
+        "purge_user": {
+            "query": "DELETE FROM users WHERE id = @id",
+            "args": { "id": { "type": "integer" } },
+            "readonly": true
+        },
+        "delete_user": {
+            "query": "DELETE FROM users WHERE id = @id",
+            "args": { "id": { "type": "integer" } }
+        }
+    }))
+    .unwrap()
+}
+
+fn setup_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+         INSERT INTO users (name) VALUES ('Alice'), ('Bob');",
+    )
+    .unwrap();
+    conn
+}
+
+fn query_only(conn: &Connection) -> bool {
+    conn.pragma_query_value(None, "query_only", |row| row.get(0))
+        .unwrap()
+}
+
+fn user_count(conn: &Connection) -> i64 {
+    conn.query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))
+        .unwrap()
+}
+
+#[test]
+fn test_readonly_query_runs_and_restores_query_only() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let result =
+        query_run_sqlite(&mut conn, &queries, "list_users", &serde_json::json!({})).unwrap();
+    assert_eq!(result.data.len(), 2);
+    assert!(!query_only(&conn));
+
+    // A query_only setting chosen by the caller is kept
+    conn.pragma_update(None, "query_only", true).unwrap();
+    query_run_sqlite(&mut conn, &queries, "list_users", &serde_json::json!({})).unwrap();
+    assert!(query_only(&conn));
+}
+
+#[test]
+fn test_readonly_query_refuses_writes() {
+    let queries = definitions();
+    let mut conn = setup_db();
+    let params = serde_json::json!({"id": 1});
+
+    let err = query_run_sqlite(&mut conn, &queries, "purge_user", &params).unwrap_err();
+    match err.downcast::<JankenError>().unwrap() {
+        JankenError::ReadonlyViolation { data } => {
+            assert_eq!(
+                error_meta(&data, M_SQL).unwrap(),
+                "DELETE FROM users WHERE id = :id"
+            );
+        }
+        other => panic!("Expected ReadonlyViolation, got: {other:?}"),
+    }
+    assert_eq!(user_count(&conn), 2);
+    assert!(!query_only(&conn));
+
+    // The check also applies inside a caller-managed transaction
+    let tx = conn.transaction().unwrap();
+    let err = query_run_sqlite_with_transaction(&tx, &queries, "purge_user", &params).unwrap_err();
+    assert!(matches!(
+        err.downcast::<JankenError>().unwrap(),
+        JankenError::ReadonlyViolation { .. }
+    ));
+    tx.commit().unwrap();
+    assert_eq!(user_count(&conn), 2);
+
+    // Queries without the flag are unaffected
+    query_run_sqlite(&mut conn, &queries, "delete_user", &params).unwrap();
+    assert_eq!(user_count(&conn), 1);
+}
+
+#[test]
+fn test_readonly_load_time_warnings() {
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "list_users": {
+            "query": "SELECT id FROM users FOR UPDATE; WITH recent AS (SELECT 'DELETE' AS word) SELECT word FROM recent",
+            "returns": ["id"],
+            "readonly": true
+        },
+        "archive_users": {
+            "query": "WITH moved AS (DELETE FROM users RETURNING *) INSERT INTO archive SELECT * FROM moved; SELECT 1",
+            "readonly": true
+        },
+        "delete_users": {
+            "query": "DELETE FROM users"
+        }
+    }))
+    .unwrap();
+
+    assert_eq!(
+        queries.warnings,
+        vec![
+            "query 'archive_users' is declared readonly but statement looks like a write: WITH moved AS (DELETE FROM users RETURNING *) INSERT INTO archive SELECT * FROM moved"
+        ]
+    );
+}
+
+#[test]
+fn test_readonly_must_be_boolean() {
+    let err = QueryDefinitions::from_json(serde_json::json!({
+        "list_users": {
+            "query": "SELECT id FROM users",
+            "readonly": "yes"
+        }
+    }))
+    .unwrap_err();
+    match err.downcast::<JankenError>().unwrap() {
+        JankenError::ParameterTypeMismatch { data } => {
+            assert_eq!(
+                error_meta(&data, M_EXPECTED).unwrap(),
+                "boolean for readonly"
+            );
+            assert_eq!(error_meta(&data, M_GOT).unwrap(), "list_users: \"yes\"");
+        }
+        other => panic!("Expected ParameterTypeMismatch, got: {other:?}"),
+    }
+}
@@ -0,0 +1,221 @@
+use jankensqlhub::{
+    JankenError, M_EXPECTED, M_MAX_ROWS, M_SQL, QueryDefinitions, RowLimit, RowLimitAction,
+    error_meta, query_run_sqlite, query_stream_sqlite,
+};
+use rusqlite::Connection;
+
+fn definitions() -> QueryDefinitions {
+    QueryDefinitions::from_json(serde_json::json!({
+        "all_numbers": {
+            "query": "SELECT n FROM numbers ORDER BY n",
+            "returns": ["n"]
+        },
+        "first_numbers": {
+            "query": "SELECT n FROM numbers ORDER BY n",
+            "returns": ["n"],
+            "max_rows": {"rows": 3, "on_exceed": "truncate"}
+        },
+        "strict_numbers": {
+            "query": "SELECT n FROM numbers WHERE n <= @upto ORDER BY n",
+            "returns": ["n"],
+            "args": {"upto": {"type": "integer"}},
+            "max_rows": 3
+        },
+        "two_sets": {
+            "query": "SELECT n FROM numbers WHERE n <= 2; SELECT n FROM numbers",
+            "returns": [["n"], ["n"]],
+            "max_rows": {"rows": 2, "on_exceed": "truncate"}
+        }
+    }))
+    .unwrap()
+}
+
+fn setup_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE numbers (n INTEGER);
+         INSERT INTO numbers VALUES (1), (2), (3), (4), (5);",
+    )
+    .unwrap();
+    conn
+}
+
+fn numbers(data: &[serde_json::Value]) -> Vec<i64> {
+    data.iter().map(|row| row["n"].as_i64().unwrap()).collect()
+}
+
+#[test]
+fn test_max_rows_truncates_or_fails() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let result =
+        query_run_sqlite(&mut conn, &queries, "first_numbers", &serde_json::json!({})).unwrap();
+    assert_eq!(numbers(&result.data), vec![1, 2, 3]);
+    assert!(result.truncated);
+
+    // Exactly max_rows rows are not truncated
+    let params = serde_json::json!({"upto": 3});
+    let result = query_run_sqlite(&mut conn, &queries, "strict_numbers", &params).unwrap();
+    assert_eq!(numbers(&result.data), vec![1, 2, 3]);
+    assert!(!result.truncated);
+
+    let params = serde_json::json!({"upto": 4});
+    let err = query_run_sqlite(&mut conn, &queries, "strict_numbers", &params).unwrap_err();
+    match err.downcast::<JankenError>().unwrap() {
+        JankenError::RowLimitExceeded { data } => {
+            assert_eq!(error_meta(&data, M_MAX_ROWS).unwrap(), "3");
+            assert_eq!(
+                error_meta(&data, M_SQL).unwrap(),
+                "SELECT n FROM numbers WHERE n <= :upto ORDER BY n"
+            );
+        }
+        other => panic!("Expected RowLimitExceeded, got {other:?}"),
+    }
+
+    // Each result set of a multi-statement query is limited on its own
+    let result = query_run_sqlite(&mut conn, &queries, "two_sets", &serde_json::json!({})).unwrap();
+    assert_eq!(numbers(&result.result_sets[0]), vec![1, 2]);
+    assert_eq!(numbers(&result.result_sets[1]), vec![1, 2]);
+    assert!(result.truncated);
+}
+
+#[test]
+fn test_default_max_rows_applies_to_queries_without_their_own() {
+    let mut queries = definitions();
+    queries.set_default_max_rows(RowLimit {
+        max_rows: 4,
+        on_exceed: RowLimitAction::Truncate,
+    });
+    let mut conn = setup_db();
+
+    let result =
+        query_run_sqlite(&mut conn, &queries, "all_numbers", &serde_json::json!({})).unwrap();
+    assert_eq!(numbers(&result.data), vec![1, 2, 3, 4]);
+    assert!(result.truncated);
+
+    // A query's own max_rows wins over the default
+    let result =
+        query_run_sqlite(&mut conn, &queries, "first_numbers", &serde_json::json!({})).unwrap();
+    assert_eq!(numbers(&result.data), vec![1, 2, 3]);
+
+    // Streaming delivers every row
+    let mut streamed = Vec::new();
+    query_stream_sqlite(
+        &mut conn,
+        &queries,
+        "all_numbers",
+        &serde_json::json!({}),
+        |row| {
+            streamed.push(row);
+            Ok(())
+        },
+    )
+    .unwrap();
+    assert_eq!(streamed.len(), 5);
+}
+
+#[test]
+fn test_max_rows_leaves_keyset_pages_alone() {
+    let mut queries = QueryDefinitions::from_json(serde_json::json!({
+        "pages_truncate": {
+            "query": "SELECT n FROM numbers",
+            "returns": ["n"],
+            "paginate": {"mode": "keyset", "keys": [{"column": "n"}], "max_page_size": 10},
+            "max_rows": {"rows": 2, "on_exceed": "truncate"}
+        },
+        "pages_error": {
+            "query": "SELECT n FROM numbers",
+            "returns": ["n"],
+            "paginate": {"mode": "keyset", "keys": [{"column": "n"}], "max_page_size": 10},
+            "max_rows": 2
+        },
+        "pages_default": {
+            "query": "SELECT n FROM numbers",
+            "returns": ["n"],
+            "paginate": {"mode": "keyset", "keys": [{"column": "n"}], "max_page_size": 10}
+        }
+    }))
+    .unwrap();
+    queries.set_default_max_rows(RowLimit {
+        max_rows: 2,
+        on_exceed: RowLimitAction::Error,
+    });
+    let mut conn = setup_db();
+
+    // Full pages of max_rows rows still read the lookahead row and get a next cursor
+    for query_name in ["pages_truncate", "pages_error", "pages_default"] {
+        let mut pages = Vec::new();
+        let mut params = serde_json::json!({"page": {"limit": 2}});
+        loop {
+            let result = query_run_sqlite(&mut conn, &queries, query_name, &params).unwrap();
+            assert!(!result.truncated);
+            pages.push(numbers(&result.data));
+            let Some(cursor) = result.next_cursor else {
+                break;
+            };
+            params = serde_json::json!({"page": {"limit": 2, "cursor": cursor}});
+        }
+        assert_eq!(pages, vec![vec![1, 2], vec![3, 4], vec![5]], "{query_name}");
+    }
+}
+
+#[test]
+fn test_max_rows_truncate_runs_every_write_chunk() {
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "add_numbers": {
+            "query": "INSERT INTO numbers (n) VALUES *[rows] RETURNING n",
+            "returns": ["n"],
+            "args": {"rows": {"fields": [{"name": "n", "type": "integer"}], "chunk_size": 2}},
+            "max_rows": {"rows": 2, "on_exceed": "truncate"}
+        }
+    }))
+    .unwrap();
+    let mut conn = setup_db();
+
+    let rows: Vec<_> = (10..16).map(|n| serde_json::json!({"n": n})).collect();
+    let params = serde_json::json!({"rows": rows});
+    let result = query_run_sqlite(&mut conn, &queries, "add_numbers", &params).unwrap();
+    assert_eq!(numbers(&result.data), vec![10, 11]);
+    assert!(result.truncated);
+    assert_eq!(result.sql_statements.len(), 3);
+
+    // Only the returned rows are limited: every chunk of the insert is stored
+    let stored: i64 = conn
+        .query_row("SELECT COUNT(*) FROM numbers WHERE n >= 10", [], |row| {
+            row.get(0)
+        })
+        .unwrap();
+    assert_eq!(stored, 6);
+}
+
+#[test]
+fn test_max_rows_definition_errors() {
+    for (max_rows, expected) in [
+        (
+            serde_json::json!(0),
+            "positive integer or {\"rows\", \"on_exceed\"} object for max_rows",
+        ),
+        (
+            serde_json::json!({"rows": 5, "limit": 1}),
+            "positive integer or {\"rows\", \"on_exceed\"} object for max_rows",
+        ),
+        (
+            serde_json::json!({"rows": 5, "on_exceed": "drop"}),
+            "max_rows on_exceed error or truncate",
+        ),
+    ] {
+        let err = QueryDefinitions::from_json(serde_json::json!({"q": {
+            "query": "SELECT n FROM numbers",
+            "returns": ["n"],
+            "max_rows": max_rows
+        }}))
+        .unwrap_err();
+        match err.downcast::<JankenError>().unwrap() {
+            JankenError::ParameterTypeMismatch { data } => {
+                assert_eq!(error_meta(&data, M_EXPECTED).unwrap(), expected);
+            }
+            other => panic!("Expected ParameterTypeMismatch, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use jankensqlhub::{
+    JankenError, M_EXPECTED, M_GOT, QueryDefinitions, SearchMode, error_meta, like_pattern,
+    query_run_sqlite,
+};
+use rusqlite::Connection;
+
+fn definitions() -> QueryDefinitions {
+    QueryDefinitions::from_json(serde_json::json!({
+        "find_contains": {
+            "query": "SELECT id FROM products WHERE name LIKE @q ORDER BY id",
+            "returns": ["id"],
+            "args": { "q": { "type": "search", "range": [1, 20] } }
+        },
+        "find_prefix": {
+            "query": "SELECT id FROM products WHERE name LIKE @q ORDER BY id",
+            "returns": ["id"],
+            "args": { "q": { "type": "search", "mode": "prefix" } }
+        },
+        "find_suffix": {
+            "query": "SELECT id FROM products WHERE name LIKE @q OR name = '@q' ORDER BY id",
+            "returns": ["id"],
+            "args": { "q": { "type": "search", "mode": "suffix" } }
+        },
+        "find_excluding": {
+            "query": "SELECT id FROM products WHERE name not like @q ORDER BY id",
+            "returns": ["id"],
+            "args": { "q": { "type": "search" } }
+        }
+    }))
+    .unwrap()
+}
+
+fn setup_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE products (id INTEGER PRIMARY KEY, name TEXT);
+         INSERT INTO products VALUES
+           (1, '50%_off coupon'),
+           (2, '50 percent off'),
+           (3, 'coupon 50%_off'),
+           (4, 'bang! deal'),
+           (5, '500 off');",
+    )
+    .unwrap();
+    conn
+}
+
+fn ids(data: &[serde_json::Value]) -> Vec<i64> {
+    data.iter().map(|row| row["id"].as_i64().unwrap()).collect()
+}
+
+fn type_mismatch(err: anyhow::Error) -> (String, String) {
+    match err.downcast::<JankenError>().unwrap() {
+        JankenError::ParameterTypeMismatch { data } => (
+            error_meta(&data, M_EXPECTED).unwrap(),
+            error_meta(&data, M_GOT).unwrap(),
+        ),
+        other => panic!("Expected ParameterTypeMismatch, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_like_pattern_escapes_wildcards() {
+    assert_eq!(like_pattern("50%_off", SearchMode::Contains), "%50!%!_off%");
+    assert_eq!(like_pattern("bang!", SearchMode::Prefix), "bang!!%");
+    assert_eq!(like_pattern("off", SearchMode::Suffix), "%off");
+}
+
+#[test]
+fn test_search_matches_term_literally() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let params = serde_json::json!({"q": "50%_off"});
+    let result = query_run_sqlite(&mut conn, &queries, "find_contains", &params).unwrap();
+    assert_eq!(ids(&result.data), vec![1, 3]);
+    assert_eq!(
+        result.sql_statements,
+        vec!["SELECT id FROM products WHERE name LIKE :q ESCAPE '!' ORDER BY id"]
+    );
+
+    // The escape character itself is matched literally
+    let params = serde_json::json!({"q": "!"});
+    let result = query_run_sqlite(&mut conn, &queries, "find_contains", &params).unwrap();
+    assert_eq!(ids(&result.data), vec![4]);
+
+    let params = serde_json::json!({"q": "50%"});
+    let result = query_run_sqlite(&mut conn, &queries, "find_prefix", &params).unwrap();
+    assert_eq!(ids(&result.data), vec![1]);
+
+    // Quoted '@q' is a string literal, so it gets no ESCAPE clause
+    let params = serde_json::json!({"q": "_off"});
+    let result = query_run_sqlite(&mut conn, &queries, "find_suffix", &params).unwrap();
+    assert_eq!(ids(&result.data), vec![3]);
+    assert_eq!(
+        result.sql_statements,
+        vec!["SELECT id FROM products WHERE name LIKE :q ESCAPE '!' OR name = '@q' ORDER BY id"]
+    );
+
+    // The operator is matched case-insensitively, NOT LIKE included
+    let params = serde_json::json!({"q": "off"});
+    let result = query_run_sqlite(&mut conn, &queries, "find_excluding", &params).unwrap();
+    assert_eq!(ids(&result.data), vec![4]);
+    assert_eq!(
+        result.sql_statements,
+        vec!["SELECT id FROM products WHERE name not like :q ESCAPE '!' ORDER BY id"]
+    );
+}
+
+#[test]
+fn test_search_validation_errors() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let params = serde_json::json!({"q": 50});
+    let err = query_run_sqlite(&mut conn, &queries, "find_contains", &params).unwrap_err();
+    assert_eq!(type_mismatch(err), ("search".to_string(), "50".to_string()));
+
+    let params = serde_json::json!({"q": ""});
+    let err = query_run_sqlite(&mut conn, &queries, "find_contains", &params).unwrap_err();
+    assert_eq!(
+        type_mismatch(err),
+        (
+            "string length between 1 and 20 characters".to_string(),
+            "0 characters".to_string()
+        )
+    );
+
+    let err = QueryDefinitions::from_json(serde_json::json!({
+        "bad_mode": {
+            "query": "SELECT id FROM products WHERE name LIKE @q",
+            "args": { "q": { "type": "search", "mode": "anywhere" } }
+        }
+    }))
+    .unwrap_err();
+    assert_eq!(
+        type_mismatch(err),
+        (
+            "search mode prefix, suffix or contains".to_string(),
+            "anywhere".to_string()
+        )
+    );
+
+    let err = QueryDefinitions::from_json(serde_json::json!({
+        "mode_on_string": {
+            "query": "SELECT id FROM products WHERE name LIKE @q",
+            "args": { "q": { "type": "string", "mode": "prefix" } }
+        }
+    }))
+    .unwrap_err();
+    assert_eq!(
+        type_mismatch(err),
+        (
+            "type search for parameter with 'mode'".to_string(),
+            "q".to_string()
+        )
+    );
+
+    // A search parameter is only escaped as the pattern of a LIKE without its own ESCAPE
+    for sql in [
+        "SELECT id FROM products WHERE name = @q",
+        "SELECT id FROM products WHERE name LIKE @q OR name = @q",
+        "SELECT id FROM products WHERE name LIKE @q ESCAPE '#'",
+        "SELECT id FROM products WHERE name LIKE @q escape '#'",
+    ] {
+        let err = QueryDefinitions::from_json(serde_json::json!({
+            "misplaced": { "query": sql, "args": { "q": { "type": "search" } } }
+        }))
+        .unwrap_err();
+        assert_eq!(
+            type_mismatch(err),
+            (
+                "search parameter as the pattern of LIKE or ILIKE without an ESCAPE clause"
+                    .to_string(),
+                "q".to_string()
+            ),
+            "{sql}"
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use jankensqlhub::{
+    JankenError, M_EXPECTED, M_GOT, M_PARAM_NAME, QueryDefinitions, error_meta, query_run_sqlite,
+};
+use rusqlite::Connection;
+
+fn definitions() -> QueryDefinitions {
+    QueryDefinitions::from_json(serde_json::json!({
+        "list_people": {
+            "query": "SELECT name, age FROM people WHERE age >= @min_age ^[sort] LIMIT 10",
+            "returns": ["name", "age"],
+            "args": {
+                "min_age": { "type": "integer" },
+                "sort": { "enum": ["name", "age"], "range": [0, 2] }
+            }
+        }
+    }))
+    .unwrap()
+}
+
+fn setup_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE people (name TEXT, age INTEGER);
+         INSERT INTO people VALUES ('bob', 30), ('amy', NULL), ('cat', 30), ('dan', 25);",
+    )
+    .unwrap();
+    conn
+}
+
+fn names(data: &[serde_json::Value]) -> Vec<&str> {
+    data.iter()
+        .map(|row| row["name"].as_str().unwrap())
+        .collect()
+}
+
+fn type_mismatch(err: anyhow::Error) -> (String, String) {
+    match err.downcast::<JankenError>().unwrap() {
+        JankenError::ParameterTypeMismatch { data } => (
+            error_meta(&data, M_EXPECTED).unwrap(),
+            error_meta(&data, M_GOT).unwrap(),
+        ),
+        other => panic!("Expected ParameterTypeMismatch, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_sort_expands_to_order_by_clause() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let params = serde_json::json!({
+        "min_age": 0,
+        "sort": [{"field": "age", "dir": "DESC", "nulls": "last"}, {"field": "name"}]
+    });
+    let result = query_run_sqlite(&mut conn, &queries, "list_people", &params).unwrap();
+    assert_eq!(
+        result.sql_statements,
+        vec![
+            "SELECT name, age FROM people WHERE age >= :min_age ORDER BY age DESC NULLS LAST, name ASC LIMIT 10"
+        ]
+    );
+    assert_eq!(names(&result.data), vec!["bob", "cat", "dan"]);
+
+    let params = serde_json::json!({
+        "min_age": -1,
+        "sort": [{"field": "name", "dir": "desc"}]
+    });
+    let result = query_run_sqlite(&mut conn, &queries, "list_people", &params).unwrap();
+    assert_eq!(names(&result.data), vec!["dan", "cat", "bob"]);
+
+    // No sort keys render no ORDER BY clause
+    let params = serde_json::json!({"min_age": 26, "sort": []});
+    let result = query_run_sqlite(&mut conn, &queries, "list_people", &params).unwrap();
+    assert_eq!(
+        result.sql_statements,
+        vec!["SELECT name, age FROM people WHERE age >= :min_age  LIMIT 10"]
+    );
+}
+
+#[test]
+fn test_sort_validation_errors() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let run = |conn: &mut Connection, sort: serde_json::Value| {
+        query_run_sqlite(
+            conn,
+            &queries,
+            "list_people",
+            &serde_json::json!({ "min_age": 0, "sort": sort }),
+        )
+        .unwrap_err()
+    };
+
+    // Columns outside the allowlist are rejected, which blocks injection through the field
+    let err = run(
+        &mut conn,
+        serde_json::json!([{"field": "age; DROP TABLE people"}]),
+    );
+    assert_eq!(
+        type_mismatch(err),
+        (
+            "one of [\"name\", \"age\"] for sort field at index 0".to_string(),
+            "\"age; DROP TABLE people\"".to_string()
+        )
+    );
+
+    let err = run(
+        &mut conn,
+        serde_json::json!([{"field": "age", "dir": "sideways"}]),
+    );
+    assert_eq!(
+        type_mismatch(err),
+        (
+            "asc or desc for dir at index 0".to_string(),
+            "\"sideways\"".to_string()
+        )
+    );
+
+    let err = run(&mut conn, serde_json::json!([{"field": "age", "nulls": 1}]));
+    assert_eq!(type_mismatch(err).0, "first or last for nulls at index 0");
+
+    let err = run(
+        &mut conn,
+        serde_json::json!([{"field": "age", "collate": "nocase"}]),
+    );
+    assert_eq!(
+        type_mismatch(err),
+        (
+            "sort key with field, dir and nulls at index 0".to_string(),
+            "collate".to_string()
+        )
+    );
+
+    let err = run(&mut conn, serde_json::json!(["age"]));
+    assert_eq!(type_mismatch(err).0, "object at index 0");
+
+    let err = run(
+        &mut conn,
+        serde_json::json!([{"field": "age"}, {"field": "name"}, {"field": "age"}]),
+    );
+    assert_eq!(
+        type_mismatch(err),
+        (
+            "sort size between 0 and 2 keys".to_string(),
+            "3 keys".to_string()
+        )
+    );
+
+    let err = run(&mut conn, serde_json::json!([{"dir": "asc"}]));
+    match err.downcast::<JankenError>().unwrap() {
+        JankenError::ParameterNotProvided { data } => {
+            assert_eq!(error_meta(&data, M_PARAM_NAME).unwrap(), "sort[0].field");
+        }
+        other => panic!("Expected ParameterNotProvided, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_sort_definition_requires_column_allowlist() {
+    for args in [
+        serde_json::json!({}),
+        serde_json::json!({"sort": {"enum": [1, 2]}}),
+    ] {
+        let err = QueryDefinitions::from_json(serde_json::json!({"q": {
+            "query": "SELECT * FROM people ^[sort]",
+            "args": args
+        }}))
+        .unwrap_err();
+        assert_eq!(
+            type_mismatch(err),
+            (
+                "'enum' allowlist of column names in args for sort parameter".to_string(),
+                "sort".to_string()
+            )
+        );
+    }
+}
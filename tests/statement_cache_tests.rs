@@ -0,0 +1,62 @@
+use jankensqlhub::{
+    QueryDefinitions, SqliteStatementCache, StatementCacheStats, query_run_sqlite_cached,
+};
+use rusqlite::Connection;
+
+#[test]
+fn test_sqlite_statement_cache_counts_hits_and_misses() {
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "add_item": {
+            "query": "INSERT INTO items (name) VALUES (@name)"
+        },
+        "find_items": {
+            "query": "SELECT name FROM items WHERE id IN :[ids] ORDER BY id",
+            "returns": ["name"],
+            "args": { "ids": { "itemtype": "integer" } }
+        }
+    }))
+    .unwrap();
+    let mut conn = Connection::open_in_memory().unwrap();
+    conn.execute("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT)", [])
+        .unwrap();
+    let cache = SqliteStatementCache::new(2);
+
+    for name in ["a", "b", "c"] {
+        let params = serde_json::json!({ "name": name });
+        query_run_sqlite_cached(&mut conn, &cache, &queries, "add_item", &params).unwrap();
+    }
+    assert_eq!(
+        cache.stats(),
+        StatementCacheStats {
+            hits: 2,
+            misses: 1,
+            len: 1,
+            capacity: 2
+        }
+    );
+
+    // Each list length renders different SQL; the least recently used statement is evicted
+    let params = serde_json::json!({ "ids": [1, 3] });
+    let result =
+        query_run_sqlite_cached(&mut conn, &cache, &queries, "find_items", &params).unwrap();
+    assert_eq!(
+        result.data,
+        vec![
+            serde_json::json!({ "name": "a" }),
+            serde_json::json!({ "name": "c" })
+        ]
+    );
+    let params = serde_json::json!({ "ids": [2] });
+    query_run_sqlite_cached(&mut conn, &cache, &queries, "find_items", &params).unwrap();
+    let params = serde_json::json!({ "name": "d" });
+    query_run_sqlite_cached(&mut conn, &cache, &queries, "add_item", &params).unwrap();
+    assert_eq!(
+        cache.stats(),
+        StatementCacheStats {
+            hits: 2,
+            misses: 4,
+            len: 2,
+            capacity: 2
+        }
+    );
+}
@@ -0,0 +1,111 @@
+use jankensqlhub::{
+    JankenError, M_EXPECTED, M_GOT, QueryDefinitions, error_meta, query_stream_sqlite,
+};
+use rusqlite::Connection;
+use serde_json::json;
+
+fn setup_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute("CREATE TABLE users (id INTEGER, name TEXT)", [])
+        .unwrap();
+    conn.execute(
+        "INSERT INTO users VALUES (1, 'Alice'), (2, 'Bob'), (3, 'Carol')",
+        [],
+    )
+    .unwrap();
+    conn
+}
+
+fn stream_definitions() -> QueryDefinitions {
+    QueryDefinitions::from_json(json!({
+        "list_users": {
+            "query": "SELECT id, name FROM users WHERE id >= @min_id ORDER BY id",
+            "returns": ["id", "name"],
+            "args": {"min_id": {"type": "integer"}}
+        },
+        "delete_users": {
+            "query": "DELETE FROM users"
+        }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_stream_sqlite_delivers_rows_in_order() {
+    let mut conn = setup_db();
+    let queries = stream_definitions();
+
+    let mut rows = Vec::new();
+    let result = query_stream_sqlite(
+        &mut conn,
+        &queries,
+        "list_users",
+        &json!({"min_id": 2}),
+        |row| {
+            rows.push(row);
+            Ok(())
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        rows,
+        vec![
+            json!({"id": 2, "name": "Bob"}),
+            json!({"id": 3, "name": "Carol"})
+        ]
+    );
+    assert_eq!(
+        result.sql_statements,
+        vec!["SELECT id, name FROM users WHERE id >= :min_id ORDER BY id"]
+    );
+    assert!(result.data.is_empty());
+}
+
+#[test]
+fn test_stream_sqlite_callback_error_stops_iteration() {
+    let mut conn = setup_db();
+    let queries = stream_definitions();
+
+    let mut seen = 0;
+    let result = query_stream_sqlite(
+        &mut conn,
+        &queries,
+        "list_users",
+        &json!({"min_id": 1}),
+        |_row| {
+            seen += 1;
+            Err(anyhow::anyhow!("client disconnected"))
+        },
+    );
+
+    assert_eq!(result.unwrap_err().to_string(), "client disconnected");
+    assert_eq!(seen, 1);
+}
+
+#[test]
+fn test_stream_sqlite_rejects_query_without_returns() {
+    let mut conn = setup_db();
+    let queries = stream_definitions();
+
+    let result = query_stream_sqlite(&mut conn, &queries, "delete_users", &json!({}), |_| Ok(()));
+
+    let err = result.unwrap_err();
+    let Ok(JankenError::ParameterTypeMismatch { data }) = err.downcast::<JankenError>() else {
+        panic!("Expected ParameterTypeMismatch");
+    };
+    assert_eq!(
+        error_meta(&data, M_EXPECTED).unwrap(),
+        "query with returns for streaming"
+    );
+    assert_eq!(
+        error_meta(&data, M_GOT).unwrap(),
+        "delete_users has no returns"
+    );
+
+    // The rejected query must not have run
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(count, 3);
+}
@@ -0,0 +1,268 @@
+use jankensqlhub::{
+    IsolationLevel, JankenError, M_EXPECTED, M_GOT, QueryDefinitions, RetryPolicy,
+    TransactionBehavior, TransactionOptions, error_meta, query_run_sqlite,
+};
+use rusqlite::Connection;
+use std::time::Duration;
+
+#[test]
+fn test_transaction_options_parsing() {
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "transfer": {
+            "query": "UPDATE accounts SET balance = balance - 1",
+            "transaction": {
+                "behavior": "immediate",
+                "isolation": "repeatable_read",
+                "read_only": false,
+                "deferrable": true,
+                "retry": { "max_attempts": 5, "backoff_ms": 20 }
+            }
+        },
+        "report": {
+            "query": "SELECT id FROM accounts",
+            "returns": ["id"],
+            "transaction": { "isolation": "SERIALIZABLE", "read_only": true }
+        },
+        "plain": {
+            "query": "SELECT id FROM accounts",
+            "returns": ["id"]
+        }
+    }))
+    .unwrap();
+
+    assert_eq!(
+        queries.definitions["transfer"].transaction,
+        TransactionOptions {
+            behavior: TransactionBehavior::Immediate,
+            isolation: Some(IsolationLevel::RepeatableRead),
+            read_only: false,
+            deferrable: true,
+            retry: Some(RetryPolicy {
+                max_attempts: 5,
+                backoff: Duration::from_millis(20),
+                max_backoff: Duration::from_secs(1),
+            }),
+        }
+    );
+    assert_eq!(
+        queries.definitions["report"].transaction,
+        TransactionOptions {
+            behavior: TransactionBehavior::Deferred,
+            isolation: Some(IsolationLevel::Serializable),
+            read_only: true,
+            deferrable: false,
+            retry: None,
+        }
+    );
+    assert_eq!(
+        queries.definitions["plain"].transaction,
+        TransactionOptions::default()
+    );
+}
+
+#[test]
+fn test_transaction_options_errors() {
+    let cases = [
+        (
+            serde_json::json!("immediate"),
+            "object for transaction",
+            "q: \"immediate\"",
+        ),
+        (
+            serde_json::json!({"behavior": "lazy"}),
+            "transaction behavior deferred, immediate or exclusive",
+            "lazy",
+        ),
+        (
+            serde_json::json!({"isolation": "snapshot"}),
+            "isolation level read_uncommitted, read_committed, repeatable_read or serializable",
+            "snapshot",
+        ),
+        (
+            serde_json::json!({"isolation": 3}),
+            "string for transaction isolation",
+            "q: 3",
+        ),
+        (
+            serde_json::json!({"retry": {"max_attempts": 0}}),
+            "at least 1 for retry max_attempts",
+            "q: 0",
+        ),
+        (
+            serde_json::json!({"retry": {"backoff_ms": -5}}),
+            "non-negative integer for retry backoff_ms",
+            "q: -5",
+        ),
+        (
+            serde_json::json!({"deferrable": "yes"}),
+            "boolean for transaction deferrable",
+            "q: \"yes\"",
+        ),
+    ];
+
+    for (transaction, expected, got) in cases {
+        let err = QueryDefinitions::from_json(serde_json::json!({
+            "q": { "query": "SELECT 1", "transaction": transaction }
+        }))
+        .unwrap_err();
+        match err.downcast::<JankenError>().unwrap() {
+            JankenError::ParameterTypeMismatch { data } => {
+                assert_eq!(error_meta(&data, M_EXPECTED).unwrap(), expected);
+                assert_eq!(error_meta(&data, M_GOT).unwrap(), got);
+            }
+            other => panic!("Expected ParameterTypeMismatch, got: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_sqlite_transaction_behavior_applied() {
+    let path = std::env::temp_dir().join(format!(
+        "janken_transaction_behavior_{}.db",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "count_deferred": {
+            "query": "SELECT COUNT(*) AS total FROM accounts",
+            "returns": ["total"]
+        },
+        "count_immediate": {
+            "query": "SELECT COUNT(*) AS total FROM accounts",
+            "returns": ["total"],
+            "transaction": { "behavior": "immediate" }
+        }
+    }))
+    .unwrap();
+
+    let mut conn = Connection::open(&path).unwrap();
+    conn.busy_timeout(Duration::ZERO).unwrap();
+    conn.execute("CREATE TABLE accounts (id INTEGER PRIMARY KEY)", [])
+        .unwrap();
+
+    // Another connection holds the write lock
+    let other = Connection::open(&path).unwrap();
+    other.execute_batch("BEGIN IMMEDIATE").unwrap();
+
+    // A deferred transaction only needs a read lock for the SELECT
+    let result = query_run_sqlite(
+        &mut conn,
+        &queries,
+        "count_deferred",
+        &serde_json::json!({}),
+    )
+    .unwrap();
+    assert_eq!(result.data, vec![serde_json::json!({"total": 0})]);
+
+    // An immediate transaction asks for the write lock when it begins
+    let err = query_run_sqlite(
+        &mut conn,
+        &queries,
+        "count_immediate",
+        &serde_json::json!({}),
+    )
+    .unwrap_err();
+    let sqlite_err = err.downcast::<rusqlite::Error>().unwrap();
+    assert_eq!(
+        sqlite_err.sqlite_error_code(),
+        Some(rusqlite::ErrorCode::DatabaseBusy)
+    );
+
+    other.execute_batch("COMMIT").unwrap();
+    query_run_sqlite(
+        &mut conn,
+        &queries,
+        "count_immediate",
+        &serde_json::json!({}),
+    )
+    .unwrap();
+
+    drop(other);
+    drop(conn);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_sqlite_retry_on_busy() {
+    let path = std::env::temp_dir().join(format!("janken_retry_busy_{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "add_account": {
+            "query": "INSERT INTO accounts (id) VALUES (@id)",
+            "args": { "id": { "type": "integer" } },
+            "transaction": { "retry": { "max_attempts": 50, "backoff_ms": 5, "max_backoff_ms": 20 } }
+        },
+        "add_account_once": {
+            "query": "INSERT INTO accounts (id) VALUES (@id)",
+            "args": { "id": { "type": "integer" } }
+        }
+    }))
+    .unwrap();
+
+    let mut conn = Connection::open(&path).unwrap();
+    conn.busy_timeout(Duration::ZERO).unwrap();
+    conn.execute("CREATE TABLE accounts (id INTEGER PRIMARY KEY)", [])
+        .unwrap();
+
+    let other = Connection::open(&path).unwrap();
+    other.execute_batch("BEGIN IMMEDIATE").unwrap();
+
+    // Without a retry policy the busy error is returned straight away
+    let err = query_run_sqlite(
+        &mut conn,
+        &queries,
+        "add_account_once",
+        &serde_json::json!({"id": 1}),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.downcast::<rusqlite::Error>()
+            .unwrap()
+            .sqlite_error_code(),
+        Some(rusqlite::ErrorCode::DatabaseBusy)
+    );
+
+    // The lock is released while the retry policy keeps re-running the transaction
+    let releaser = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(100));
+        other.execute_batch("COMMIT").unwrap();
+    });
+    let result = query_run_sqlite(
+        &mut conn,
+        &queries,
+        "add_account",
+        &serde_json::json!({"id": 1}),
+    )
+    .unwrap();
+    releaser.join().unwrap();
+    assert!(result.attempts > 1);
+
+    // Non-transient errors are not retried
+    let err = query_run_sqlite(
+        &mut conn,
+        &queries,
+        "add_account",
+        &serde_json::json!({"id": 1}),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.downcast::<rusqlite::Error>()
+            .unwrap()
+            .sqlite_error_code(),
+        Some(rusqlite::ErrorCode::ConstraintViolation)
+    );
+
+    let result = query_run_sqlite(
+        &mut conn,
+        &queries,
+        "add_account",
+        &serde_json::json!({"id": 2}),
+    )
+    .unwrap();
+    assert_eq!(result.attempts, 1);
+
+    drop(conn);
+    let _ = std::fs::remove_file(&path);
+}
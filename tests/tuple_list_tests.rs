@@ -0,0 +1,137 @@
+use jankensqlhub::{
+    JankenError, M_EXPECTED, M_GOT, M_PARAM_NAME, QueryDefinitions, error_meta, query_run_sqlite,
+};
+use rusqlite::Connection;
+
+fn definitions() -> QueryDefinitions {
+    QueryDefinitions::from_json(serde_json::json!({
+        "find_stock": {
+            "query": "SELECT tenant_id, sku, qty FROM stock WHERE (tenant_id, sku) IN :[keys] ORDER BY tenant_id, sku",
+            "returns": ["tenant_id", "sku", "qty"],
+            "args": {
+                "keys": {
+                    "range": [1, 100],
+                    "fields": [
+                        { "name": "tenant_id", "type": "integer", "range": [1, 1000] },
+                        { "name": "sku", "pattern": "^[a-z]+$" }
+                    ]
+                }
+            }
+        }
+    }))
+    .unwrap()
+}
+
+fn setup_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE stock (tenant_id INTEGER, sku TEXT, qty INTEGER);
+         INSERT INTO stock VALUES (1, 'a', 10), (1, 'b', 11), (2, 'a', 20), (2, 'b', 21);",
+    )
+    .unwrap();
+    conn
+}
+
+fn expected_error(err: anyhow::Error) -> String {
+    match err.downcast::<JankenError>().unwrap() {
+        JankenError::ParameterTypeMismatch { data } => {
+            format!(
+                "{} | {}",
+                error_meta(&data, M_EXPECTED).unwrap(),
+                error_meta(&data, M_GOT).unwrap()
+            )
+        }
+        other => panic!("Expected ParameterTypeMismatch, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_tuple_list_matches_composite_keys() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    // Items can be positional arrays or objects keyed by field name
+    let params = serde_json::json!({"keys": [[1, "b"], {"sku": "a", "tenant_id": 2}, [3, "a"]]});
+    let result = query_run_sqlite(&mut conn, &queries, "find_stock", &params).unwrap();
+
+    assert_eq!(
+        result.sql_statements,
+        vec![
+            "SELECT tenant_id, sku, qty FROM stock WHERE (tenant_id, sku) IN ((:keys_0_tenant_id, :keys_0_sku), (:keys_1_tenant_id, :keys_1_sku), (:keys_2_tenant_id, :keys_2_sku)) ORDER BY tenant_id, sku"
+        ]
+    );
+    assert_eq!(
+        result.data,
+        vec![
+            serde_json::json!({"tenant_id": 1, "sku": "b", "qty": 11}),
+            serde_json::json!({"tenant_id": 2, "sku": "a", "qty": 20})
+        ]
+    );
+}
+
+#[test]
+fn test_tuple_list_validation_errors() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let mut run = |keys: serde_json::Value| {
+        query_run_sqlite(
+            &mut conn,
+            &queries,
+            "find_stock",
+            &serde_json::json!({ "keys": keys }),
+        )
+        .unwrap_err()
+    };
+
+    assert_eq!(
+        expected_error(run(serde_json::json!([[1, "a"], [2]]))),
+        "array of 2 values at index 1 | [2]"
+    );
+    assert_eq!(
+        expected_error(run(serde_json::json!([["1", "a"]]))),
+        "integer for field tenant_id at index 0 | \"1\""
+    );
+    assert_eq!(
+        expected_error(run(serde_json::json!([[5000, "a"]]))),
+        "value between 1 and 1000 for field tenant_id at index 0 | 5000"
+    );
+    assert_eq!(
+        expected_error(run(serde_json::json!([[1, "A"]]))),
+        "string matching pattern '^[a-z]+$' for field sku at index 0 | A"
+    );
+    assert_eq!(
+        expected_error(run(serde_json::json!([1, 2]))),
+        "array or object at index 0 | 1"
+    );
+
+    match run(serde_json::json!([{"tenant_id": 1}]))
+        .downcast::<JankenError>()
+        .unwrap()
+    {
+        JankenError::ParameterNotProvided { data } => {
+            assert_eq!(error_meta(&data, M_PARAM_NAME).unwrap(), "keys[0].sku");
+        }
+        other => panic!("Expected ParameterNotProvided, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_tuple_list_definition_rejects_itemtype_with_fields() {
+    let err = QueryDefinitions::from_json(serde_json::json!({
+        "q": {
+            "query": "SELECT * FROM stock WHERE (tenant_id, sku) IN :[keys]",
+            "args": {
+                "keys": {
+                    "itemtype": "integer",
+                    "fields": [{ "name": "tenant_id", "type": "integer" }, { "name": "sku" }]
+                }
+            }
+        }
+    }))
+    .unwrap_err();
+    assert_eq!(
+        expected_error(err),
+        "either 'itemtype' or 'fields' for list parameter | keys"
+    );
+}
@@ -0,0 +1,153 @@
+use jankensqlhub::{
+    Dialect, JankenError, M_EXPECTED, M_GOT, M_PARAM_NAME, M_QUERY_NAME, ParameterValue,
+    QueryDefinitions, error_meta, query_run_sqlite,
+};
+use rusqlite::Connection;
+
+fn definitions() -> QueryDefinitions {
+    QueryDefinitions::from_json(serde_json::json!({
+        "find_users": {
+            "query": "SELECT id, name FROM #[source] WHERE id IN :[ids] AND name <> @name",
+            "returns": ["id", "name"],
+            "args": {
+                "source": { "enum": ["users"] },
+                "ids": { "itemtype": "integer" },
+                "name": { "range": [1, 10] }
+            }
+        },
+        "rename_user": {
+            "query": "UPDATE users SET name = @name WHERE id = @id; DELETE FROM users WHERE name = @name AND id <> @id",
+            "args": { "id": { "type": "integer" } }
+        }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_validate_matches_executed_statements() {
+    let queries = definitions();
+    let mut conn = Connection::open_in_memory().unwrap();
+    conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)", [])
+        .unwrap();
+
+    for (query_name, params) in [
+        (
+            "find_users",
+            serde_json::json!({"source": "users", "ids": [1, 2], "name": "bob"}),
+        ),
+        ("rename_user", serde_json::json!({"id": 1, "name": "amy"})),
+    ] {
+        let validated = queries
+            .validate(query_name, &params, Dialect::Sqlite)
+            .unwrap();
+        let result = query_run_sqlite(&mut conn, &queries, query_name, &params).unwrap();
+        assert_eq!(validated, result.statements);
+    }
+}
+
+#[test]
+fn test_validate_postgresql_dialect() {
+    let queries = definitions();
+    let params = serde_json::json!({"id": 7, "name": "amy"});
+
+    let statements = queries
+        .validate("rename_user", &params, Dialect::Postgresql)
+        .unwrap();
+
+    assert_eq!(statements.len(), 2);
+    assert_eq!(
+        statements[0].sql,
+        "UPDATE users SET name = $1 WHERE id = $2"
+    );
+    assert_eq!(
+        statements[1].sql,
+        "DELETE FROM users WHERE name = $1 AND id <> $2"
+    );
+    assert_eq!(
+        statements[1].render_sql(),
+        "DELETE FROM users WHERE name = 'amy' AND id <> 7"
+    );
+}
+
+#[test]
+fn test_validate_postgresql_pg_array_list() {
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "find_users": {
+            "query": "SELECT id FROM users WHERE id IN :[ids] AND id NOT IN :[skip]",
+            "returns": ["id"],
+            "args": {
+                "ids": { "itemtype": "integer", "pg_array": true },
+                "skip": { "itemtype": "integer" }
+            }
+        }
+    }))
+    .unwrap();
+
+    // Over the parameter limit, but bound as a single array in PostgreSQL
+    let ids: Vec<i64> = (0..70_000).collect();
+    let params = serde_json::json!({"ids": ids, "skip": [1, 2]});
+    let statements = queries
+        .validate("find_users", &params, Dialect::Postgresql)
+        .unwrap();
+    assert_eq!(statements.len(), 1);
+    assert_eq!(
+        statements[0].sql,
+        "SELECT id FROM users WHERE id = ANY($1) AND id NOT IN ($2, $3)"
+    );
+    assert_eq!(statements[0].parameters.len(), 3);
+    assert!(matches!(
+        &statements[0].parameters[0].value,
+        ParameterValue::Array { items, .. } if items.len() == 70_000
+    ));
+
+    // SQLite still expands the list into one placeholder per item
+    let params = serde_json::json!({"ids": [1, 2], "skip": [3]});
+    let statements = queries
+        .validate("find_users", &params, Dialect::Sqlite)
+        .unwrap();
+    assert_eq!(
+        statements[0].sql,
+        "SELECT id FROM users WHERE id IN (:ids_0, :ids_1) AND id NOT IN (:skip_0)"
+    );
+}
+
+#[test]
+fn test_validate_errors() {
+    let queries = definitions();
+
+    let err = queries
+        .validate("missing", &serde_json::json!({}), Dialect::Sqlite)
+        .unwrap_err();
+    match err {
+        JankenError::QueryNotFound { data } => {
+            assert_eq!(error_meta(&data, M_QUERY_NAME).unwrap(), "missing");
+        }
+        other => panic!("Expected QueryNotFound, got: {other:?}"),
+    }
+
+    let err = queries
+        .validate(
+            "rename_user",
+            &serde_json::json!({"id": 1}),
+            Dialect::Sqlite,
+        )
+        .unwrap_err();
+    match err {
+        JankenError::ParameterNotProvided { data } => {
+            assert_eq!(error_meta(&data, M_PARAM_NAME).unwrap(), "name");
+        }
+        other => panic!("Expected ParameterNotProvided, got: {other:?}"),
+    }
+
+    let params = serde_json::json!({"source": "accounts", "ids": [1], "name": "bob"});
+    let err = queries
+        .validate("find_users", &params, Dialect::Postgresql)
+        .unwrap_err();
+    match err {
+        JankenError::ParameterTypeMismatch { data } => {
+            assert_eq!(error_meta(&data, M_EXPECTED).unwrap(), "one of [\"users\"]");
+            assert_eq!(error_meta(&data, M_GOT).unwrap(), "\"accounts\"");
+        }
+        other => panic!("Expected ParameterTypeMismatch, got: {other:?}"),
+    }
+}
@@ -0,0 +1,299 @@
+use jankensqlhub::{
+    JankenError, M_EXPECTED, M_GOT, M_QUERY_NAME, QueryDefinitions, TransactionBehavior,
+    error_meta, workflow_run_sqlite,
+};
+use rusqlite::Connection;
+use std::time::Duration;
+
+fn definitions() -> QueryDefinitions {
+    QueryDefinitions::from_json(serde_json::json!({
+        "insert_order": {
+            "query": "INSERT INTO orders (customer) VALUES (@customer) RETURNING id",
+            "returns": ["id"]
+        },
+        "insert_item": {
+            "query": "INSERT INTO items (order_id, sku, quantity, status) VALUES (@order_id, @sku, @quantity, @status)",
+            "args": {
+                "order_id": { "type": "integer" },
+                "quantity": { "type": "integer", "range": [1, 100] }
+            }
+        },
+        "update_stock": {
+            "query": "UPDATE stock SET available = available - @quantity WHERE sku = @sku",
+            "args": { "quantity": { "type": "integer" } }
+        },
+        "place_order": {
+            "workflow": [
+                { "name": "order", "query": "insert_order" },
+                {
+                    "query": "insert_item",
+                    "params": {
+                        "order_id": { "$from": "/steps/order/data/0/id" },
+                        "status": "pending"
+                    }
+                },
+                { "query": "update_stock" }
+            ]
+        }
+    }))
+    .unwrap()
+}
+
+fn setup_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE orders (id INTEGER PRIMARY KEY, customer TEXT);
+         CREATE TABLE items (order_id INTEGER, sku TEXT, quantity INTEGER, status TEXT);
+         CREATE TABLE stock (sku TEXT PRIMARY KEY, available INTEGER CHECK (available >= 0));
+         INSERT INTO stock VALUES ('apple', 10);",
+    )
+    .unwrap();
+    conn
+}
+
+#[test]
+fn test_workflow_runs_steps_with_mapped_params() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    let params = serde_json::json!({"customer": "Alice", "sku": "apple", "quantity": 3});
+    let result = workflow_run_sqlite(&mut conn, &queries, "place_order", &params).unwrap();
+
+    let step_names: Vec<&str> = result.steps.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(step_names, vec!["order", "insert_item", "update_stock"]);
+    assert_eq!(
+        result.step("order").unwrap().data,
+        vec![serde_json::json!({"id": 1})]
+    );
+    assert_eq!(
+        result.step("insert_item").unwrap().statements[0].render_sql(),
+        "INSERT INTO items (order_id, sku, quantity, status) VALUES (1, 'apple', 3, 'pending')"
+    );
+
+    let item: (i64, String, i64, String) = conn
+        .query_row(
+            "SELECT order_id, sku, quantity, status FROM items",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .unwrap();
+    assert_eq!(item, (1, "apple".to_string(), 3, "pending".to_string()));
+    let available: i64 = conn
+        .query_row(
+            "SELECT available FROM stock WHERE sku = 'apple'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(available, 7);
+}
+
+#[test]
+fn test_workflow_failure_rolls_back_all_steps() {
+    let queries = definitions();
+    let mut conn = setup_db();
+
+    // The stock check constraint fails in the last step
+    let params = serde_json::json!({"customer": "Bob", "sku": "apple", "quantity": 50});
+    let err = workflow_run_sqlite(&mut conn, &queries, "place_order", &params).unwrap_err();
+    assert_eq!(
+        err.downcast::<rusqlite::Error>()
+            .unwrap()
+            .sqlite_error_code(),
+        Some(rusqlite::ErrorCode::ConstraintViolation)
+    );
+
+    let orders: i64 = conn
+        .query_row("SELECT COUNT(*) FROM orders", [], |row| row.get(0))
+        .unwrap();
+    let items: i64 = conn
+        .query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!((orders, items), (0, 0));
+
+    let err = workflow_run_sqlite(&mut conn, &queries, "missing", &params).unwrap_err();
+    match err.downcast::<JankenError>().unwrap() {
+        JankenError::QueryNotFound { data } => {
+            assert_eq!(error_meta(&data, M_QUERY_NAME).unwrap(), "missing");
+        }
+        other => panic!("Expected QueryNotFound, got: {other:?}"),
+    }
+}
+
+#[test]
+fn test_workflow_unresolved_mapping() {
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "insert_order": {
+            "query": "INSERT INTO orders (customer) VALUES (@customer)"
+        },
+        "broken": {
+            "workflow": [
+                {
+                    "query": "insert_order",
+                    "params": { "customer": { "$from": "/request/name" } }
+                }
+            ]
+        }
+    }))
+    .unwrap();
+    let mut conn = setup_db();
+
+    let err =
+        workflow_run_sqlite(&mut conn, &queries, "broken", &serde_json::json!({})).unwrap_err();
+    match err.downcast::<JankenError>().unwrap() {
+        JankenError::ParameterTypeMismatch { data } => {
+            assert_eq!(
+                error_meta(&data, M_EXPECTED).unwrap(),
+                "value at /request/name"
+            );
+            assert_eq!(
+                error_meta(&data, M_GOT).unwrap(),
+                "insert_order.customer: nothing found"
+            );
+        }
+        other => panic!("Expected ParameterTypeMismatch, got: {other:?}"),
+    }
+}
+
+#[test]
+fn test_workflow_definition_errors() {
+    let cases = [
+        (
+            serde_json::json!({ "w": { "workflow": [{ "query": "missing" }] } }),
+            "workflow step referencing an existing query",
+            "w: missing",
+        ),
+        (
+            serde_json::json!({
+                "q": { "query": "SELECT 1" },
+                "w": { "workflow": [{ "query": "q" }, { "query": "q" }] }
+            }),
+            "unique workflow step names",
+            "w: duplicate step 'q'",
+        ),
+        (
+            serde_json::json!({ "w": { "workflow": { "query": "q" } } }),
+            "array of steps for workflow",
+            "w: {\"query\":\"q\"}",
+        ),
+        (
+            serde_json::json!({ "w": { "workflow": [{ "name": "step" }] } }),
+            "workflow step with string 'query' field",
+            "w: {\"name\":\"step\"}",
+        ),
+        (
+            serde_json::json!({
+                "a": { "query": "SELECT 1", "transaction": { "behavior": "immediate" } },
+                "b": { "query": "SELECT 2", "transaction": { "behavior": "exclusive" } },
+                "w": { "workflow": [{ "query": "a" }, { "query": "b" }] }
+            }),
+            "workflow steps with the transaction options of the workflow",
+            "w: step 'b' (b)",
+        ),
+        (
+            serde_json::json!({
+                "a": { "query": "SELECT 1", "transaction": { "isolation": "serializable" } },
+                "w": {
+                    "workflow": [{ "name": "first", "query": "a" }],
+                    "transaction": { "isolation": "repeatable_read" }
+                }
+            }),
+            "workflow steps with the transaction options of the workflow",
+            "w: step 'first' (a)",
+        ),
+    ];
+
+    for (json, expected, got) in cases {
+        let err = QueryDefinitions::from_json(json).unwrap_err();
+        match err.downcast::<JankenError>().unwrap() {
+            JankenError::ParameterTypeMismatch { data } => {
+                assert_eq!(error_meta(&data, M_EXPECTED).unwrap(), expected);
+                assert_eq!(error_meta(&data, M_GOT).unwrap(), got);
+            }
+            other => panic!("Expected ParameterTypeMismatch, got: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_workflow_transaction_options() {
+    let path = std::env::temp_dir().join(format!(
+        "janken_workflow_transaction_{}.db",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+
+    let queries = QueryDefinitions::from_json(serde_json::json!({
+        "add_account": {
+            "query": "INSERT INTO accounts (id) VALUES (@id)",
+            "args": { "id": { "type": "integer" } },
+            "transaction": { "retry": { "max_attempts": 50, "backoff_ms": 5, "max_backoff_ms": 20 } }
+        },
+        "count_accounts": {
+            "query": "SELECT COUNT(*) AS total FROM accounts",
+            "returns": ["total"]
+        },
+        "add_and_count": {
+            "workflow": [{ "query": "add_account" }, { "query": "count_accounts" }]
+        },
+        "count_immediate": {
+            "workflow": [{ "query": "count_accounts" }],
+            "transaction": { "behavior": "immediate" }
+        }
+    }))
+    .unwrap();
+    // Without a transaction field, the workflow takes the options its steps declare
+    assert_eq!(
+        queries.workflows["add_and_count"].transaction,
+        queries.definitions["add_account"].transaction
+    );
+    assert_eq!(
+        queries.workflows["count_immediate"].transaction.behavior,
+        TransactionBehavior::Immediate
+    );
+
+    let mut conn = Connection::open(&path).unwrap();
+    conn.busy_timeout(Duration::ZERO).unwrap();
+    conn.execute("CREATE TABLE accounts (id INTEGER PRIMARY KEY)", [])
+        .unwrap();
+
+    let other = Connection::open(&path).unwrap();
+    other.execute_batch("BEGIN IMMEDIATE").unwrap();
+
+    // The workflow's immediate transaction asks for the write lock even for a read-only step
+    let err = workflow_run_sqlite(
+        &mut conn,
+        &queries,
+        "count_immediate",
+        &serde_json::json!({}),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.downcast::<rusqlite::Error>()
+            .unwrap()
+            .sqlite_error_code(),
+        Some(rusqlite::ErrorCode::DatabaseBusy)
+    );
+
+    // The step's retry policy re-runs the whole workflow until the lock is released
+    let releaser = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(100));
+        other.execute_batch("COMMIT").unwrap();
+    });
+    let result = workflow_run_sqlite(
+        &mut conn,
+        &queries,
+        "add_and_count",
+        &serde_json::json!({"id": 1}),
+    )
+    .unwrap();
+    releaser.join().unwrap();
+    assert!(result.attempts > 1);
+    assert_eq!(
+        result.step("count_accounts").unwrap().data,
+        vec![serde_json::json!({"total": 1})]
+    );
+
+    drop(conn);
+    let _ = std::fs::remove_file(&path);
+}